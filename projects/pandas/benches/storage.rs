@@ -0,0 +1,217 @@
+//! Timings of reading a csv file, saving it as a columnar file, converting,
+//! filtering and grouping its columns, run with `cargo bench -p pandas`. The
+//! number of rows can be passed as an argument. Reading and converting are
+//! also timed with the values stored as one `Option<Cell>` per row, the way
+//! `Series` stored them before typed columns.
+
+#[allow(dead_code, unused_imports)]
+#[path = "../src/pandas/mod.rs"]
+mod pandas;
+
+use std::{
+    env, fs,
+    hint::black_box,
+    io::{BufWriter, Write},
+    mem::size_of,
+    time::{Duration, Instant},
+};
+
+use pandas::{Cell, ColumnarOptions, CsvOptions, DType};
+
+const RUNS: usize = 5;
+
+/// writes a csv file with an int, a string, a float and a bool column
+fn write_sample(path: &str, rows: usize) {
+    let mut file = BufWriter::new(fs::File::create(path).unwrap());
+    writeln!(file, "id,name,score,active").unwrap();
+    for row in 0..rows {
+        let score = (row * 7919 % 1000) as f64 / 10.0;
+        writeln!(file, "{row},student_{},{score},{}", row % 997, row % 3 == 0).unwrap();
+    }
+}
+
+/// fastest of `RUNS` runs of `f`
+fn best<T>(mut f: impl FnMut() -> T) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+/// columns that store one `Option<Cell>` per row
+type Cells = Vec<(String, Vec<Option<Cell>>)>;
+
+/// Converts text into a cell of type `d_type`, as `Cell::cast` did for
+/// `Str` cells.
+fn parse_cell(v: &str, d_type: DType) -> Option<Cell> {
+    Some(match d_type {
+        DType::Bool => Cell::Bool(match v {
+            "0" => false,
+            "1" => true,
+            v => v.parse::<bool>().ok()?,
+        }),
+        DType::Int => Cell::Int(v.parse::<i64>().ok()?),
+        DType::Float => Cell::Float(v.parse::<f64>().ok()?),
+        _ => Cell::Str(v.to_string()),
+    })
+}
+
+/// Reads the sample file into cells, with the type of every column being the
+/// first of `Int`, `Float` and `Bool` that parses all of its values. The
+/// sample has no quoted fields, so lines are split on every comma.
+fn read_cells(path: &str, infer: bool) -> Cells {
+    let text = fs::read_to_string(path).unwrap();
+    let mut lines = text.lines();
+    let mut columns = lines
+        .next()
+        .unwrap()
+        .split(',')
+        .map(|name| (name.to_string(), vec![]))
+        .collect::<Cells>();
+    for line in lines {
+        columns
+            .iter_mut()
+            .zip(line.split(','))
+            .for_each(|((_, cells), v)| cells.push(Some(Cell::Str(v.to_string()))));
+    }
+    if infer {
+        for (_, cells) in &mut columns {
+            if let Some(converted) = [DType::Int, DType::Float, DType::Bool]
+                .into_iter()
+                .find_map(|d_type| cast_cells(cells, d_type))
+            {
+                *cells = converted;
+            }
+        }
+    }
+    columns
+}
+
+/// the cells converted into `d_type`, `None` if any of them can not be
+fn cast_cells(cells: &[Option<Cell>], d_type: DType) -> Option<Vec<Option<Cell>>> {
+    cells
+        .iter()
+        .map(|cell| match cell {
+            Some(Cell::Str(v)) => parse_cell(v, d_type).map(Some),
+            cell => Some(cell.clone()),
+        })
+        .collect()
+}
+
+/// bytes the values take as one `Option<Cell>` per row
+fn cell_memory_usage(columns: &Cells) -> usize {
+    let text = columns
+        .iter()
+        .flat_map(|(_, cells)| cells)
+        .map(|cell| match cell {
+            Some(Cell::Str(v)) => v.capacity(),
+            _ => 0,
+        })
+        .sum::<usize>();
+    let cells = columns.iter().map(|(_, cells)| cells.len()).sum::<usize>();
+    cells * size_of::<Option<Cell>>() + text
+}
+
+fn main() {
+    let rows = env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse::<usize>().ok())
+        .unwrap_or(200_000);
+    let path = env::temp_dir().join("pandas_storage_bench.csv");
+    let path = path.to_str().unwrap();
+    write_sample(path, rows);
+
+    let untyped = CsvOptions::new().infer_types(false);
+    let read_str = best(|| pandas::read_csv_with(path, &untyped).unwrap());
+    let read_typed = best(|| pandas::read_csv_with(path, &CsvOptions::new()).unwrap());
+    pandas::set_threads(1);
+    let read_serial = best(|| pandas::read_csv_with(path, &CsvOptions::new()).unwrap());
+    pandas::set_threads(0);
+    let read_cells_typed = best(|| read_cells(path, true));
+    let columnar = env::temp_dir().join("pandas_storage_bench.pdc");
+    let columnar = columnar.to_str().unwrap();
+    let typed = pandas::read_csv_with(path, &CsvOptions::new()).unwrap();
+    let write_columnar = best(|| typed.to_columnar(columnar).unwrap());
+    let read_columnar =
+        best(|| pandas::read_columnar_with(columnar, &ColumnarOptions::new()).unwrap());
+    fs::remove_file(columnar).unwrap();
+    let df = pandas::read_csv_with(path, &untyped).unwrap();
+    let as_type = best(|| {
+        let mut df = df.clone();
+        df.as_type([
+            (String::from("id"), DType::Int),
+            (String::from("score"), DType::Float),
+            (String::from("active"), DType::Bool),
+        ])
+        .unwrap();
+        df
+    });
+    let cells = read_cells(path, false);
+    let as_type_cells = best(|| {
+        let mut cells = cells.clone();
+        for (name, d_type) in [
+            ("id", DType::Int),
+            ("score", DType::Float),
+            ("active", DType::Bool),
+        ] {
+            let column = cells.iter_mut().find(|(n, _)| n == name).unwrap();
+            column.1 = cast_cells(&column.1, d_type).unwrap();
+        }
+        cells
+    });
+    let eager = best(|| {
+        pandas::read_csv_with(path, &CsvOptions::new())
+            .and_then(|df| df.query("score > 50"))
+            .and_then(|df| df.select(["name"]))
+            .unwrap()
+    });
+    // only the name and score columns are converted
+    let lazy = best(|| {
+        pandas::scan_csv(path, &CsvOptions::new())
+            .filter("score > 50")
+            .unwrap()
+            .select(["name"])
+            .collect()
+            .unwrap()
+    });
+    let cell_memory = cell_memory_usage(&read_cells(path, true));
+    fs::remove_file(path).unwrap();
+    let mut categorical = typed.clone();
+    categorical
+        .as_type([(String::from("name"), DType::Category)])
+        .unwrap();
+    let group_str = best(|| typed.group_by(["name"]).groups().unwrap());
+    let group_category = best(|| categorical.group_by(["name"]).groups().unwrap());
+
+    println!("rows: {rows}, best of {RUNS} runs");
+    println!("{:<28}{:>10.1?}", "read_csv (no inference)", read_str);
+    println!("{:<28}{:>10.1?}", "read_csv (inferred types)", read_typed);
+    println!("{:<28}{:>10.1?}", "read_csv (one thread)", read_serial);
+    println!("{:<28}{:>10.1?}", "read_csv (as cells)", read_cells_typed);
+    println!("{:<28}{:>10.1?}", "to_columnar", write_columnar);
+    println!("{:<28}{:>10.1?}", "read_columnar", read_columnar);
+    println!("{:<28}{:>10.1?}", "as_type (3 columns)", as_type);
+    println!("{:<28}{:>10.1?}", "as_type (as cells)", as_type_cells);
+    println!("{:<28}{:>10.1?}", "filter and select (eager)", eager);
+    println!("{:<28}{:>10.1?}", "filter and select (lazy)", lazy);
+    println!(
+        "{:<28}{:>7} KiB (as cells: {} KiB)",
+        "memory",
+        typed.memory_usage() / 1024,
+        cell_memory / 1024
+    );
+    println!(
+        "{:<28}{:>7} KiB",
+        "memory (name as Category)",
+        categorical.memory_usage() / 1024
+    );
+    println!("{:<28}{:>10.1?}", "group_by name (Str)", group_str);
+    println!(
+        "{:<28}{:>10.1?}",
+        "group_by name (Category)", group_category
+    );
+}
@@ -1,5 +1,9 @@
+#![allow(dead_code)]
+
 mod pandas;
 
+use std::{fs::File, io::BufReader};
+
 use pandas as pd;
 
 use crate::pd::DType;
@@ -11,29 +15,29 @@ fn main() {
     let df1 = pd::DataFrame::new([
         (
             String::from("First Name"),
-            vec!["John", "Jane", "Satoshi", "Adam"]
+            ["John", "Jane", "Satoshi", "Adam"]
                 .iter()
                 .map(|v| v.to_string())
                 .collect(),
         ),
         (
             String::from("Last Name"),
-            vec!["Doe", "Deo", "Nakamoto", "Av"]
+            ["Doe", "Deo", "Nakamoto", "Av"]
                 .iter()
                 .map(|v| v.to_string())
                 .collect(),
         ),
     ]);
 
-    df1.describe();
-    df1.head(5);
+    df1.info();
+    println!("{}", df1.head(5));
     println!("Shape of the dataframe 1: {:?}", df1.shape());
 
-    // reading from a csv file
+    // reading from a csv file, column types are inferred from the values
     println!("\n\n");
     let mut df2 = pd::read_csv("projects/pandas/sample/students.csv", true);
-    df2.head(5);
-    df2.describe();
+    println!("{}", df2.head(5));
+    df2.info();
     println!("Shape of the dataframe 2: {:?}", df2.shape());
 
     // Data Processing
@@ -42,7 +46,284 @@ fn main() {
         (String::from("Age"), DType::Int),
         (String::from("CGPA"), DType::Float),
         (String::from("Graduated"), DType::Bool),
+    ])
+    .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", df2.head(5));
+    let options = pd::DisplayOptions::new().precision(2);
+    println!("{}", df2.describe().to_string_with(&options));
+
+    // selecting columns and filtering rows
+    let graduated = df2
+        .select(["First Name", "CGPA", "Graduated"])
+        .unwrap_or_else(|err| panic!("{err}"))
+        .filter_rows(|row| matches!(row[2], Some(pd::Cell::Bool(true))));
+    println!("{}", graduated.head(5));
+
+    // vectorised arithmetic and comparisons
+    let percentage = &df2["CGPA".to_string()] * 25;
+    println!("CGPA as a percentage:\n{percentage}");
+    let cgpa = &df2["CGPA".to_string()];
+    let trend = cgpa.rolling(2).mean().unwrap_or_else(|err| panic!("{err}"));
+    let best = cgpa.expanding().max().unwrap_or_else(|err| panic!("{err}"));
+    let options = pd::DisplayOptions::new().precision(3);
+    println!(
+        "Rolling mean of the CGPA:\n{}",
+        trend.to_string_with(&options)
+    );
+    println!("Best CGPA so far: {:?}", best.iter().collect::<Vec<_>>());
+    let adults = df2
+        .filter(
+            &df2["Age".to_string()]
+                .ge(21)
+                .unwrap_or_else(|err| panic!("{err}")),
+        )
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", adults.head(5));
+
+    // filtering rows with a query expression
+    let graduates = df2
+        .query("Age > 19 and Graduated == true")
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{graduates}");
+    let query = "`Last Name` in ('Doe') and CGPA >";
+    if let Err(err) = df2.query(query) {
+        println!("{err}");
+    }
+
+    // custom transformations with closures
+    let initials = df2.apply_rows(|row| match (&row[1], &row[2]) {
+        (Some(first), Some(last)) => {
+            let initial = |name: &pd::Cell| name.to_string().chars().next().unwrap_or(' ');
+            Some(pd::Cell::Str(format!(
+                "{}. {}.",
+                initial(first),
+                initial(last)
+            )))
+        }
+        _ => None,
+    });
+    let with_initials = df2
+        .clone()
+        .with_column("Initials", initials)
+        .unwrap_or_else(|err| panic!("{err}"))
+        .with_column("Percentage", percentage)
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", with_initials.head(5));
+
+    // handling missing values
+    let complete = df2
+        .fill_null_with(
+            ["Last Name"],
+            pd::Fill::Value(pd::Cell::Str("Unknown".into())),
+        )
+        .unwrap_or_else(|err| panic!("{err}"))
+        .drop_nulls(pd::How::Any);
+    println!("{}", complete.head(5));
+
+    // repeated values stored as categories
+    let mut last_names = df2["Last Name".to_string()].clone();
+    last_names
+        .as_type(DType::Category)
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!(
+        "Categories of the last names: {:?}",
+        last_names
+            .categories()
+            .unwrap_or_else(|err| panic!("{err}"))
+    );
+    println!("{}", last_names.value_counts().head(5));
+
+    // repeated rows and values
+    let families = df2
+        .drop_duplicates_with(pd::Keep::Last, Some(["Last Name"]))
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", families);
+    println!(
+        "Distinct last names: {}, first names: {:?}",
+        last_names.n_unique(),
+        df2["First Name".to_string()]
+            .unique()
+            .iter()
+            .flatten()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>()
+    );
+    println!("{}", last_names.value_counts_with(true).head(3));
+
+    // sorting rows, missing values first
+    let ranked = df2
+        .sort_values_with(["Graduated", "CGPA"], &[true, false], pd::NaPosition::First)
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", ranked.head(2));
+    println!("{}", ranked.tail(2));
+
+    // aggregating groups of rows
+    let report = df2
+        .group_by(["Graduated"])
+        .agg([
+            ("CGPA", pd::Agg::Mean),
+            ("Age", pd::Agg::Max),
+            ("SN", pd::Agg::Count),
+        ])
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", report.head(5));
+
+    // reshaping between long and wide form
+    let long = df2
+        .melt(["First Name"], ["Age", "CGPA"])
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", long.head(8));
+    let wide = long
+        .pivot_table(["First Name"], "variable", "value", pd::Agg::First)
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", wide.head(5));
+    let stacked = wide
+        .set_index("First Name")
+        .and_then(|wide| wide.stack())
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", stacked.head(4));
+    println!(
+        "{}",
+        stacked
+            .unstack()
+            .unwrap_or_else(|err| panic!("{err}"))
+            .head(2)
+    );
+
+    // exporting data frames
+    println!("{}", report.to_markdown());
+    println!("{}", report.to_json(pd::Orient::Records));
+
+    // combining data frames
+    let scholarships = pd::DataFrame::new([
+        (
+            String::from("First Name"),
+            ["Jane", "Adam"].iter().map(|v| v.to_string()).collect(),
+        ),
+        (
+            String::from("Amount"),
+            ["500", "750"].iter().map(|v| v.to_string()).collect(),
+        ),
     ]);
-    df2.head(5);
-    df2.describe();
+    let merged = df2
+        .merge(&scholarships, ["First Name"], pd::Join::Left)
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", merged.head(5));
+    let combined = pd::concat(&[df1, merged], pd::Axis::Rows).unwrap_or_else(|err| panic!("{err}"));
+    println!("Shape of the combined dataframe: {:?}", combined.shape());
+
+    // looking rows up by label and matching the rows of two data frames by label
+    let by_name = df2
+        .set_index("First Name")
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!(
+        "{}",
+        by_name.loc("Jane").unwrap_or_else(|err| panic!("{err}"))
+    );
+    let by_sn = df2.set_index("SN").unwrap_or_else(|err| panic!("{err}"));
+    println!(
+        "{}",
+        by_sn.loc_range(2..=3).unwrap_or_else(|err| panic!("{err}"))
+    );
+    let mut bonus = scholarships
+        .set_index("First Name")
+        .unwrap_or_else(|err| panic!("{err}"));
+    bonus
+        .as_type([(String::from("Amount"), DType::Int)])
+        .unwrap_or_else(|err| panic!("{err}"));
+    // arithmetic pairs up the values with the same label
+    println!(
+        "{}",
+        &bonus["Amount".to_string()] / &by_name["CGPA".to_string()]
+    );
+    let (students, bonus) = by_name
+        .align(&bonus, pd::Join::Left)
+        .unwrap_or_else(|err| panic!("{err}"));
+    let awarded = students
+        .with_column("Amount", bonus["Amount".to_string()].clone())
+        .unwrap_or_else(|err| panic!("{err}"))
+        .reset_index(false)
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", awarded.head(5));
+
+    // saving to a columnar file and loading some of its columns back
+    let path = std::env::temp_dir().join("students.pdc");
+    let path = path.to_string_lossy();
+    df2.to_columnar(&path).unwrap_or_else(|err| panic!("{err}"));
+    let options = pd::ColumnarOptions::new().columns(["First Name", "CGPA"]);
+    let saved = pd::read_columnar_with(&path, &options).unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", saved.head(3));
+
+    // reading a csv file with custom options
+    println!("\n\n");
+    let options = pd::CsvOptions::new().delimiter(';').comment('#');
+    let mut df3 = pd::read_csv_with("projects/pandas/sample/employees.csv", &options)
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", df3.head(5));
+    println!("Shape of the dataframe 3: {:?}", df3.shape());
+
+    // reading a csv file in chunks of rows
+    let file =
+        File::open("projects/pandas/sample/students.csv").unwrap_or_else(|err| panic!("{err}"));
+    let mut rows = 0;
+    for chunk in pd::read_csv_chunked(BufReader::new(file), &pd::CsvOptions::new(), 2) {
+        rows += chunk.unwrap_or_else(|err| panic!("{err}")).shape().0;
+    }
+    println!("Rows read in chunks of 2: {rows}");
+
+    // building a lazy plan that only reads the columns it uses
+    let plan = pd::scan_csv(
+        "projects/pandas/sample/students.csv",
+        &pd::CsvOptions::new(),
+    )
+    .filter("Age >= 20")
+    .and_then(|plan| plan.filter("Graduated == true"))
+    .unwrap_or_else(|err| panic!("{err}"))
+    .group_by(["Graduated"])
+    .agg([("CGPA", pd::Agg::Mean)]);
+    println!("{}", plan.explain());
+    println!("{}", plan.collect().unwrap_or_else(|err| panic!("{err}")));
+
+    // parsing csv files and converting columns on one or more threads
+    pd::set_threads(1);
+    let serial = pd::read_csv("projects/pandas/sample/students.csv", true);
+    pd::set_threads(4);
+    let parallel = pd::read_csv("projects/pandas/sample/students.csv", true);
+    println!(
+        "Same data frame on one and four threads: {}",
+        serial.to_string() == parallel.to_string()
+    );
+    pd::set_threads(0);
+
+    // parsing dates and grouping rows by month
+    let mut sales = pd::read_csv("projects/pandas/sample/sales.csv", true);
+    sales
+        .parse_dates(["Date"], DType::Date, "%d/%m/%Y", pd::OnError::Raise)
+        .unwrap_or_else(|err| panic!("{err}"));
+    let dates = &sales["Date".to_string()];
+    println!(
+        "Months of the sales: {:?}",
+        dates
+            .month()
+            .unwrap_or_else(|err| panic!("{err}"))
+            .iter()
+            .collect::<Vec<_>>()
+    );
+    let due = dates + pd::TimeDelta::days(30);
+    let due = due.iter().flatten().map(|date| date.to_string());
+    println!("Payments due: {:?}", due.collect::<Vec<_>>());
+    let monthly = sales
+        .resample("Date", pd::Freq::Month)
+        .agg([("Amount", pd::Agg::Sum), ("Amount", pd::Agg::Count)])
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", monthly.head(5));
+
+    // values that can not be converted are either reported or set to None
+    let age = [(String::from("Age"), DType::Int)];
+    if let Err(err) = df3.as_type(age.clone()) {
+        println!("{err}");
+    }
+    df3.as_type_with(age, pd::OnError::Coerce)
+        .unwrap_or_else(|err| panic!("{err}"));
+    println!("{}", df3.head(5));
 }
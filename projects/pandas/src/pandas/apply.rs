@@ -0,0 +1,201 @@
+use super::{
+    error::{PandasError, Result},
+    Cell, DType, DataFrame, Header, Series,
+};
+
+impl Series {
+    /// Returns a new series with `f` applied to every value, missing values
+    /// included. The type of the result is the common type of the returned
+    /// values, see `map_with` to choose it.
+    ///
+    /// ```ignore
+    /// let initials = df["Name".to_string()].map(|name| {
+    ///     name.and_then(|n| n.to_string().chars().next().map(|c| Cell::Str(c.into())))
+    /// });
+    /// ```
+    pub(crate) fn map<F>(&self, f: F) -> Series
+    where
+        F: FnMut(Option<Cell>) -> Option<Cell>,
+    {
+        self.iter().map(f).collect()
+    }
+
+    /// Returns a new series of type `d_type` with `f` applied to every value.
+    /// Returned values of another type are converted and become `None` if
+    /// they can not be.
+    pub(crate) fn map_with<F>(&self, d_type: DType, f: F) -> Series
+    where
+        F: FnMut(Option<Cell>) -> Option<Cell>,
+    {
+        Series::from_cells(d_type, self.iter().map(f))
+    }
+}
+
+impl DataFrame {
+    /// Returns a new series with the result of `f` for every row, see
+    /// `apply_rows_with`. The type of the result is the common type of the
+    /// returned values.
+    ///
+    /// ```ignore
+    /// let total = df.apply_rows(|row| match (&row[1], &row[2]) {
+    ///     (Some(Cell::Int(a)), Some(Cell::Int(b))) => Some(Cell::Int(a + b)),
+    ///     _ => None,
+    /// });
+    /// let df = df.with_column("Total", total)?;
+    /// ```
+    pub(crate) fn apply_rows<F>(&self, mut f: F) -> Series
+    where
+        F: FnMut(&[Option<Cell>]) -> Option<Cell>,
+    {
+        (0..self.shape().0)
+            .map(|idx| f(&self.get_item_at(idx)))
+            .collect()
+    }
+
+    /// Returns a new series of type `d_type` with the result of `f` for every
+    /// row. `f` receives each row as returned by `get_item_at`, in `headers`
+    /// order, returned values of another type are converted and become
+    /// `None` if they can not be.
+    pub(crate) fn apply_rows_with<F>(&self, d_type: DType, mut f: F) -> Series
+    where
+        F: FnMut(&[Option<Cell>]) -> Option<Cell>,
+    {
+        Series::from_cells(
+            d_type,
+            (0..self.shape().0).map(|idx| f(&self.get_item_at(idx))),
+        )
+    }
+
+    /// Returns a new data frame with every column replaced by the result of
+    /// `f`, which receives the name and the values of the column and must
+    /// return a series of the same length.
+    ///
+    /// ```ignore
+    /// let df = df.apply(|_, series| series.fill_null(Fill::Forward))?;
+    /// ```
+    pub(crate) fn apply<F>(&self, mut f: F) -> Result<DataFrame>
+    where
+        F: FnMut(&str, &Series) -> Result<Series>,
+    {
+        let mut df = self.clone();
+        for header in &self.headers {
+            let series = f(&header.name, &self.data[&header.name])
+                .map_err(|err| err.in_column(&header.name))?;
+            df = df.with_column(&header.name, series)?;
+        }
+        Ok(df)
+    }
+
+    /// Adds `series` as the column `name`, or replaces the column if it
+    /// exists, keeping its position. The series must have one value per row
+    /// unless the data frame has no columns.
+    ///
+    /// ```ignore
+    /// let df = df.with_column("Percentage", &df["CGPA".to_string()] * 25)?;
+    /// ```
+    pub(crate) fn with_column(mut self, name: &str, series: Series) -> Result<DataFrame> {
+        if !self.headers.is_empty() && series.len() != self.shape().0 {
+            return Err(PandasError::LengthMismatch {
+                expected: self.shape().0,
+                found: series.len(),
+            });
+        }
+        match self.headers.iter_mut().find(|h| h.name == name) {
+            Some(header) => header.d_type = series.d_type(),
+            None => self.headers.push(Header {
+                name: name.to_string(),
+                d_type: series.d_type(),
+            }),
+        }
+        self.data.insert(name.to_string(), series);
+        self.share_labels();
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::fixtures::read;
+
+    fn gaps() -> DataFrame {
+        let source = "Name,Age,CGPA\n\
+                      John,20,3.5\n\
+                      Jane,,3.8\n\
+                      Adam,22,\n";
+        read(source)
+    }
+
+    fn column(df: &DataFrame, name: &str) -> Vec<Option<Cell>> {
+        df.column(name).unwrap().iter().collect()
+    }
+
+    #[test]
+    fn test_map() {
+        let df = gaps();
+        let older = df.column("Age").unwrap().map(|age| match age {
+            Some(Cell::Int(age)) => Some(Cell::Int(age + 1)),
+            _ => Some(Cell::Int(0)),
+        });
+        assert_eq!(older.d_type(), DType::Int);
+        assert_eq!(
+            older.iter().collect::<Vec<_>>(),
+            [21, 0, 23].map(|v| Some(Cell::Int(v)))
+        );
+
+        let lengths = df.column("Name").unwrap().map_with(DType::Float, |name| {
+            name.map(|n| Cell::Int(n.to_string().len() as i64))
+        });
+        assert_eq!(lengths.d_type(), DType::Float);
+        assert_eq!(lengths.get(2), Some(Cell::Float(4.0)));
+    }
+
+    #[test]
+    fn test_apply_rows_and_with_column() {
+        let df = gaps();
+        let summary = df.apply_rows(|row| match (&row[0], &row[1]) {
+            (Some(name), Some(age)) => Some(Cell::Str(format!("{name} ({age})"))),
+            _ => None,
+        });
+        let df = df.with_column("Summary", summary).unwrap();
+        assert_eq!(df.shape(), (3, 4));
+        assert_eq!(df.headers[3].d_type, DType::Str);
+        assert_eq!(
+            column(&df, "Summary")[0],
+            Some(Cell::Str("John (20)".into()))
+        );
+        assert_eq!(column(&df, "Summary")[1], None);
+
+        // replacing a column keeps its position and updates its type
+        let flags = df.apply_rows_with(DType::Bool, |row| row[2].clone());
+        let df = df.with_column("CGPA", flags).unwrap();
+        let names: Vec<String> = df.headers.iter().map(|h| h.name.clone()).collect();
+        assert_eq!(names, ["Name", "Age", "CGPA", "Summary"]);
+        assert_eq!(df.header("CGPA").unwrap().d_type, DType::Bool);
+
+        assert!(matches!(
+            df.with_column("Short", Series::new(vec!["x".into()])),
+            Err(PandasError::LengthMismatch {
+                expected: 3,
+                found: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_apply() {
+        let df = gaps()
+            .apply(|name, series| match name {
+                "Name" => Ok(series.clone()),
+                _ => series.interpolate(),
+            })
+            .unwrap();
+        assert_eq!(df.header("Age").unwrap().d_type, DType::Float);
+        assert_eq!(column(&df, "Age")[1], Some(Cell::Float(21.0)));
+        assert!(matches!(
+            gaps().apply(|_, series| series.interpolate()),
+            Err(PandasError::DTypeMismatch { .. })
+        ));
+        assert!(gaps().apply(|_, series| Ok(series.take(&[0]))).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use super::{
+    error::{PandasError, Result},
+    storage::{Column, Dictionary},
+    DType, Series,
+};
+
+impl Series {
+    /// the dictionary of a `Category` series
+    fn dictionary(&self) -> Result<&Dictionary> {
+        match &self.values {
+            Column::Category { dictionary, .. } => Ok(dictionary),
+            column => Err(PandasError::DTypeMismatch {
+                expected: DType::Category,
+                found: column.d_type(),
+            }),
+        }
+    }
+
+    /// the categories of a `Category` series, in category order
+    pub(crate) fn categories(&self) -> Result<Vec<String>> {
+        let dictionary = self.dictionary()?;
+        Ok(dictionary.iter().map(String::from).collect())
+    }
+
+    /// encodes the values of a `Category` series with another dictionary,
+    /// values that are not in it become `None`
+    fn with_dictionary(&self, dictionary: Dictionary) -> Series {
+        let Column::Category {
+            codes,
+            dictionary: current,
+        } = &self.values
+        else {
+            panic!("{:?} series has no categories", self.d_type());
+        };
+        let recoded = current
+            .iter()
+            .map(|value| dictionary.code(value))
+            .collect::<Vec<Option<u32>>>();
+        let mut validity = self.validity.clone();
+        let codes = codes
+            .iter()
+            .enumerate()
+            .map(
+                |(row, &code)| match validity.get(row).then(|| recoded[code as usize]).flatten() {
+                    Some(code) => code,
+                    None => {
+                        validity.set(row, false);
+                        0
+                    }
+                },
+            )
+            .collect();
+        Series {
+            values: Column::Category { codes, dictionary },
+            validity,
+            labels: self.labels.clone(),
+            cells: OnceLock::new(),
+        }
+    }
+
+    /// the series with its categories in lexicographic order
+    pub(crate) fn sort_categories(&self) -> Series {
+        let Ok(dictionary) = self.dictionary() else {
+            return self.clone();
+        };
+        let mut values = dictionary.iter().collect::<Vec<&str>>();
+        values.sort_unstable();
+        self.with_dictionary(values.into_iter().collect())
+    }
+
+    /// Changes the order of the categories, which is the order in which
+    /// `sort_values` places the values. `order` must list every category
+    /// exactly once.
+    ///
+    /// ```ignore
+    /// let sizes = df["Size".to_string()].reorder_categories(["S", "M", "L"])?;
+    /// ```
+    pub(crate) fn reorder_categories<T, S>(&self, order: T) -> Result<Series>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let dictionary = self.dictionary()?;
+        let order = order
+            .into_iter()
+            .map(|value| value.as_ref().to_string())
+            .collect::<Vec<String>>();
+        let reordered = order.iter().collect::<Dictionary>();
+        if reordered.len() != order.len()
+            || reordered.len() != dictionary.len()
+            || dictionary
+                .iter()
+                .any(|value| reordered.code(value).is_none())
+        {
+            return Err(PandasError::Categories {
+                expected: self.categories()?,
+                found: order,
+            });
+        }
+        Ok(self.with_dictionary(reordered))
+    }
+
+    /// Replaces the categories with `categories`, in that order. Values that
+    /// are not one of the new categories become `None`, repeated categories
+    /// are ignored.
+    pub(crate) fn set_categories<T, S>(&self, categories: T) -> Result<Series>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.dictionary()?;
+        Ok(self.with_dictionary(categories.into_iter().collect()))
+    }
+
+    /// Dictionary encodes the values, returning the code of every row and
+    /// the number of codes. Missing values have the code `0`, the categories
+    /// of a `Category` series keep their order and other values are numbered
+    /// in order of first appearance.
+    pub(crate) fn encode(&self) -> (Vec<usize>, usize) {
+        let len = self.len();
+        let valid = |row: usize| self.is_valid(row);
+        match &self.values {
+            Column::Category { codes, dictionary } => (
+                (0..len)
+                    .map(|row| match valid(row) {
+                        true => codes[row] as usize + 1,
+                        false => 0,
+                    })
+                    .collect(),
+                dictionary.len() + 1,
+            ),
+            // text is looked up without copying it
+            Column::Str { .. } => {
+                let mut positions: HashMap<&str, usize> = HashMap::new();
+                let codes = (0..len)
+                    .map(|row| match self.str_at(row) {
+                        Some(value) => {
+                            let next = positions.len() + 1;
+                            *positions.entry(value).or_insert(next)
+                        }
+                        None => 0,
+                    })
+                    .collect();
+                (codes, positions.len() + 1)
+            }
+            _ => {
+                let mut positions = HashMap::new();
+                let codes = (0..len)
+                    .map(|row| match valid(row) {
+                        true => {
+                            let next = positions.len() + 1;
+                            *positions.entry(self.hash_key(row)).or_insert(next)
+                        }
+                        false => 0,
+                    })
+                    .collect();
+                (codes, positions.len() + 1)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::{fixtures::read, Cell};
+
+    fn names() -> Series {
+        let source = "Name,Age\nDoe,20\nNakamoto,21\n,19\nDoe,22\nAv,20\nDoe,23\nAv,21\n";
+        let mut df = read(source);
+        df.as_type([(String::from("Name"), DType::Category)])
+            .unwrap();
+        df.column("Name").unwrap().clone()
+    }
+
+    fn cells(series: &Series) -> Vec<Option<Cell>> {
+        series.iter().collect()
+    }
+
+    #[test]
+    fn test_as_category() {
+        let names = names();
+        assert_eq!(names.d_type(), DType::Category);
+        assert_eq!(names.categories().unwrap(), ["Av", "Doe", "Nakamoto"]);
+        assert_eq!(names.get(0), Some(Cell::Category("Doe".into())));
+        assert_eq!(names.get(2), None);
+        assert_eq!(names.str_at(1), Some("Nakamoto"));
+
+        let mut text = names.clone();
+        text.as_type(DType::Str).unwrap();
+        assert_eq!(text.get(0), Some(Cell::Str("Doe".into())));
+        assert_eq!(text.hash_key(0), names.hash_key(0));
+        assert!(Series::new(vec!["x".into()]).categories().is_err());
+    }
+
+    #[test]
+    fn test_reorder_categories() {
+        let names = names();
+        let reordered = names.reorder_categories(["Nakamoto", "Doe", "Av"]).unwrap();
+        assert_eq!(cells(&reordered), cells(&names));
+        assert_eq!(reordered.categories().unwrap(), ["Nakamoto", "Doe", "Av"]);
+        assert!(matches!(
+            names.reorder_categories(["Doe", "Av"]),
+            Err(PandasError::Categories { .. })
+        ));
+        assert!(names
+            .reorder_categories(["Doe", "Av", "Av", "Nakamoto"])
+            .is_err());
+
+        let set = names.set_categories(["Doe", "Smith"]).unwrap();
+        assert_eq!(set.categories().unwrap(), ["Doe", "Smith"]);
+        assert_eq!(set.null_count(), 4);
+        assert_eq!(set.get(3), Some(Cell::Category("Doe".into())));
+    }
+
+    #[test]
+    fn test_value_counts() {
+        let counts = names().set_categories(["Av", "Doe", "Nakamoto", "Smith"]);
+        let counts = counts.unwrap().value_counts();
+        assert_eq!(counts.shape(), (4, 2));
+        assert_eq!(
+            cells(counts.column("value").unwrap()),
+            ["Doe", "Av", "Nakamoto", "Smith"].map(|v| Some(Cell::Category(v.into())))
+        );
+        assert_eq!(
+            cells(counts.column("count").unwrap()),
+            [3, 2, 1, 0].map(|v| Some(Cell::Int(v)))
+        );
+
+        let ages = [
+            Some(Cell::Int(20)),
+            None,
+            Some(Cell::Int(21)),
+            Some(Cell::Int(21)),
+        ];
+        let counts = ages.into_iter().collect::<Series>().value_counts();
+        assert_eq!(
+            cells(counts.column("value").unwrap()),
+            [21, 20].map(|v| Some(Cell::Int(v)))
+        );
+    }
+}
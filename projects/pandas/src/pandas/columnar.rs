@@ -0,0 +1,933 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    sync::{Arc, OnceLock},
+};
+
+use super::{
+    error::{PandasError, Result},
+    parallel,
+    storage::{Bitmap, Column, Dictionary},
+    DType, DataFrame, Header, Labels, Series,
+};
+
+/// the first bytes of every columnar file
+const MAGIC: &[u8; 4] = b"PDCF";
+const VERSION: u8 = 1;
+/// Number of rows allocated up front when decoding a column, the row count
+/// of the file is not trusted with more before its chunks are read.
+const PREALLOCATED_ROWS: usize = 65_536;
+
+/// Options that control how a `DataFrame` is written as a columnar file and
+/// which of its columns are loaded back.
+///
+/// A columnar file starts with a schema that holds the name and `DType` of
+/// every column, the number of rows, the index and the name and `DType` of the
+/// labels set with `set_index`. The values of each column, and of the labels,
+/// follow in chunks of `chunk_rows` rows with a bitmap of their missing values.
+/// Every section carries a CRC-32 checksum that is verified while reading.
+///
+/// ```ignore
+/// df.to_columnar_with("students.pdc", &ColumnarOptions::new().chunk_rows(10_000))?;
+/// let options = ColumnarOptions::new().columns(["Age", "CGPA"]);
+/// let df = pd::read_columnar_with("students.pdc", &options)?;
+/// ```
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnarOptions {
+    pub(crate) chunk_rows: usize,
+    pub(crate) encode: bool,
+    pub(crate) columns: Option<Vec<String>>,
+}
+
+impl Default for ColumnarOptions {
+    fn default() -> Self {
+        Self {
+            chunk_rows: 65_536,
+            encode: true,
+            columns: None,
+        }
+    }
+}
+
+impl ColumnarOptions {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// number of rows of a column that are stored and checked together, at
+    /// most `u32::MAX`
+    pub(crate) fn chunk_rows(mut self, rows: usize) -> Self {
+        self.chunk_rows = rows.clamp(1, u32::MAX as usize);
+        self
+    }
+
+    /// Store every chunk with the smallest of the plain, run-length and, for
+    /// `Str` columns, dictionary encodings. When disabled values are always
+    /// written as they are.
+    pub(crate) fn encode(mut self, encode: bool) -> Self {
+        self.encode = encode;
+        self
+    }
+
+    /// Only load the listed columns, in the order of the file. The chunks of
+    /// the other columns are skipped without being read.
+    pub(crate) fn columns<T, S>(mut self, columns: T) -> Self
+    where
+        T: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.columns = Some(columns.into_iter().map(|c| c.into()).collect());
+        self
+    }
+}
+
+/// how the values of a chunk are stored
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    /// every value back to back, booleans as bits
+    Plain,
+    /// runs of repeated values as a length and the value
+    RunLength,
+    /// the distinct values of a `Str` chunk followed by the code of every value
+    Dictionary,
+}
+
+impl Encoding {
+    fn code(self) -> u8 {
+        match self {
+            Encoding::Plain => 0,
+            Encoding::RunLength => 1,
+            Encoding::Dictionary => 2,
+        }
+    }
+
+    fn from_code(code: u8) -> Result<Self> {
+        match code {
+            0 => Ok(Encoding::Plain),
+            1 => Ok(Encoding::RunLength),
+            2 => Ok(Encoding::Dictionary),
+            code => Err(error(format!("unknown encoding {code}"))),
+        }
+    }
+}
+
+fn dtype_code(d_type: DType) -> u8 {
+    match d_type {
+        DType::Bool => 0,
+        DType::Int => 1,
+        DType::Float => 2,
+        DType::Str => 3,
+        DType::Date => 4,
+        DType::DateTime => 5,
+        DType::Category => 6,
+    }
+}
+
+fn dtype_from_code(code: u8) -> Result<DType> {
+    Ok(match code {
+        0 => DType::Bool,
+        1 => DType::Int,
+        2 => DType::Float,
+        3 => DType::Str,
+        4 => DType::Date,
+        5 => DType::DateTime,
+        6 => DType::Category,
+        code => return Err(error(format!("unknown type {code}"))),
+    })
+}
+
+fn error(message: String) -> PandasError {
+    PandasError::Columnar(message)
+}
+
+/// lookup table of the CRC-32 (IEEE) polynomial
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut idx = 0;
+    while idx < 256 {
+        let mut crc = idx as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 1 {
+                1 => 0xEDB8_8320 ^ (crc >> 1),
+                _ => crc >> 1,
+            };
+            bit += 1;
+        }
+        table[idx] = crc;
+        idx += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &byte| {
+        CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_str(out: &mut Vec<u8>, value: &str) {
+    put_u32(out, value.len() as u32);
+    out.extend_from_slice(value.as_bytes());
+}
+
+/// appends `bytes` with its length and checksum
+fn put_section(out: &mut Vec<u8>, bytes: &[u8]) {
+    put_u64(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+    put_u32(out, crc32(bytes));
+}
+
+/// A cursor over the bytes of a section, every read fails instead of
+/// panicking when the section is too short.
+struct Input<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Input<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| error(String::from("unexpected end of a section")))?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// an unsigned integer of `width` little endian bytes
+    fn uint(&mut self, width: usize) -> Result<u64> {
+        let mut word = [0; 8];
+        word[..width].copy_from_slice(self.take(width)?);
+        Ok(u64::from_le_bytes(word))
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<usize> {
+        Ok(self.uint(4)? as usize)
+    }
+
+    fn u64(&mut self) -> Result<usize> {
+        usize::try_from(self.uint(8)?).map_err(|_| error(String::from("length out of range")))
+    }
+
+    fn str(&mut self) -> Result<&'a str> {
+        let len = self.u32()?;
+        std::str::from_utf8(self.take(len)?).map_err(|_| error(String::from("invalid utf-8 text")))
+    }
+
+    /// the next section after verifying its checksum, `what` names it in errors
+    fn section(&mut self, what: &str) -> Result<&'a [u8]> {
+        let len = self.u64()?;
+        let bytes = self
+            .take(len)
+            .map_err(|_| error(format!("{what} is truncated")))?;
+        let checksum = self
+            .uint(4)
+            .map_err(|_| error(format!("{what} is truncated")))?;
+        match crc32(bytes) as u64 == checksum {
+            true => Ok(bytes),
+            false => Err(error(format!("checksum mismatch in {what}"))),
+        }
+    }
+
+    fn finish(&self) -> Result<()> {
+        match self.position == self.bytes.len() {
+            true => Ok(()),
+            false => Err(error(String::from(
+                "unexpected bytes at the end of a section",
+            ))),
+        }
+    }
+}
+
+/// bytes stored for every value of a fixed width column
+fn width(d_type: DType) -> usize {
+    match d_type {
+        DType::Bool => 1,
+        DType::Category => 4,
+        _ => 8,
+    }
+}
+
+/// runs of equal values as their length and the value
+fn runs<T: PartialEq + Copy>(values: &[T]) -> Vec<(u32, T)> {
+    let mut runs: Vec<(u32, T)> = vec![];
+    for &value in values {
+        match runs.last_mut() {
+            Some((len, last)) if *last == value && *len < u32::MAX => *len += 1,
+            _ => runs.push((1, value)),
+        }
+    }
+    runs
+}
+
+/// Values of a chunk: the bits of the fixed width types, `Category` codes
+/// included, or the text of a `Str` column.
+enum Values<'a> {
+    Fixed(Vec<u64>),
+    Text(Vec<&'a str>),
+}
+
+impl<'a> Values<'a> {
+    fn new(column: &'a Column, rows: Range<usize>) -> Self {
+        match column {
+            Column::Bool(values) => Values::Fixed(rows.map(|idx| values.get(idx) as u64).collect()),
+            Column::Int(values) | Column::Date(values) | Column::DateTime(values) => {
+                Values::Fixed(values[rows].iter().map(|&v| v as u64).collect())
+            }
+            Column::Float(values) => {
+                Values::Fixed(values[rows].iter().map(|v| v.to_bits()).collect())
+            }
+            Column::Category { codes, .. } => {
+                Values::Fixed(codes[rows].iter().map(|&code| code as u64).collect())
+            }
+            Column::Str { .. } => Values::Text(rows.map(|idx| column.str_at(idx)).collect()),
+        }
+    }
+
+    fn encode(&self, encoding: Encoding, d_type: DType, out: &mut Vec<u8>) {
+        let width = width(d_type);
+        match (self, encoding) {
+            (Values::Fixed(values), Encoding::Plain) if d_type == DType::Bool => {
+                out.extend(values.chunks(8).map(|bits| {
+                    bits.iter()
+                        .enumerate()
+                        .fold(0u8, |byte, (idx, &bit)| byte | ((bit as u8) << idx))
+                }));
+            }
+            (Values::Fixed(values), Encoding::Plain) => values
+                .iter()
+                .for_each(|v| out.extend_from_slice(&v.to_le_bytes()[..width])),
+            (Values::Fixed(values), Encoding::RunLength) => {
+                let runs = runs(values);
+                put_u32(out, runs.len() as u32);
+                for (len, value) in runs {
+                    put_u32(out, len);
+                    out.extend_from_slice(&value.to_le_bytes()[..width]);
+                }
+            }
+            (Values::Text(values), Encoding::Plain) => {
+                values.iter().for_each(|value| put_str(out, value))
+            }
+            (Values::Text(values), Encoding::RunLength) => {
+                let runs = runs(values);
+                put_u32(out, runs.len() as u32);
+                for (len, value) in runs {
+                    put_u32(out, len);
+                    put_str(out, value);
+                }
+            }
+            (Values::Text(values), Encoding::Dictionary) => {
+                let dictionary = values.iter().collect::<Dictionary>();
+                put_u32(out, dictionary.len() as u32);
+                dictionary.iter().for_each(|value| put_str(out, value));
+                for value in values {
+                    put_u32(out, dictionary.code(value).expect("value in dictionary"));
+                }
+            }
+            (Values::Fixed(_), Encoding::Dictionary) => {
+                unreachable!("only text is dictionary encoded")
+            }
+        }
+    }
+
+    fn decode(
+        input: &mut Input<'a>,
+        encoding: Encoding,
+        d_type: DType,
+        len: usize,
+    ) -> Result<Self> {
+        let width = width(d_type);
+        let values = match (d_type, encoding) {
+            (DType::Bool, Encoding::Plain) => {
+                let bytes = input.take(len.div_ceil(8))?;
+                Values::Fixed(
+                    (0..len)
+                        .map(|idx| (bytes[idx / 8] >> (idx % 8) & 1) as u64)
+                        .collect(),
+                )
+            }
+            (DType::Str, Encoding::Plain) => {
+                Values::Text((0..len).map(|_| input.str()).collect::<Result<_>>()?)
+            }
+            (DType::Str, Encoding::RunLength) => {
+                let mut values = vec![];
+                for _ in 0..input.u32()? {
+                    let run = input.u32()?;
+                    let value = input.str()?;
+                    if values.len() + run > len {
+                        return Err(error(format!("more than {len} values in a chunk")));
+                    }
+                    values.extend(std::iter::repeat_n(value, run));
+                }
+                Values::Text(values)
+            }
+            (DType::Str, Encoding::Dictionary) => {
+                let dictionary = (0..input.u32()?)
+                    .map(|_| input.str())
+                    .collect::<Result<Vec<&str>>>()?;
+                let values = (0..len)
+                    .map(|_| {
+                        let code = input.u32()?;
+                        dictionary
+                            .get(code)
+                            .copied()
+                            .ok_or_else(|| error(format!("unknown dictionary code {code}")))
+                    })
+                    .collect::<Result<_>>()?;
+                Values::Text(values)
+            }
+            (_, Encoding::Plain) => {
+                Values::Fixed((0..len).map(|_| input.uint(width)).collect::<Result<_>>()?)
+            }
+            (_, Encoding::RunLength) => {
+                let mut values = vec![];
+                for _ in 0..input.u32()? {
+                    let run = input.u32()?;
+                    let value = input.uint(width)?;
+                    if values.len() + run > len {
+                        return Err(error(format!("more than {len} values in a chunk")));
+                    }
+                    values.extend(std::iter::repeat_n(value, run));
+                }
+                Values::Fixed(values)
+            }
+            (d_type, Encoding::Dictionary) => {
+                return Err(error(format!("dictionary encoded {d_type:?} values")));
+            }
+        };
+        let found = match &values {
+            Values::Fixed(values) => values.len(),
+            Values::Text(values) => values.len(),
+        };
+        match found == len {
+            true => Ok(values),
+            false => Err(error(format!("expected {len} values, found {found}"))),
+        }
+    }
+
+    /// appends the values to a column of their type
+    fn append(self, column: &mut Column) {
+        match self {
+            Values::Fixed(values) => values.into_iter().for_each(|v| match column {
+                Column::Bool(values) => values.push(v != 0),
+                Column::Int(values) | Column::Date(values) | Column::DateTime(values) => {
+                    values.push(v as i64)
+                }
+                Column::Float(values) => values.push(f64::from_bits(v)),
+                Column::Category { codes, .. } => codes.push(v as u32),
+                Column::Str { .. } => unreachable!("text is never stored as bits"),
+            }),
+            Values::Text(values) => values.into_iter().for_each(|v| column.push_str(v)),
+        }
+    }
+}
+
+/// Encodes a chunk: its encoding, number of rows, missing values and values
+fn encode_chunk(series: &Series, rows: Range<usize>, options: &ColumnarOptions) -> Vec<u8> {
+    let d_type = series.d_type();
+    let mut out = vec![];
+    out.push(0);
+    put_u32(&mut out, rows.len() as u32);
+    match rows.clone().all(|idx| series.is_valid(idx)) {
+        true => out.push(0),
+        false => {
+            out.push(1);
+            let validity = rows
+                .clone()
+                .map(|idx| series.is_valid(idx) as u64)
+                .collect();
+            Values::Fixed(validity).encode(Encoding::Plain, DType::Bool, &mut out);
+        }
+    }
+    let values = Values::new(&series.values, rows);
+    let encodings = match (options.encode, &values) {
+        (false, _) => vec![Encoding::Plain],
+        (true, Values::Fixed(_)) => vec![Encoding::Plain, Encoding::RunLength],
+        (true, Values::Text(_)) => {
+            vec![Encoding::Plain, Encoding::RunLength, Encoding::Dictionary]
+        }
+    };
+    let (encoding, payload) = encodings
+        .into_iter()
+        .map(|encoding| {
+            let mut payload = vec![];
+            values.encode(encoding, d_type, &mut payload);
+            (encoding, payload)
+        })
+        .min_by_key(|(_, payload)| payload.len())
+        .expect("plain is always a candidate");
+    out[0] = encoding.code();
+    out.extend(payload);
+    out
+}
+
+/// Encodes the sections of a column: the categories of a `Category` column
+/// followed by its chunks.
+fn encode_column(series: &Series, options: &ColumnarOptions) -> Vec<u8> {
+    let mut out = vec![];
+    if let Column::Category { dictionary, .. } = &series.values {
+        let mut categories = vec![];
+        put_u32(&mut categories, dictionary.len() as u32);
+        dictionary
+            .iter()
+            .for_each(|value| put_str(&mut categories, value));
+        put_section(&mut out, &categories);
+    }
+    for start in (0..series.len()).step_by(options.chunk_rows) {
+        let rows = start..series.len().min(start + options.chunk_rows);
+        put_section(&mut out, &encode_chunk(series, rows, options));
+    }
+    out
+}
+
+/// Decodes the sections of the column `name` that holds `rows` values
+fn decode_column(bytes: &[u8], name: &str, d_type: DType, rows: usize) -> Result<Series> {
+    let mut input = Input::new(bytes);
+    let mut values = Column::with_capacity(d_type, rows.min(PREALLOCATED_ROWS));
+    if let Column::Category { dictionary, .. } = &mut values {
+        let mut categories = Input::new(input.section(&format!("categories of column {name:?}"))?);
+        *dictionary = (0..categories.u32()?)
+            .map(|_| categories.str())
+            .collect::<Result<Dictionary>>()?;
+        categories.finish()?;
+    }
+    let mut validity = Bitmap::with_capacity(rows.min(PREALLOCATED_ROWS));
+    let mut chunk = 0;
+    while validity.len() < rows {
+        let what = format!("chunk {chunk} of column {name:?}");
+        let mut section = Input::new(input.section(&what)?);
+        let encoding = Encoding::from_code(section.u8()?)?;
+        let len = section.u32()?;
+        if validity.len() + len > rows {
+            return Err(error(format!("more than {rows} rows in column {name:?}")));
+        }
+        match section.u8()? {
+            0 => (0..len).for_each(|_| validity.push(true)),
+            _ => match Values::decode(&mut section, Encoding::Plain, DType::Bool, len)? {
+                Values::Fixed(bits) => bits.into_iter().for_each(|bit| validity.push(bit != 0)),
+                Values::Text(_) => unreachable!("validity is stored as bits"),
+            },
+        }
+        Values::decode(&mut section, encoding, d_type, len)?.append(&mut values);
+        section.finish()?;
+        chunk += 1;
+    }
+    input.finish()?;
+    if validity.len() != rows {
+        return Err(error(format!("expected {rows} rows in column {name:?}")));
+    }
+    if let Column::Category { codes, dictionary } = &values {
+        let unknown =
+            (0..rows).any(|idx| validity.get(idx) && codes[idx] as usize >= dictionary.len());
+        if unknown {
+            return Err(error(format!("unknown category in column {name:?}")));
+        }
+    }
+    Ok(Series {
+        values,
+        validity,
+        labels: None,
+        cells: OnceLock::new(),
+    })
+}
+
+/// reads exactly `buf.len()` bytes, reporting a short file as truncated
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<()> {
+    reader.read_exact(buf).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => error(String::from("the file is truncated")),
+        _ => PandasError::Io(err),
+    })
+}
+
+/// reads `len` bytes without trusting `len` for the allocation
+fn read_bytes<R: Read>(reader: &mut R, len: u64) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    reader.by_ref().take(len).read_to_end(&mut bytes)?;
+    match bytes.len() as u64 == len {
+        true => Ok(bytes),
+        false => Err(error(String::from("the file is truncated"))),
+    }
+}
+
+/// Reads a columnar file written by `DataFrame::write_columnar` from any
+/// seekable reader. The sections of the loaded columns are checked against
+/// their checksums, the other columns are skipped.
+pub(crate) fn read_columnar_from<R: Read + Seek>(
+    mut reader: R,
+    options: &ColumnarOptions,
+) -> Result<DataFrame> {
+    let mut start = [0; 5];
+    read_exact(&mut reader, &mut start)?;
+    if &start[..4] != MAGIC {
+        return Err(error(String::from("not a columnar file")));
+    }
+    if start[4] != VERSION {
+        return Err(error(format!("unsupported version {}", start[4])));
+    }
+    let mut len = [0; 8];
+    read_exact(&mut reader, &mut len)?;
+    let mut schema = len.to_vec();
+    let len = u64::from_le_bytes(len)
+        .checked_add(4)
+        .ok_or_else(|| error(String::from("length out of range")))?;
+    schema.extend(read_bytes(&mut reader, len)?);
+    let mut schema = Input::new(Input::new(&schema).section("schema")?);
+
+    let rows = schema.u64()?;
+    let columns = (0..schema.u32()?)
+        .map(|_| {
+            let name = schema.str()?.to_string();
+            let d_type = dtype_from_code(schema.u8()?)?;
+            Ok((Header { name, d_type }, schema.uint(8)?))
+        })
+        .collect::<Result<Vec<(Header, u64)>>>()?;
+    let index = match schema.u8()? {
+        0 => None,
+        _ => Some(
+            (0..rows)
+                .map(|_| schema.u64())
+                .collect::<Result<Vec<usize>>>()?,
+        ),
+    };
+    let labels = match schema.u8()? {
+        0 => None,
+        _ => {
+            let name = schema.str()?.to_string();
+            let d_type = dtype_from_code(schema.u8()?)?;
+            Some((Header { name, d_type }, schema.uint(8)?))
+        }
+    };
+    schema.finish()?;
+    if let Some(names) = &options.columns {
+        if let Some(name) = names
+            .iter()
+            .find(|name| !columns.iter().any(|(h, _)| &h.name == *name))
+        {
+            return Err(PandasError::ColumnNotFound(name.clone()));
+        }
+    }
+
+    let mut blocks = vec![];
+    for (header, len) in columns {
+        match options
+            .columns
+            .as_ref()
+            .is_none_or(|c| c.contains(&header.name))
+        {
+            true => blocks.push((header, read_bytes(&mut reader, len)?)),
+            false => {
+                let len =
+                    i64::try_from(len).map_err(|_| error(String::from("length out of range")))?;
+                reader.seek(SeekFrom::Current(len))?;
+            }
+        }
+    }
+    let labels = match labels {
+        Some((header, len)) => {
+            let bytes = read_bytes(&mut reader, len)?;
+            let values = decode_column(&bytes, &header.name, header.d_type, rows)?;
+            Some(Arc::new(Labels::new(&header.name, values)))
+        }
+        None => None,
+    };
+    let data = parallel::map(parallel::threads(), blocks, |(header, bytes)| {
+        let series = decode_column(&bytes, &header.name, header.d_type, rows)?;
+        Ok((header, series))
+    })
+    .into_iter()
+    .collect::<Result<Vec<(Header, Series)>>>()?;
+    let mut df = DataFrame {
+        headers: data.iter().map(|(header, _)| header.clone()).collect(),
+        data: data
+            .into_iter()
+            .map(|(header, series)| (header.name, series))
+            .collect(),
+        index,
+        labels,
+    };
+    df.share_labels();
+    Ok(df)
+}
+
+/// Reads a columnar file using the provided options
+pub(crate) fn read_columnar_with(path: &str, options: &ColumnarOptions) -> Result<DataFrame> {
+    read_columnar_from(BufReader::new(File::open(path)?), options)
+}
+
+/// Reads every column of a columnar file
+pub(crate) fn read_columnar(path: &str) -> Result<DataFrame> {
+    read_columnar_with(path, &ColumnarOptions::new())
+}
+
+impl DataFrame {
+    /// Writes the data frame as a columnar file to any writer, see
+    /// `ColumnarOptions`. Columns are encoded in parallel.
+    pub(crate) fn write_columnar<W: Write>(
+        &self,
+        writer: W,
+        options: &ColumnarOptions,
+    ) -> Result<()> {
+        let columns = self.headers.iter().collect();
+        let blocks = parallel::map(parallel::threads(), columns, |header| {
+            encode_column(&self.data[&header.name], options)
+        });
+        let rows = self.shape().0;
+        let mut schema = vec![];
+        put_u64(&mut schema, rows as u64);
+        put_u32(&mut schema, self.headers.len() as u32);
+        for (header, block) in self.headers.iter().zip(&blocks) {
+            put_str(&mut schema, &header.name);
+            schema.push(dtype_code(header.d_type));
+            put_u64(&mut schema, block.len() as u64);
+        }
+        match &self.index {
+            Some(index) => {
+                schema.push(1);
+                index
+                    .iter()
+                    .for_each(|&label| put_u64(&mut schema, label as u64));
+            }
+            None => schema.push(0),
+        }
+        let labels = self
+            .labels
+            .as_ref()
+            .map(|labels| encode_column(&labels.values, options));
+        match (self.labels.as_deref(), &labels) {
+            (Some(Labels { name, values, .. }), Some(block)) => {
+                schema.push(1);
+                put_str(&mut schema, name);
+                schema.push(dtype_code(values.d_type()));
+                put_u64(&mut schema, block.len() as u64);
+            }
+            _ => schema.push(0),
+        }
+
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        let mut section = vec![];
+        put_section(&mut section, &schema);
+        writer.write_all(&section)?;
+        for block in blocks.iter().chain(&labels) {
+            writer.write_all(block)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the data frame to a columnar file using the provided options
+    pub(crate) fn to_columnar_with(&self, path: &str, options: &ColumnarOptions) -> Result<()> {
+        self.write_columnar(File::create(path)?, options)
+    }
+
+    /// Writes the data frame to a columnar file with encoded chunks of 65536
+    /// rows, which `read_columnar` loads back into the same data frame.
+    pub(crate) fn to_columnar(&self, path: &str) -> Result<()> {
+        self.to_columnar_with(path, &ColumnarOptions::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::pandas::{
+        csv::{read_csv_from, CsvOptions},
+        fixtures,
+    };
+
+    fn typed() -> DataFrame {
+        let source = "SN,Name,Dept,Age,CGPA,Graduated,Joined,Seen\n\
+                      1,John,CS,20,3.5,true,2021-09-01,2024-01-05 10:30:00\n\
+                      2,Jane,EE,19,,false,2022-09-01,\n\
+                      3,,CS,21,3.75,,2021-09-01,2024-02-11 08:00\n\
+                      4,Adam,CS,,2.5,true,,2024-03-01 17:45:30\n\
+                      5,Jane,ME,19,3.0,false,2022-09-01,2024-03-02 09:00\n";
+        let mut df = fixtures::read(source);
+        df.as_type([
+            (String::from("Joined"), DType::Date),
+            (String::from("Seen"), DType::DateTime),
+            (String::from("Dept"), DType::Category),
+        ])
+        .unwrap();
+        df
+    }
+
+    fn write(df: &DataFrame, options: &ColumnarOptions) -> Vec<u8> {
+        let mut output = vec![];
+        df.write_columnar(&mut output, options).unwrap();
+        output
+    }
+
+    fn read(bytes: &[u8], options: &ColumnarOptions) -> Result<DataFrame> {
+        read_columnar_from(Cursor::new(bytes), options)
+    }
+
+    /// the values, types, index, labels and categories of a data frame
+    fn contents(df: &DataFrame) -> (String, Vec<DType>, Vec<usize>, String, Vec<String>) {
+        let mut csv = vec![];
+        df.write_csv(&mut csv, &CsvOptions::new()).unwrap();
+        let types = df.headers.iter().map(|h| h.d_type).collect();
+        let labels = df.labels.as_ref().map_or(String::new(), |labels| {
+            format!("{}\n{}", labels.name, labels.values)
+        });
+        let categories = match df.data.get("Dept") {
+            Some(series) => series.categories().unwrap(),
+            None => vec![],
+        };
+        (
+            String::from_utf8(csv).unwrap(),
+            types,
+            df.index(),
+            labels,
+            categories,
+        )
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let df = typed();
+        let filtered = df.query("SN != 2").unwrap();
+        let empty = df.query("SN > 10").unwrap();
+        let labelled = filtered.set_index("Name").unwrap();
+        for df in [&df, &filtered, &empty, &labelled] {
+            for options in [
+                ColumnarOptions::new(),
+                ColumnarOptions::new().chunk_rows(2),
+                ColumnarOptions::new().chunk_rows(3).encode(false),
+            ] {
+                let copy = read(&write(df, &options), &ColumnarOptions::new()).unwrap();
+                assert_eq!(contents(&copy), contents(df));
+            }
+        }
+        assert_eq!(filtered.index(), vec![0, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_encodings() {
+        let source = (0..1000)
+            .map(|idx| format!("{},team {},{}\n", idx / 100, idx % 3, idx % 2 == 0))
+            .collect::<String>();
+        let options = CsvOptions::new().headers(false);
+        let df = read_csv_from(source.as_bytes(), &options).unwrap();
+        let plain = write(&df, &ColumnarOptions::new().encode(false));
+        let encoded = write(&df, &ColumnarOptions::new());
+        assert!(
+            encoded.len() * 3 < plain.len(),
+            "{} vs {}",
+            encoded.len(),
+            plain.len()
+        );
+        let copy = read(&encoded, &ColumnarOptions::new()).unwrap();
+        assert_eq!(contents(&copy), contents(&df));
+    }
+
+    #[test]
+    fn test_selected_columns() {
+        let df = typed();
+        let bytes = write(&df, &ColumnarOptions::new().chunk_rows(2));
+        let options = ColumnarOptions::new().columns(["CGPA", "Dept"]);
+        let selected = read(&bytes, &options).unwrap();
+        assert_eq!(
+            contents(&selected),
+            contents(&df.select(["Dept", "CGPA"]).unwrap())
+        );
+        // the chunks of the other columns are not checked
+        let mut corrupted = bytes.clone();
+        let name = bytes.windows(4).rposition(|w| w == b"John").unwrap();
+        corrupted[name] = b'j';
+        assert!(read(&corrupted, &options).is_ok());
+        assert!(matches!(
+            read(&bytes, &ColumnarOptions::new().columns(["Grade"])),
+            Err(PandasError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_corrupted_files() {
+        let bytes = write(&typed(), &ColumnarOptions::new().encode(false));
+        let name = bytes.windows(4).rposition(|w| w == b"John").unwrap();
+        let mut corrupted = bytes.clone();
+        corrupted[name] = b'j';
+        let err = read(&corrupted, &ColumnarOptions::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "columnar file error: checksum mismatch in chunk 0 of column \"Name\""
+        );
+
+        let err = read(&bytes[..bytes.len() - 1], &ColumnarOptions::new()).unwrap_err();
+        assert!(matches!(err, PandasError::Columnar(message) if message.contains("truncated")));
+        let err = read(b"SN,Name\n1,John\n", &ColumnarOptions::new()).unwrap_err();
+        assert_eq!(err.to_string(), "columnar file error: not a columnar file");
+        let mut version = bytes.clone();
+        version[4] = 9;
+        assert!(read(&version, &ColumnarOptions::new()).is_err());
+        let mut schema = bytes.clone();
+        schema[20] ^= 1;
+        let err = read(&schema, &ColumnarOptions::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "columnar file error: checksum mismatch in schema"
+        );
+        let mut huge = b"PDCF\x01".to_vec();
+        huge.extend([0xff; 8]);
+        let err = read(&huge, &ColumnarOptions::new()).unwrap_err();
+        assert_eq!(err.to_string(), "columnar file error: length out of range");
+    }
+
+    #[test]
+    fn test_row_count_not_trusted() {
+        let df = typed();
+        let age = df.column("Age").unwrap();
+        let bytes = encode_column(age, &ColumnarOptions::new().chunk_rows(2));
+        assert!(decode_column(&bytes, "Age", DType::Int, 5).is_ok());
+        let err = decode_column(&bytes, "Age", DType::Int, 3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "columnar file error: more than 3 rows in column \"Age\""
+        );
+        let err = decode_column(&bytes, "Age", DType::Int, usize::MAX).unwrap_err();
+        assert!(matches!(err, PandasError::Columnar(_)));
+        let options = ColumnarOptions::new().chunk_rows(usize::MAX);
+        assert_eq!(options.chunk_rows, u32::MAX as usize);
+    }
+
+    #[test]
+    fn test_columnar_file() {
+        let path = std::env::temp_dir().join("pandas_columnar_test.pdc");
+        let path = path.to_str().unwrap();
+        let df = typed();
+        df.to_columnar(path).unwrap();
+        let copy = read_columnar(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(contents(&copy), contents(&df));
+    }
+}
@@ -0,0 +1,1232 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+};
+
+use super::{
+    error::{PandasError, Result},
+    parallel, Cell, DType, DataFrame, Header, Series,
+};
+
+/// smallest number of bytes worth parsing on a thread of its own, sources are
+/// read `threads` times as many bytes at a time
+const PARALLEL_BYTES: usize = 1 << 20;
+
+/// Options that control how a csv source is parsed into a `DataFrame` and how
+/// a `DataFrame` is written as csv.
+///
+/// The reader follows RFC 4180: fields may be wrapped in quotes, a quote
+/// inside a quoted field is escaped by doubling it (`""`) and quoted fields
+/// may span multiple lines.
+///
+/// ```ignore
+/// let options = CsvOptions::new().delimiter(';').comment('#').skip_rows(2);
+/// let df = pd::read_csv_with("data.csv", &options)?;
+/// ```
+#[derive(Debug, Clone)]
+pub(crate) struct CsvOptions {
+    pub(crate) headers: bool,
+    pub(crate) delimiter: char,
+    pub(crate) quote: char,
+    pub(crate) comment: Option<char>,
+    pub(crate) skip_rows: usize,
+    pub(crate) na_values: Vec<String>,
+    pub(crate) trim: bool,
+    pub(crate) infer_types: bool,
+    pub(crate) infer_rows: Option<usize>,
+    pub(crate) dtypes: Vec<(String, DType)>,
+    pub(crate) columns: Option<Vec<String>>,
+    pub(crate) na_rep: String,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            headers: true,
+            delimiter: ',',
+            quote: '"',
+            comment: None,
+            skip_rows: 0,
+            na_values: vec![String::new(), String::from("NA"), String::from("null")],
+            trim: true,
+            infer_types: true,
+            infer_rows: None,
+            dtypes: vec![],
+            columns: None,
+            na_rep: String::new(),
+        }
+    }
+}
+
+impl CsvOptions {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// whether the first record contains the column names
+    pub(crate) fn headers(mut self, headers: bool) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// field separator such as `,`, `\t`, `;` or `|`
+    pub(crate) fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub(crate) fn quote(mut self, quote: char) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// lines starting with the given character are ignored
+    pub(crate) fn comment(mut self, comment: char) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    /// number of lines to skip at the start of the source before the header
+    pub(crate) fn skip_rows(mut self, skip_rows: usize) -> Self {
+        self.skip_rows = skip_rows;
+        self
+    }
+
+    /// unquoted values that are loaded as missing (`None`) cells
+    pub(crate) fn na_values<T, S>(mut self, na_values: T) -> Self
+    where
+        T: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.na_values = na_values.into_iter().map(|v| v.into()).collect();
+        self
+    }
+
+    /// strip whitespace around unquoted fields and before opening quotes
+    pub(crate) fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// infer `Bool`, `Int` and `Float` columns, when disabled every column
+    /// without an explicit `dtype` is loaded as `DType::Str`
+    pub(crate) fn infer_types(mut self, infer_types: bool) -> Self {
+        self.infer_types = infer_types;
+        self
+    }
+
+    /// only look at the first `rows` rows while inferring column types, values
+    /// after the sample must still be convertible into the inferred type
+    pub(crate) fn infer_rows(mut self, rows: usize) -> Self {
+        self.infer_rows = Some(rows);
+        self
+    }
+
+    /// Only load the listed columns, in the order of the source. Fields of
+    /// the other columns are skipped without being converted.
+    pub(crate) fn columns<T, S>(mut self, columns: T) -> Self
+    where
+        T: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.columns = Some(columns.into_iter().map(|c| c.into()).collect());
+        self
+    }
+
+    /// text written for missing values by `DataFrame::to_csv_with`
+    pub(crate) fn na_rep(mut self, na_rep: &str) -> Self {
+        self.na_rep = na_rep.to_string();
+        self
+    }
+
+    /// load the column `name` as `d_type` instead of inferring its type
+    pub(crate) fn dtype(mut self, name: &str, d_type: DType) -> Self {
+        self.dtypes.retain(|(n, _)| n != name);
+        self.dtypes.push((name.to_string(), d_type));
+        self
+    }
+}
+
+/// A record together with the line number at which it starts
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Record {
+    pub(crate) line: usize,
+    /// the text of all fields back to back
+    text: String,
+    /// the end of every field in `text` and whether it was quoted, quoted
+    /// fields are never treated as missing values
+    ends: Vec<(usize, bool)>,
+}
+
+impl Record {
+    /// number of fields
+    pub(crate) fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// the text of the field at `idx` and whether it was quoted
+    pub(crate) fn field(&self, idx: usize) -> (&str, bool) {
+        let start = idx.checked_sub(1).map_or(0, |prev| self.ends[prev].0);
+        let (end, quoted) = self.ends[idx];
+        (&self.text[start..end], quoted)
+    }
+
+    pub(crate) fn fields(&self) -> impl Iterator<Item = (&str, bool)> + '_ {
+        (0..self.len()).map(|idx| self.field(idx))
+    }
+
+    /// ends the field being read, dropping trailing whitespace if `trim` and
+    /// the field is not quoted
+    fn finish(&mut self, quoted: bool, trim: bool) {
+        if trim && !quoted {
+            let start = self.ends.last().map_or(0, |(end, _)| *end);
+            let len = self.text[start..].trim_end().len();
+            self.text.truncate(start + len);
+        }
+        self.ends.push((self.text.len(), quoted));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Start,
+    Unquoted,
+    Quoted,
+    // a quote has been seen inside a quoted field, it is either an escape or
+    // the closing quote depending on the next character
+    QuoteInQuoted,
+    AfterQuoted,
+}
+
+/// Iterator over the csv records of any buffered reader.
+pub(crate) struct Records<R> {
+    reader: R,
+    options: CsvOptions,
+    line: usize,
+    skipped: bool,
+    /// the physical line being parsed, kept to reuse its allocation
+    buf: String,
+}
+
+impl<R: BufRead> Records<R> {
+    pub(crate) fn new(reader: R, options: CsvOptions) -> Self {
+        Self {
+            reader,
+            options,
+            line: 0,
+            skipped: false,
+            buf: String::new(),
+        }
+    }
+
+    /// reads a physical line without its line terminator
+    fn read_line(&mut self, buf: &mut String) -> Result<bool> {
+        buf.clear();
+        let read = self.reader.read_line(buf)?;
+        if read == 0 {
+            return Ok(false);
+        }
+        self.line += 1;
+        if buf.ends_with('\n') {
+            buf.pop();
+            if buf.ends_with('\r') {
+                buf.pop();
+            }
+        }
+        Ok(true)
+    }
+
+    /// Reads the next record into `record`, reusing its buffers. Returns
+    /// `false` at the end of the source.
+    pub(crate) fn read_record(&mut self, record: &mut Record) -> Result<bool> {
+        let mut buf = std::mem::take(&mut self.buf);
+        let read = self.parse_record(&mut buf, record);
+        self.buf = buf;
+        read
+    }
+
+    fn parse_record(&mut self, buf: &mut String, record: &mut Record) -> Result<bool> {
+        if !self.skipped {
+            self.skipped = true;
+            for _ in 0..self.options.skip_rows {
+                if !self.read_line(buf)? {
+                    return Ok(false);
+                }
+            }
+        }
+        loop {
+            if !self.read_line(buf)? {
+                return Ok(false);
+            }
+            let is_comment = self.options.comment.is_some_and(|c| buf.starts_with(c));
+            if !buf.is_empty() && !is_comment {
+                break;
+            }
+        }
+
+        let (delimiter, quote, trim) = (
+            self.options.delimiter,
+            self.options.quote,
+            self.options.trim,
+        );
+        record.line = self.line;
+        record.text.clear();
+        record.ends.clear();
+        if !buf.contains(quote) {
+            // without quotes every delimiter ends a field
+            for field in buf.split(delimiter) {
+                record
+                    .text
+                    .push_str(if trim { field.trim() } else { field });
+                record.ends.push((record.text.len(), false));
+            }
+            return Ok(true);
+        }
+
+        let mut state = State::Start;
+        let quoted = |state: State| !matches!(state, State::Start | State::Unquoted);
+        loop {
+            for ch in buf.chars() {
+                match state {
+                    State::Start if ch == quote => state = State::Quoted,
+                    State::Start | State::Unquoted if ch == delimiter => {
+                        record.finish(quoted(state), trim);
+                        state = State::Start;
+                    }
+                    State::Start if trim && ch.is_whitespace() => {}
+                    State::Start | State::Unquoted => {
+                        record.text.push(ch);
+                        state = State::Unquoted;
+                    }
+                    State::Quoted if ch == quote => state = State::QuoteInQuoted,
+                    State::Quoted => record.text.push(ch),
+                    State::QuoteInQuoted if ch == quote => {
+                        record.text.push(quote);
+                        state = State::Quoted;
+                    }
+                    State::QuoteInQuoted | State::AfterQuoted if ch == delimiter => {
+                        record.finish(quoted(state), trim);
+                        state = State::Start;
+                    }
+                    State::QuoteInQuoted | State::AfterQuoted if ch.is_whitespace() => {
+                        state = State::AfterQuoted;
+                    }
+                    State::QuoteInQuoted | State::AfterQuoted => {
+                        return Err(PandasError::Csv {
+                            line: self.line,
+                            message: format!("unexpected character {ch:?} after closing quote"),
+                        });
+                    }
+                }
+            }
+            if state != State::Quoted {
+                break;
+            }
+            // the quoted field continues on the next line
+            record.text.push('\n');
+            if !self.read_line(buf)? {
+                return Err(PandasError::Csv {
+                    line: record.line,
+                    message: String::from("unterminated quoted field"),
+                });
+            }
+        }
+        record.finish(quoted(state), trim);
+        Ok(true)
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = Record::default();
+        match self.read_record(&mut record) {
+            Ok(true) => Some(Ok(record)),
+            Ok(false) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// makes column names unique by suffixing repeated names with `.1`, `.2`, ...
+fn unique_names(names: Vec<String>) -> Vec<String> {
+    let mut seen: Vec<String> = vec![];
+    names
+        .into_iter()
+        .map(|name| {
+            let mut candidate = name.clone();
+            let mut count = 0;
+            while seen.contains(&candidate) {
+                count += 1;
+                candidate = format!("{name}.{count}");
+            }
+            seen.push(candidate.clone());
+            candidate
+        })
+        .collect()
+}
+
+/// The columns of a data frame being read, every field is parsed straight
+/// into the buffer of its column's type.
+struct Columns {
+    names: Vec<String>,
+    series: Vec<Series>,
+    rows: usize,
+}
+
+impl Columns {
+    /// empty `Str` columns, or columns of the given types
+    fn new(names: &[String], d_types: Option<&[(String, DType)]>) -> Self {
+        let series = match d_types {
+            Some(d_types) => d_types
+                .iter()
+                .map(|(_, d_type)| Series::with_capacity(*d_type, 0))
+                .collect(),
+            None => names
+                .iter()
+                .map(|_| Series::with_capacity(DType::Str, 0))
+                .collect(),
+        };
+        Self {
+            names: names.to_vec(),
+            series,
+            rows: 0,
+        }
+    }
+
+    /// Appends a record of at most `width` fields, keeping only the fields at
+    /// `positions` if set, which must be sorted. Missing trailing fields are
+    /// loaded as missing values.
+    fn push(
+        &mut self,
+        record: &Record,
+        width: usize,
+        positions: Option<&[usize]>,
+        options: &CsvOptions,
+    ) -> Result<()> {
+        if record.len() > width {
+            return Err(PandasError::Csv {
+                line: record.line,
+                message: format!("expected {width} fields, found {}", record.len()),
+            });
+        }
+        let fields = record
+            .fields()
+            .enumerate()
+            .filter(|(idx, _)| positions.is_none_or(|p| p.binary_search(idx).is_ok()))
+            .map(|(_, (value, quoted))| {
+                match !quoted && options.na_values.iter().any(|na| na == value) {
+                    true => None,
+                    false => Some(value),
+                }
+            });
+        let fields = fields.chain(std::iter::repeat(None));
+        for ((name, series), value) in self.names.iter().zip(&mut self.series).zip(fields) {
+            if !series.push_parsed(value) {
+                return Err(PandasError::Conversion {
+                    column: Some(name.clone()),
+                    row: self.rows,
+                    value: value.unwrap_or_default().to_string(),
+                    d_type: series.d_type(),
+                });
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// appends the rows of `other`, which has the same `Str` columns
+    fn append(&mut self, other: &Columns) {
+        self.series
+            .iter_mut()
+            .zip(&other.series)
+            .for_each(|(series, other)| append_str(series, other));
+        self.rows += other.rows;
+    }
+
+    fn into_frame(self) -> DataFrame {
+        let headers = self
+            .names
+            .iter()
+            .zip(&self.series)
+            .map(|(name, series)| Header {
+                name: name.clone(),
+                d_type: series.d_type(),
+            })
+            .collect();
+        let data = self
+            .names
+            .into_iter()
+            .zip(self.series.into_iter().map(|series| {
+                // categories are sorted as `Series::as_type` sorts them
+                match series.d_type() {
+                    DType::Category => series.sort_categories(),
+                    _ => series,
+                }
+            }));
+        DataFrame {
+            headers,
+            data: data.collect(),
+            index: None,
+            labels: None,
+        }
+    }
+}
+
+/// Iterator over the rows of a csv source in data frames of at most
+/// `chunk_size` rows, created with `read_csv_chunked`.
+///
+/// Column types are inferred from the first chunk only, or taken from
+/// `CsvOptions::dtypes`, and every later chunk is converted into the same
+/// types. Rows are indexed by their position in the whole source.
+pub(crate) struct CsvChunks<R> {
+    records: Records<R>,
+    options: CsvOptions,
+    chunk_size: usize,
+    /// names of the loaded columns, read before the first chunk
+    names: Option<Vec<String>>,
+    /// number of fields of a record
+    width: usize,
+    /// positions of the loaded columns if `CsvOptions::columns` is set
+    positions: Option<Vec<usize>>,
+    /// the first record of a source without headers, which holds data
+    pending: Option<Record>,
+    /// type of every column, known once the first chunk has been read
+    d_types: Option<Vec<(String, DType)>>,
+    /// number of rows in the chunks read so far
+    rows: usize,
+    done: bool,
+}
+
+impl<R: BufRead> CsvChunks<R> {
+    /// column names, `None` if the source has no records at all
+    fn names(&mut self) -> Result<Option<Vec<String>>> {
+        if self.names.is_none() {
+            let Some(first) = self.records.next().transpose()? else {
+                return Ok(None);
+            };
+            self.width = first.len();
+            let names = match self.options.headers {
+                true => unique_names(first.fields().map(|(name, _)| name.to_string()).collect()),
+                false => {
+                    self.pending = Some(first);
+                    (0..self.width).map(|idx| idx.to_string()).collect()
+                }
+            };
+            self.names = Some(match &self.options.columns {
+                Some(columns) => {
+                    let mut positions = columns
+                        .iter()
+                        .map(|column| {
+                            names
+                                .iter()
+                                .position(|name| name == column)
+                                .ok_or_else(|| PandasError::ColumnNotFound(column.clone()))
+                        })
+                        .collect::<Result<Vec<usize>>>()?;
+                    positions.sort_unstable();
+                    positions.dedup();
+                    let names = positions.iter().map(|&p| names[p].clone()).collect();
+                    self.positions = Some(positions);
+                    names
+                }
+                None => names,
+            });
+        }
+        Ok(self.names.clone())
+    }
+
+    /// the explicit types of the loaded columns
+    fn dtypes(&self) -> Vec<(String, DType)> {
+        let names = self.names.as_deref().unwrap_or_default();
+        self.options
+            .dtypes
+            .iter()
+            .filter(|(name, _)| self.options.columns.is_none() || names.contains(name))
+            .cloned()
+            .collect()
+    }
+
+    /// A data frame with the columns of the source and no rows, for sources
+    /// that only have a header.
+    pub(crate) fn empty(&mut self) -> Result<DataFrame> {
+        let Some(names) = self.names()? else {
+            return Ok(DataFrame::new(Vec::<(String, Vec<String>)>::new()));
+        };
+        let mut df = DataFrame::new(
+            names
+                .into_iter()
+                .map(|name| (name, vec![]))
+                .collect::<Vec<(String, Vec<String>)>>(),
+        );
+        df.as_type(self.dtypes())?;
+        Ok(df)
+    }
+
+    /// Infers the types of the first chunk, or of the whole source when it is
+    /// read in parallel, and remembers them for the later chunks.
+    fn convert(&mut self, df: &mut DataFrame) -> Result<()> {
+        let options = &self.options;
+        if options.infer_types {
+            let headers = df
+                .headers
+                .iter()
+                .filter(|h| !options.dtypes.iter().any(|(name, _)| name == &h.name))
+                .collect();
+            let inferred = parallel::map(parallel::threads(), headers, |h| {
+                (
+                    h.name.clone(),
+                    df.data[&h.name].infer_type(options.infer_rows),
+                )
+            });
+            df.as_type(inferred)?;
+        }
+        df.as_type(self.dtypes())?;
+        self.d_types = Some(
+            df.headers
+                .iter()
+                .map(|h| (h.name.clone(), h.d_type))
+                .collect(),
+        );
+        Ok(())
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<DataFrame>> {
+        let Some(names) = self.names()? else {
+            return Ok(None);
+        };
+        let offset = self.rows;
+        let positions = self.positions.as_deref();
+        let mut columns = Columns::new(&names, self.d_types.as_deref());
+        let mut record = Record::default();
+        while columns.rows < self.chunk_size {
+            match self.pending.take() {
+                Some(pending) => record = pending,
+                None if self.records.read_record(&mut record)? => {}
+                None => break,
+            }
+            // conversion errors name the row within the whole source
+            columns
+                .push(&record, self.width, positions, &self.options)
+                .map_err(|err| match err {
+                    PandasError::Conversion {
+                        column,
+                        row,
+                        value,
+                        d_type,
+                    } => PandasError::Conversion {
+                        column,
+                        row: row + offset,
+                        value,
+                        d_type,
+                    },
+                    err => err,
+                })?;
+        }
+        let len = columns.rows;
+        if len == 0 {
+            return Ok(None);
+        }
+
+        let mut df = columns.into_frame();
+        if self.d_types.is_none() {
+            self.convert(&mut df)?;
+        }
+        if offset > 0 {
+            df.index = Some((offset..offset + len).collect());
+        }
+        self.rows += len;
+        Ok(Some(df))
+    }
+}
+
+impl<R: BufRead> Iterator for CsvChunks<R> {
+    type Item = Result<DataFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let chunk = self.next_chunk().transpose();
+        // stop after the last chunk or the first error
+        self.done = !matches!(chunk, Some(Ok(_)));
+        chunk
+    }
+}
+
+/// Reads a csv document from any buffered reader in data frames of at most
+/// `chunk_size` rows, so that sources larger than memory can be processed
+/// one chunk at a time.
+///
+/// ```ignore
+/// let file = BufReader::new(File::open("exports.csv")?);
+/// for chunk in read_csv_chunked(file, &CsvOptions::new(), 100_000) {
+///     let chunk = chunk?;
+///     total += chunk.shape().0;
+/// }
+/// ```
+pub(crate) fn read_csv_chunked<R: BufRead>(
+    reader: R,
+    options: &CsvOptions,
+    chunk_size: usize,
+) -> CsvChunks<R> {
+    CsvChunks {
+        records: Records::new(reader, options.clone()),
+        options: options.clone(),
+        chunk_size: chunk_size.max(1),
+        names: None,
+        width: 0,
+        positions: None,
+        pending: None,
+        d_types: None,
+        rows: 0,
+        done: false,
+    }
+}
+
+/// Quoting state at the end of the raw `line`, started in `state` and
+/// following the rules of `Records`. The line ends a record unless the state
+/// is `State::Quoted`, in which case a quoted field continues on the next line.
+fn scan_line(mut state: State, line: &str, options: &CsvOptions) -> State {
+    let (delimiter, quote, trim) = (options.delimiter, options.quote, options.trim);
+    let text = line.strip_suffix('\n').unwrap_or(line);
+    let text = text.strip_suffix('\r').unwrap_or(text);
+    if state == State::Start && options.comment.is_some_and(|c| text.starts_with(c)) {
+        return State::Start;
+    }
+    for ch in text.chars() {
+        state = match state {
+            State::Start if ch == quote => State::Quoted,
+            State::Start | State::Unquoted if ch == delimiter => State::Start,
+            State::Start if trim && ch.is_whitespace() => State::Start,
+            State::Start | State::Unquoted => State::Unquoted,
+            State::Quoted if ch == quote => State::QuoteInQuoted,
+            State::Quoted => State::Quoted,
+            State::QuoteInQuoted if ch == quote => State::Quoted,
+            State::QuoteInQuoted | State::AfterQuoted if ch == delimiter => State::Start,
+            // the parser fails on any other character, records after it are
+            // never read
+            State::QuoteInQuoted | State::AfterQuoted => State::AfterQuoted,
+        };
+    }
+    match state {
+        State::Quoted => State::Quoted,
+        _ => State::Start,
+    }
+}
+
+impl<R: BufRead> Records<R> {
+    /// Reads the raw text of whole records until at least `size` bytes have
+    /// been read or the source ends, see `scan_line`.
+    fn read_block(&mut self, size: usize) -> Result<String> {
+        let mut text = String::new();
+        let mut state = State::Start;
+        while text.len() < size || state == State::Quoted {
+            let start = text.len();
+            let read = self.reader.read_line(&mut text)?;
+            if read == 0 {
+                break;
+            }
+            self.line += 1;
+            state = scan_line(state, &text[start..], &self.options);
+        }
+        Ok(text)
+    }
+}
+
+/// Byte offsets at which `body` can be split into about `parts` runs of whole
+/// records, each with the number of lines before it, see `scan_line`.
+fn record_boundaries(body: &str, parts: usize, options: &CsvOptions) -> Vec<(usize, usize)> {
+    let size = body.len().div_ceil(parts.max(1)).max(1);
+    let mut boundaries = vec![(0, 0)];
+    let mut state = State::Start;
+    let mut offset = 0;
+    for (lines, line) in body.split_inclusive('\n').enumerate() {
+        offset += line.len();
+        state = scan_line(state, line, options);
+        if state == State::Start && offset >= size * boundaries.len() && offset < body.len() {
+            boundaries.push((offset, lines + 1));
+        }
+    }
+    boundaries
+}
+
+/// appends the values of the `Str` series `other` to `series`
+fn append_str(series: &mut Series, other: &Series) {
+    for idx in 0..other.len() {
+        match other.is_valid(idx) {
+            true => series.values.push_str(other.values.str_at(idx)),
+            false => series.values.push_default(),
+        }
+        series.validity.push(other.is_valid(idx));
+    }
+}
+
+/// Reads a whole csv document on up to `threads` threads. The records after
+/// the header are read in blocks of about `threads * run_bytes` bytes, which
+/// are split into runs of whole records that are parsed in parallel and joined
+/// in order. The result and the first error are the same as with a single
+/// thread.
+fn read_parallel<R: BufRead>(
+    reader: R,
+    options: &CsvOptions,
+    threads: usize,
+    run_bytes: usize,
+) -> Result<DataFrame> {
+    let mut chunks = read_csv_chunked(reader, options, usize::MAX);
+    let Some(names) = chunks.names()? else {
+        return chunks.empty();
+    };
+    let (width, positions) = (chunks.width, chunks.positions.clone());
+    let positions = positions.as_deref();
+    let mut columns = Columns::new(&names, None);
+    // the first record of a source without headers has already been read
+    if let Some(first) = chunks.pending.take() {
+        columns.push(&first, width, positions, options)?;
+    }
+    loop {
+        let line = chunks.records.line;
+        let block = chunks.records.read_block(threads.max(1) * run_bytes)?;
+        if block.is_empty() {
+            break;
+        }
+        let parts = threads.min(block.len().div_ceil(run_bytes));
+        let boundaries = record_boundaries(&block, parts, options);
+        let runs = boundaries
+            .iter()
+            .zip(
+                boundaries
+                    .iter()
+                    .skip(1)
+                    .map(|(end, _)| *end)
+                    .chain([block.len()]),
+            )
+            .map(|(&(begin, before), end)| (&block[begin..end], line + before))
+            .collect::<Vec<_>>();
+        let runs = parallel::map(runs.len(), runs, |(run, line)| {
+            let mut records = Records {
+                line,
+                skipped: true,
+                ..Records::new(run.as_bytes(), options.clone())
+            };
+            let mut run = Columns::new(&names, None);
+            let mut record = Record::default();
+            while records.read_record(&mut record)? {
+                run.push(&record, width, positions, options)?;
+            }
+            Ok(run)
+        })
+        .into_iter()
+        .collect::<Result<Vec<Columns>>>()?;
+        runs.iter().for_each(|run| columns.append(run));
+    }
+    if columns.rows == 0 {
+        return chunks.empty();
+    }
+    let mut df = columns.into_frame();
+    chunks.convert(&mut df)?;
+    Ok(df)
+}
+
+/// Reads a csv document from any buffered reader into a `DataFrame`, on
+/// several threads for large sources, see `set_threads`.
+pub(crate) fn read_csv_from<R: BufRead>(reader: R, options: &CsvOptions) -> Result<DataFrame> {
+    let threads = parallel::threads();
+    if threads > 1 {
+        return read_parallel(reader, options, threads, PARALLEL_BYTES);
+    }
+    let mut chunks = read_csv_chunked(reader, options, usize::MAX);
+    match chunks.next() {
+        Some(df) => df,
+        None => chunks.empty(),
+    }
+}
+
+/// Reads a csv file into a `DataFrame` using the provided options
+pub(crate) fn read_csv_with(path: &str, options: &CsvOptions) -> Result<DataFrame> {
+    read_csv_from(BufReader::new(File::open(path)?), options)
+}
+
+/// Reads a comma separated csv file into a `DataFrame`, `headers` tells
+/// whether the first line contains column names.
+///
+/// This function panics if the file can not be read or is malformed, use
+/// `read_csv_with` to handle the error instead.
+pub(crate) fn read_csv(path: &str, headers: bool) -> DataFrame {
+    read_csv_with(path, &CsvOptions::new().headers(headers)).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Quotes a field if it would not be read back as the same value: when it
+/// contains the delimiter, a quote or a line break, has surrounding whitespace
+/// or looks like a missing value.
+fn quote_field(value: &str, options: &CsvOptions) -> String {
+    let needs_quotes = value.is_empty()
+        || value.contains([options.delimiter, options.quote, '\n', '\r'])
+        || value.trim() != value
+        || options.na_values.iter().any(|na| na == value);
+    match needs_quotes {
+        true => {
+            let quote = options.quote.to_string();
+            let escaped = value.replace(&quote, &quote.repeat(2));
+            format!("{quote}{escaped}{quote}")
+        }
+        false => value.to_string(),
+    }
+}
+
+impl DataFrame {
+    /// Writes the data frame as csv to any writer, columns are written in
+    /// `headers` order and missing values as `options.na_rep`.
+    pub(crate) fn write_csv<W: Write>(&self, writer: W, options: &CsvOptions) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        let delimiter = options.delimiter.to_string();
+        if options.headers {
+            let names = self
+                .headers
+                .iter()
+                .map(|h| quote_field(&h.name, options))
+                .collect::<Vec<String>>();
+            writeln!(writer, "{}", names.join(&delimiter))?;
+        }
+        for row in 0..self.shape().0 {
+            let fields = self
+                .get_item_at(row)
+                .iter()
+                .map(|cell| match cell {
+                    // `NaN`, `inf` and `-inf` are read back as floats, the
+                    // debug format keeps the decimal point of round floats
+                    Some(Cell::Float(v)) if v.is_nan() => String::from("NaN"),
+                    Some(Cell::Float(v)) if v.is_infinite() => v.to_string(),
+                    Some(Cell::Float(v)) => format!("{v:?}"),
+                    Some(cell) => quote_field(&cell.to_string(), options),
+                    None => options.na_rep.clone(),
+                })
+                .collect::<Vec<String>>();
+            writeln!(writer, "{}", fields.join(&delimiter))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the data frame to a csv file using the provided options
+    pub(crate) fn to_csv_with(&self, path: &str, options: &CsvOptions) -> Result<()> {
+        self.write_csv(File::create(path)?, options)
+    }
+
+    /// Writes the data frame to a comma separated csv file with a header line
+    pub(crate) fn to_csv(&self, path: &str) -> Result<()> {
+        self.to_csv_with(path, &CsvOptions::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(source: &str, options: &CsvOptions) -> Result<DataFrame> {
+        read_csv_from(source.as_bytes(), options)
+    }
+
+    fn cell(df: &DataFrame, column: &str, row: usize) -> Option<String> {
+        df.column(column).unwrap()[row]
+            .as_ref()
+            .map(|c| c.to_string())
+    }
+
+    #[test]
+    fn test_quoted_fields() {
+        let source = "name,quote\n\"Doe, John\",\"He said \"\"hi\"\"\"\n";
+        let df = read(source, &CsvOptions::new()).unwrap();
+        assert_eq!(df.shape(), (1, 2));
+        assert_eq!(cell(&df, "name", 0), Some("Doe, John".to_string()));
+        assert_eq!(cell(&df, "quote", 0), Some("He said \"hi\"".to_string()));
+    }
+
+    #[test]
+    fn test_multiline_record() {
+        let source = "id,text\r\n1,\"first\nsecond\"\r\n2,plain\r\n";
+        let df = read(source, &CsvOptions::new()).unwrap();
+        assert_eq!(df.shape(), (2, 2));
+        assert_eq!(cell(&df, "text", 0), Some("first\nsecond".to_string()));
+        assert_eq!(cell(&df, "id", 1), Some("2".to_string()));
+    }
+
+    #[test]
+    fn test_delimiter_comment_and_skip_rows() {
+        let source = "generated file\n# a comment\na|b\n1|2\n\n# another\n3|\n";
+        let options = CsvOptions::new().delimiter('|').comment('#').skip_rows(1);
+        let df = read(source, &options).unwrap();
+        assert_eq!(df.shape(), (2, 2));
+        assert_eq!(cell(&df, "b", 0), Some("2".to_string()));
+        assert_eq!(cell(&df, "b", 1), None);
+    }
+
+    #[test]
+    fn test_tab_delimiter_keeps_inner_spaces() {
+        let options = CsvOptions::new().delimiter('\t');
+        let df = read("a\tb\n x y \t z\n", &options).unwrap();
+        assert_eq!(cell(&df, "a", 0), Some("x y".to_string()));
+        assert_eq!(cell(&df, "b", 0), Some("z".to_string()));
+    }
+
+    #[test]
+    fn test_na_values() {
+        let options = CsvOptions::new().infer_types(false);
+        let source = "a,b,c\nNA,null,\n\"NA\",x,-\n";
+        let df = read(source, &options).unwrap();
+        assert_eq!(cell(&df, "a", 0), None);
+        assert_eq!(cell(&df, "b", 0), None);
+        assert_eq!(cell(&df, "c", 0), None);
+        // quoted values are never missing
+        assert_eq!(cell(&df, "a", 1), Some("NA".to_string()));
+
+        let df = read(source, &options.na_values(["-"])).unwrap();
+        assert_eq!(cell(&df, "a", 0), Some("NA".to_string()));
+        assert_eq!(cell(&df, "c", 1), None);
+    }
+
+    #[test]
+    fn test_columns() {
+        // `x` in column a would fail the conversion of b if it were loaded
+        let source = "a,b,c\nx,2,3.5\ny,4\n";
+        let options = CsvOptions::new().columns(["c", "b"]).dtype("a", DType::Int);
+        let df = read(source, &options).unwrap();
+        let names: Vec<&str> = df.headers.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, ["b", "c"]);
+        assert_eq!(df.header("b").unwrap().d_type, DType::Int);
+        assert_eq!(cell(&df, "c", 0), Some("3.5".to_string()));
+        assert_eq!(cell(&df, "c", 1), None);
+
+        let chunks = read_csv_chunked(source.as_bytes(), &options, 1);
+        assert_eq!(
+            chunks
+                .map(|chunk| chunk.unwrap().shape())
+                .collect::<Vec<_>>(),
+            [(1, 2); 2]
+        );
+        assert!(matches!(
+            read(source, &CsvOptions::new().columns(["d"])),
+            Err(PandasError::ColumnNotFound(_))
+        ));
+        let df = read(
+            "1,2\n3,4\n",
+            &CsvOptions::new().headers(false).columns(["1"]),
+        )
+        .unwrap();
+        assert_eq!(cell(&df, "1", 1), Some("4".to_string()));
+    }
+
+    #[test]
+    fn test_without_headers() {
+        let df = read("1,2\n3,4\n", &CsvOptions::new().headers(false)).unwrap();
+        assert_eq!(df.shape(), (2, 2));
+        assert_eq!(cell(&df, "1", 1), Some("4".to_string()));
+    }
+
+    #[test]
+    fn test_malformed_records() {
+        let err = read("a,b\n1,2,3\n", &CsvOptions::new()).unwrap_err();
+        assert!(matches!(err, PandasError::Csv { line: 2, .. }));
+
+        let err = read("a,b\n1,\"open\n", &CsvOptions::new()).unwrap_err();
+        assert!(matches!(err, PandasError::Csv { line: 2, .. }));
+
+        let err = read("a,b\n\"x\"y,1\n", &CsvOptions::new()).unwrap_err();
+        assert!(matches!(err, PandasError::Csv { line: 2, .. }));
+    }
+
+    #[test]
+    fn test_type_inference() {
+        let source = "SN,Name,CGPA,Graduated\n1,John,3.81,true\n2,Jane,,0\n";
+        let df = read(source, &CsvOptions::new()).unwrap();
+        let types: Vec<DType> = df.headers.iter().map(|h| h.d_type).collect();
+        assert_eq!(types, [DType::Int, DType::Str, DType::Float, DType::Bool]);
+        assert!(matches!(
+            df.column("Graduated").unwrap()[1],
+            Some(Cell::Bool(false))
+        ));
+
+        let options = CsvOptions::new()
+            .dtype("SN", DType::Str)
+            .dtype("CGPA", DType::Float);
+        let df = read(source, &options.infer_types(false)).unwrap();
+        let types: Vec<DType> = df.headers.iter().map(|h| h.d_type).collect();
+        assert_eq!(types, [DType::Str, DType::Str, DType::Float, DType::Str]);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let source =
+            "id,name,score,note\n1,\"Doe, John\",3.0,\"say \"\"hi\"\"\"\n2,\"NA\",,\" padded \"\n";
+        let df = read(source, &CsvOptions::new()).unwrap();
+        let mut output = vec![];
+        df.write_csv(&mut output, &CsvOptions::new()).unwrap();
+        assert_eq!(String::from_utf8(output.clone()).unwrap(), source);
+
+        let copy = read(&String::from_utf8(output).unwrap(), &CsvOptions::new()).unwrap();
+        assert_eq!(copy.headers[2].d_type, DType::Float);
+        assert_eq!(cell(&copy, "name", 1), Some("NA".to_string()));
+
+        let mut output = vec![];
+        let options = CsvOptions::new().delimiter(';').na_rep("-").headers(false);
+        df.write_csv(&mut output, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "1;Doe, John;3.0;\"say \"\"hi\"\"\"\n2;\"NA\";-;\" padded \"\n"
+        );
+    }
+
+    #[test]
+    fn test_write_special_floats() {
+        let values = [1.5, f64::NAN, f64::INFINITY, f64::NEG_INFINITY];
+        let x = values.into_iter().map(|v| Some(Cell::Float(v))).collect();
+        let df = DataFrame::new(Vec::<(String, Vec<String>)>::new())
+            .with_column("x", x)
+            .unwrap();
+        let mut output = vec![];
+        df.write_csv(&mut output, &CsvOptions::new()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, "x\n1.5\nNaN\ninf\n-inf\n");
+
+        let copy = read(&output, &CsvOptions::new()).unwrap();
+        assert_eq!(copy.headers[0].d_type, DType::Float);
+        let x = copy.column("x").unwrap();
+        assert!(matches!(x.get(1), Some(Cell::Float(v)) if v.is_nan()));
+        assert_eq!(x.get(2), Some(Cell::Float(f64::INFINITY)));
+        assert_eq!(x.get(3), Some(Cell::Float(f64::NEG_INFINITY)));
+    }
+
+    #[test]
+    fn test_duplicate_headers() {
+        let df = read("a,a,a\n1,2,3\n", &CsvOptions::new()).unwrap();
+        let names: Vec<String> = df.headers.iter().map(|h| h.name.clone()).collect();
+        assert_eq!(names, vec!["a", "a.1", "a.2"]);
+    }
+
+    #[test]
+    fn test_read_chunked() {
+        let source = "id,score,name\n1,3.5,a\n2,,b\n3,4,c\n4,2.5,d\n5,1,e\n";
+        let chunks = read_csv_chunked(source.as_bytes(), &CsvOptions::new(), 2)
+            .collect::<Result<Vec<DataFrame>>>()
+            .unwrap();
+        assert_eq!(
+            chunks.iter().map(|c| c.shape()).collect::<Vec<_>>(),
+            vec![(2, 3), (2, 3), (1, 3)]
+        );
+        // types inferred from the first chunk are kept by the later ones
+        assert!(chunks
+            .iter()
+            .all(|c| c.header("score").unwrap().d_type == DType::Float));
+        assert_eq!(
+            chunks[1].column("score").unwrap()[1],
+            Some(Cell::Float(2.5))
+        );
+        assert_eq!(chunks[2].index(), vec![4]);
+
+        // later chunks parse their fields straight into the known types
+        let options = CsvOptions::new().dtype("name", DType::Category);
+        let source = "id,name\n1,b\n2,a\n3,\n4,d\n5,c\n";
+        let chunks = read_csv_chunked(source.as_bytes(), &options, 3)
+            .collect::<Result<Vec<DataFrame>>>()
+            .unwrap();
+        let name = chunks[1].column("name").unwrap();
+        assert_eq!(name.categories().unwrap(), ["c", "d"]);
+        assert_eq!(name[1], Some(Cell::Category("c".into())));
+        assert_eq!(chunks[1].column("id").unwrap()[0], Some(Cell::Int(4)));
+
+        let options = CsvOptions::new().headers(false);
+        let chunks = read_csv_chunked("x,1\ny,2\nz,3\n".as_bytes(), &options, 2)
+            .collect::<Result<Vec<DataFrame>>>()
+            .unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(cell(&chunks[0], "0", 0), Some("x".into()));
+        assert_eq!(chunks[1].column("1").unwrap()[0], Some(Cell::Int(3)));
+
+        assert_eq!(
+            read_csv_chunked("a,b\n".as_bytes(), &CsvOptions::new(), 2).count(),
+            0
+        );
+        assert_eq!(read("a,b\n", &CsvOptions::new()).unwrap().shape(), (0, 2));
+        assert_eq!(
+            read("", &CsvOptions::new().dtype("a", DType::Int))
+                .unwrap()
+                .shape(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn test_read_parallel() {
+        let serial = |source: &str, options: &CsvOptions| {
+            let mut chunks = read_csv_chunked(source.as_bytes(), options, usize::MAX);
+            chunks.next().unwrap_or_else(|| chunks.empty())
+        };
+        let written = |df: &DataFrame| {
+            let mut output = vec![];
+            df.write_csv(&mut output, &CsvOptions::new()).unwrap();
+            let types = df.headers.iter().map(|h| h.d_type).collect::<Vec<_>>();
+            (String::from_utf8(output).unwrap(), types, df.index())
+        };
+        let rows = (0..40)
+            .map(|i| format!("{i},\"line {i}\nof \"\"{i}\"\"\",{}.5\n", i % 7))
+            .collect::<String>();
+        let sources = [
+            (format!("id,text,score\n{rows}"), CsvOptions::new()),
+            (
+                format!(
+                    "skipped\n# comment\nid;text;score\n{}",
+                    rows.replace(',', ";")
+                ),
+                CsvOptions::new().delimiter(';').comment('#').skip_rows(1),
+            ),
+            (rows.clone(), CsvOptions::new().headers(false)),
+            (
+                format!("id,text,score\n{rows}"),
+                CsvOptions::new().columns(["score", "id"]),
+            ),
+            (String::from("id,text\n"), CsvOptions::new()),
+            (String::new(), CsvOptions::new()),
+        ];
+        for (source, options) in &sources {
+            let expected = written(&serial(source, options).unwrap());
+            for threads in [1, 2, 3, 8] {
+                for run_bytes in [1, 50, 300, 1 << 20] {
+                    let df = read_parallel(source.as_bytes(), options, threads, run_bytes).unwrap();
+                    assert_eq!(written(&df), expected, "{threads} threads, {run_bytes}");
+                }
+            }
+        }
+
+        let source = format!(
+            "id,score\n{}",
+            (0..30).map(|i| format!("{i},{i}\n")).collect::<String>()
+        );
+        let errors = [
+            source.replace("\n12,12\n", "\n12,\"12\"x\n"),
+            source.replace("\n20,20\n", "\n20,20,20\n"),
+            source.replace("\n25,25\n", "\n25,\"25\n"),
+            source.replace("\n7,7\n", "\n7,seven\n"),
+        ];
+        let options = CsvOptions::new().dtype("score", DType::Int);
+        for source in &errors {
+            let expected = serial(source, &options).unwrap_err().to_string();
+            for (threads, run_bytes) in [(2, 1), (3, 20), (8, 100), (2, 1 << 20)] {
+                let err = read_parallel(source.as_bytes(), &options, threads, run_bytes);
+                assert_eq!(err.unwrap_err().to_string(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_chunked_errors() {
+        let source = "id\n1\n2\nthree\n4\n";
+        let mut chunks = read_csv_chunked(source.as_bytes(), &CsvOptions::new(), 2);
+        assert!(chunks.next().unwrap().is_ok());
+        assert!(matches!(
+            chunks.next().unwrap(),
+            Err(PandasError::Conversion { row: 2, value, .. }) if value == "three"
+        ));
+        assert!(chunks.next().is_none());
+    }
+}
@@ -0,0 +1,741 @@
+use std::ops::{Add, RangeInclusive, Sub};
+
+use super::{
+    error::{PandasError, Result},
+    groupby::aggregate,
+    parallel,
+    storage::Column,
+    Agg, Cell, DType, DataFrame, Header, OnError, Series,
+};
+
+pub(crate) const SECONDS_PER_DAY: i64 = 86_400;
+
+/// values of `Date` cells, the days whose midnight is a valid `DateTime`
+pub(crate) const DATE_RANGE: RangeInclusive<i64> =
+    i64::MIN / SECONDS_PER_DAY..=i64::MAX / SECONDS_PER_DAY;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// formats tried, in order, when text is converted into a `DateTime`
+const DATETIME_FORMATS: [&str; 5] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d",
+];
+
+/// Days since 1970-01-01 of a date of the proleptic gregorian calendar, see
+/// <https://howardhinnant.github.io/date_algorithms.html>.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    // months are counted from march so that leap days come last
+    let month = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// year, month and day of the date `days` after 1970-01-01
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // shifting the epoch to 0000-03-01 overflows an `i64` near its bounds
+    let days = days as i128 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+    let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i128;
+    // a year is shorter than a day has seconds, so it always fits
+    (year as i64, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// day of the week of the date `days` after 1970-01-01, Monday is 0
+fn weekday(days: i64) -> i64 {
+    // 1970-01-01 was a Thursday
+    (days + 3).rem_euclid(7)
+}
+
+/// formats `days` after 1970-01-01 as `YYYY-MM-DD`
+pub(crate) fn format_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// formats `seconds` after 1970-01-01 00:00:00 as `YYYY-MM-DD HH:MM:SS`
+pub(crate) fn format_datetime(seconds: i64) -> String {
+    let time = seconds.rem_euclid(SECONDS_PER_DAY);
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        format_date(seconds.div_euclid(SECONDS_PER_DAY)),
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// parses at most `digits` leading digits of `input`
+fn number(input: &str, digits: usize) -> Option<(i64, &str)> {
+    let len = input
+        .bytes()
+        .take(digits)
+        .take_while(u8::is_ascii_digit)
+        .count();
+    match len {
+        0 => None,
+        len => Some((input[..len].parse().ok()?, &input[len..])),
+    }
+}
+
+/// Parses `value` with a `strftime` like `format` into seconds since
+/// 1970-01-01 00:00:00, `None` if it does not match or is not a valid date.
+///
+/// Supported directives are `%Y` (year), `%y` (two digit year, 1969 to
+/// 2068), `%m` (month), `%b` (abbreviated month name), `%d` (day), `%H`
+/// (hour), `%M` (minute), `%S` (second) and `%%`. Fields missing from the
+/// format default to 1970-01-01 00:00:00.
+pub(crate) fn parse_datetime(value: &str, format: &str) -> Option<i64> {
+    let (mut year, mut month, mut day) = (1970, 1, 1);
+    let (mut hour, mut minute, mut second) = (0, 0, 0);
+    let mut input = value;
+    let mut directives = format.chars();
+    while let Some(ch) = directives.next() {
+        if ch != '%' {
+            input = input.strip_prefix(ch)?;
+            continue;
+        }
+        let directive = directives.next()?;
+        let (parsed, rest) = match directive {
+            '%' => (0, input.strip_prefix('%')?),
+            'Y' => number(input, 4)?,
+            'y' => number(input, 2)?,
+            'b' => {
+                let name = input.get(..3)?;
+                let month = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(name))?;
+                (month as i64 + 1, &input[3..])
+            }
+            _ => number(input, 2)?,
+        };
+        match directive {
+            'Y' => year = parsed,
+            'y' if parsed < 69 => year = 2000 + parsed,
+            'y' => year = 1900 + parsed,
+            'm' | 'b' => month = parsed as u32,
+            'd' => day = parsed as u32,
+            'H' => hour = parsed,
+            'M' => minute = parsed,
+            'S' => second = parsed,
+            '%' => {}
+            _ => return None,
+        }
+        input = rest;
+    }
+    let valid = input.is_empty()
+        && (1..=12).contains(&month)
+        && (1..=days_in_month(year, month)).contains(&day)
+        && hour < 24
+        && minute < 60
+        && second < 60;
+    valid.then(|| {
+        days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second
+    })
+}
+
+/// Parses ISO 8601 text into the value of a `Date` (days) or `DateTime`
+/// (seconds) cell. Dates are `YYYY-MM-DD`, date times may add `HH:MM` or
+/// `HH:MM:SS` separated by a space or `T`.
+pub(crate) fn parse_iso(value: &str, d_type: DType) -> Option<i64> {
+    match d_type {
+        DType::Date => parse_datetime(value, "%Y-%m-%d").map(|s| s.div_euclid(SECONDS_PER_DAY)),
+        _ => DATETIME_FORMATS
+            .iter()
+            .find_map(|format| parse_datetime(value, format)),
+    }
+}
+
+/// A fixed length of time that can be added to or subtracted from `Date`
+/// and `DateTime` series.
+///
+/// ```ignore
+/// let due = &df["Issued".to_string()] + TimeDelta::days(14);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct TimeDelta {
+    seconds: i64,
+}
+
+impl TimeDelta {
+    pub(crate) fn weeks(weeks: i64) -> Self {
+        Self::days(weeks * 7)
+    }
+
+    pub(crate) fn days(days: i64) -> Self {
+        Self::seconds(days * SECONDS_PER_DAY)
+    }
+
+    pub(crate) fn hours(hours: i64) -> Self {
+        Self::seconds(hours * 3600)
+    }
+
+    pub(crate) fn minutes(minutes: i64) -> Self {
+        Self::seconds(minutes * 60)
+    }
+
+    pub(crate) fn seconds(seconds: i64) -> Self {
+        Self { seconds }
+    }
+}
+
+impl Series {
+    /// seconds since 1970-01-01 00:00:00 of the value at `idx` of a `Date` or
+    /// `DateTime` series, `None` for a date outside of `DATE_RANGE`
+    pub(crate) fn timestamp_at(&self, idx: usize) -> Option<i64> {
+        match (&self.values, self.is_valid(idx)) {
+            (Column::Date(values), true) => values[idx].checked_mul(SECONDS_PER_DAY),
+            (Column::DateTime(values), true) => Some(values[idx]),
+            _ => None,
+        }
+    }
+
+    /// Parses a `Str` or `Category` series into `Date` or `DateTime` values
+    /// with a `strftime` like `format`, see `parse_datetime`. Values that do
+    /// not match are either reported or turned into `None` depending on
+    /// `errors`.
+    ///
+    /// ```ignore
+    /// series.parse_dates(DType::Date, "%d/%m/%Y", OnError::Raise)?;
+    /// ```
+    pub(crate) fn parse_dates(
+        &mut self,
+        d_type: DType,
+        format: &str,
+        errors: OnError,
+    ) -> Result<()> {
+        if !matches!(d_type, DType::Date | DType::DateTime) {
+            return Err(PandasError::DTypeMismatch {
+                expected: DType::DateTime,
+                found: d_type,
+            });
+        }
+        if !matches!(self.d_type(), DType::Str | DType::Category) {
+            return self.as_type_with(d_type, errors);
+        }
+        let mut parsed = Series::with_capacity(d_type, self.len());
+        for row in 0..self.len() {
+            let Some(value) = self.str_at(row) else {
+                parsed.push_cell(None);
+                continue;
+            };
+            let cell = parse_datetime(value, format).map(|seconds| match d_type {
+                DType::Date => Cell::Date(seconds.div_euclid(SECONDS_PER_DAY)),
+                _ => Cell::DateTime(seconds),
+            });
+            if cell.is_none() && errors == OnError::Raise {
+                return Err(PandasError::Conversion {
+                    column: None,
+                    row,
+                    value: value.to_string(),
+                    d_type,
+                });
+            }
+            parsed.push_cell(cell);
+        }
+        parsed.labels = self.labels.clone();
+        *self = parsed;
+        Ok(())
+    }
+
+    /// applies `f` to the seconds since the epoch of every value
+    fn date_component<F>(&self, f: F) -> Result<Series>
+    where
+        F: Fn(i64) -> i64,
+    {
+        if !matches!(self.d_type(), DType::Date | DType::DateTime) {
+            return Err(PandasError::DTypeMismatch {
+                expected: DType::DateTime,
+                found: self.d_type(),
+            });
+        }
+        Ok(Series::from_cells(
+            DType::Int,
+            (0..self.len()).map(|row| self.timestamp_at(row).map(|s| Cell::Int(f(s)))),
+        ))
+    }
+
+    pub(crate) fn year(&self) -> Result<Series> {
+        self.date_component(|s| civil_from_days(s.div_euclid(SECONDS_PER_DAY)).0)
+    }
+
+    /// month of every date, January is 1
+    pub(crate) fn month(&self) -> Result<Series> {
+        self.date_component(|s| civil_from_days(s.div_euclid(SECONDS_PER_DAY)).1 as i64)
+    }
+
+    /// day of the month of every date
+    pub(crate) fn day(&self) -> Result<Series> {
+        self.date_component(|s| civil_from_days(s.div_euclid(SECONDS_PER_DAY)).2 as i64)
+    }
+
+    /// day of the week of every date, Monday is 0 and Sunday is 6
+    pub(crate) fn weekday(&self) -> Result<Series> {
+        self.date_component(|s| weekday(s.div_euclid(SECONDS_PER_DAY)))
+    }
+
+    /// hour of every date time, 0 for `Date` values
+    pub(crate) fn hour(&self) -> Result<Series> {
+        self.date_component(|s| s.rem_euclid(SECONDS_PER_DAY) / 3600)
+    }
+
+    /// Moves every date by `delta`. `Date` values stay dates when `delta` is
+    /// a whole number of days and become `DateTime` values otherwise. Dates
+    /// moved past the range of an `i64` fail with `PandasError::Overflow`.
+    pub(crate) fn shift_dates(&self, delta: TimeDelta) -> Result<Series> {
+        let whole_days = delta.seconds % SECONDS_PER_DAY == 0;
+        let d_type = match self.d_type() {
+            DType::Date if whole_days => DType::Date,
+            DType::Date | DType::DateTime => DType::DateTime,
+            found => {
+                return Err(PandasError::DTypeMismatch {
+                    expected: DType::DateTime,
+                    found,
+                })
+            }
+        };
+        let cells = (0..self.len())
+            .map(|row| {
+                let Some(seconds) = self.timestamp_at(row) else {
+                    return Ok(None);
+                };
+                let seconds = seconds
+                    .checked_add(delta.seconds)
+                    .ok_or_else(|| PandasError::Overflow(format!("date shift at row {row}")))?;
+                Ok(Some(match d_type {
+                    DType::Date => Cell::Date(seconds / SECONDS_PER_DAY),
+                    _ => Cell::DateTime(seconds),
+                }))
+            })
+            .collect::<Result<Vec<Option<Cell>>>>()?;
+        Ok(Series::from_cells(d_type, cells))
+    }
+}
+
+impl Add<TimeDelta> for &Series {
+    type Output = Series;
+
+    fn add(self, delta: TimeDelta) -> Series {
+        self.shift_dates(delta)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+}
+
+impl Sub<TimeDelta> for &Series {
+    type Output = Series;
+
+    fn sub(self, delta: TimeDelta) -> Series {
+        self + TimeDelta::seconds(-delta.seconds)
+    }
+}
+
+impl DataFrame {
+    /// Parses the given `Str` columns into `Date` or `DateTime` values, see
+    /// `DataFrame::as_type_with_format`. No column is modified if any of them
+    /// fails.
+    pub(crate) fn parse_dates<T, S>(
+        &mut self,
+        columns: T,
+        d_type: DType,
+        format: &str,
+        errors: OnError,
+    ) -> Result<()>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        if !matches!(d_type, DType::Date | DType::DateTime) {
+            return Err(PandasError::DTypeMismatch {
+                expected: DType::DateTime,
+                found: d_type,
+            });
+        }
+        let titles = columns
+            .into_iter()
+            .map(|name| (name.as_ref().to_string(), d_type));
+        self.as_type_with_format(titles, format, errors)
+    }
+}
+
+/// Length of the periods `resample` groups rows into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Freq {
+    Minute,
+    Hour,
+    Day,
+    /// weeks starting on Monday
+    Week,
+    Month,
+    Year,
+}
+
+impl Freq {
+    /// start of the period that contains `seconds`
+    fn floor(&self, seconds: i64) -> i64 {
+        let days = seconds.div_euclid(SECONDS_PER_DAY);
+        let (year, month, _) = civil_from_days(days);
+        match self {
+            Freq::Minute => seconds - seconds.rem_euclid(60),
+            Freq::Hour => seconds - seconds.rem_euclid(3600),
+            Freq::Day => days * SECONDS_PER_DAY,
+            Freq::Week => (days - weekday(days)) * SECONDS_PER_DAY,
+            Freq::Month => days_from_civil(year, month, 1) * SECONDS_PER_DAY,
+            Freq::Year => days_from_civil(year, 1, 1) * SECONDS_PER_DAY,
+        }
+    }
+
+    /// start of the period after the one starting at `start`, `None` past
+    /// the last representable date time
+    fn next(&self, start: i64) -> Option<i64> {
+        let (year, month, _) = civil_from_days(start.div_euclid(SECONDS_PER_DAY));
+        match self {
+            Freq::Minute => start.checked_add(60),
+            Freq::Hour => start.checked_add(3600),
+            Freq::Day => start.checked_add(SECONDS_PER_DAY),
+            Freq::Week => start.checked_add(7 * SECONDS_PER_DAY),
+            Freq::Month if month == 12 => {
+                days_from_civil(year + 1, 1, 1).checked_mul(SECONDS_PER_DAY)
+            }
+            Freq::Month => days_from_civil(year, month + 1, 1).checked_mul(SECONDS_PER_DAY),
+            Freq::Year => days_from_civil(year + 1, 1, 1).checked_mul(SECONDS_PER_DAY),
+        }
+    }
+}
+
+/// Rows of a `DataFrame` grouped into periods of a date column, created with
+/// `DataFrame::resample`.
+pub(crate) struct Resampler<'a> {
+    df: &'a DataFrame,
+    on: String,
+    freq: Freq,
+}
+
+impl DataFrame {
+    /// Groups the rows into consecutive periods of length `freq` of the `on`
+    /// column, which must be a `Date` or `DateTime` column.
+    ///
+    /// ```ignore
+    /// let monthly = df
+    ///     .resample("Date", Freq::Month)
+    ///     .agg([("Amount", Agg::Sum), ("Amount", Agg::Count)])?;
+    /// ```
+    pub(crate) fn resample(&self, on: &str, freq: Freq) -> Resampler<'_> {
+        Resampler {
+            df: self,
+            on: on.to_string(),
+            freq,
+        }
+    }
+}
+
+impl Resampler<'_> {
+    /// Computes the aggregations for every period between the first and the
+    /// last date, including periods without rows. The result has the start of
+    /// every period in the `on` column followed by the aggregated columns,
+    /// named as in `GroupBy::agg`. Rows with a missing date are left out.
+    pub(crate) fn agg<T, S>(&self, aggs: T) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = (S, Agg)>,
+        S: AsRef<str>,
+    {
+        let aggs = aggs
+            .into_iter()
+            .map(|(column, agg)| (column.as_ref().to_string(), agg))
+            .collect::<Vec<(String, Agg)>>();
+        let series = self.df.column(&self.on)?;
+        let d_type = match (series.d_type(), self.freq) {
+            (DType::Date, Freq::Minute | Freq::Hour) => DType::DateTime,
+            (DType::Date, _) => DType::Date,
+            (DType::DateTime, _) => DType::DateTime,
+            (found, _) => {
+                return Err(PandasError::DTypeMismatch {
+                    expected: DType::DateTime,
+                    found,
+                })
+            }
+        };
+
+        let periods = (0..series.len())
+            .map(|row| series.timestamp_at(row).map(|s| self.freq.floor(s)))
+            .collect::<Vec<Option<i64>>>();
+        let mut starts = vec![];
+        let first = periods.iter().flatten().min().copied();
+        let last = periods.iter().flatten().max().copied();
+        if let (Some(mut start), Some(last)) = (first, last) {
+            while start <= last {
+                starts.push(start);
+                match self.freq.next(start) {
+                    Some(next) => start = next,
+                    None => break,
+                }
+            }
+        }
+        let mut groups: Vec<Vec<usize>> = vec![vec![]; starts.len()];
+        for (row, period) in periods.iter().enumerate() {
+            if let Some(period) = period {
+                groups[starts.partition_point(|s| s < period)].push(row);
+            }
+        }
+
+        let mut df = DataFrame::new(Vec::<(String, Vec<String>)>::new());
+        df.headers.push(Header {
+            name: self.on.clone(),
+            d_type,
+        });
+        df.data.insert(
+            self.on.clone(),
+            Series::from_cells(
+                d_type,
+                starts.iter().map(|&s| match d_type {
+                    DType::Date => Some(Cell::Date(s / SECONDS_PER_DAY)),
+                    _ => Some(Cell::DateTime(s)),
+                }),
+            ),
+        );
+        aggregate(
+            self.df,
+            &groups,
+            &aggs,
+            std::slice::from_ref(&self.on),
+            &mut df,
+            parallel::threads(),
+        )?;
+        Ok(df)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::csv::{read_csv_from, CsvOptions};
+
+    fn sales() -> DataFrame {
+        let source = "Date,Time,Amount\n\
+                      2024-01-30,2024-01-30 09:15:00,10\n\
+                      2024-01-31,2024-01-31T18:40:00,20\n\
+                      2024-03-02,2024-03-02 23:59,5\n\
+                      ,,7\n";
+        let options = CsvOptions::new()
+            .dtype("Date", DType::Date)
+            .dtype("Time", DType::DateTime);
+        read_csv_from(source.as_bytes(), &options).unwrap()
+    }
+
+    fn column(series: &Series) -> Vec<Option<Cell>> {
+        series.iter().collect()
+    }
+
+    #[test]
+    fn test_civil_dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+        for days in [-800_000, -1, 0, 59, 10_000, 2_932_896] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        assert_eq!(format_datetime(-1), "1969-12-31 23:59:59");
+    }
+
+    #[test]
+    fn test_date_range() {
+        assert_eq!(Cell::Int(i64::MAX).cast(DType::Date), None);
+        let last = *DATE_RANGE.end();
+        assert_eq!(Cell::Int(last).cast(DType::Date), Some(Cell::Date(last)));
+        assert!(Cell::Date(last).cast(DType::DateTime).is_some());
+        assert_eq!(Cell::Date(i64::MIN).cast(DType::DateTime), None);
+        assert_eq!(Cell::DateTime(i64::MIN).cast(DType::Date), None);
+        for days in [i64::MIN, i64::MAX] {
+            assert!(!format_date(days).is_empty());
+        }
+
+        let mut df = DataFrame::new([(
+            String::from("Days"),
+            vec![String::from("1"), i64::MAX.to_string()],
+        )]);
+        df.as_type([(String::from("Days"), DType::Int)]).unwrap();
+        let days = [(String::from("Days"), DType::Date)];
+        let err = df.clone().as_type(days.clone()).unwrap_err();
+        assert!(matches!(err, PandasError::Conversion { row: 1, .. }));
+        df.as_type_with(days, OnError::Coerce).unwrap();
+        assert_eq!(
+            column(df.column("Days").unwrap()),
+            vec![Some(Cell::Date(1)), None]
+        );
+
+        let dates = Series::from_cells(DType::Date, [Some(Cell::Date(i64::MAX))]);
+        assert_eq!(dates.timestamp_at(0), None);
+        let times = Series::from_cells(DType::DateTime, [Some(Cell::DateTime(i64::MAX - 10))]);
+        let err = times.shift_dates(TimeDelta::minutes(1)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "integer overflow in the date shift at row 0"
+        );
+    }
+
+    #[test]
+    fn test_parse_dates() {
+        assert_eq!(parse_iso("2024-02-29", DType::Date), Some(19_782));
+        assert_eq!(parse_iso("2023-02-29", DType::Date), None);
+        assert_eq!(parse_iso("2024-01-01 25:00", DType::DateTime), None);
+        assert_eq!(
+            parse_datetime("03 Mar 24, 7h", "%d %b %y, %Hh"),
+            Some(days_from_civil(2024, 3, 3) * SECONDS_PER_DAY + 7 * 3600)
+        );
+
+        let df = sales();
+        assert_eq!(df.header("Date").unwrap().d_type, DType::Date);
+        assert_eq!(
+            df.column("Date").unwrap().get(0).unwrap().to_string(),
+            "2024-01-30"
+        );
+        assert_eq!(
+            df.column("Time").unwrap().get(2).unwrap().to_string(),
+            "2024-03-02 23:59:00"
+        );
+
+        let mut df = DataFrame::new([(
+            String::from("Joined"),
+            vec![String::from("31/12/2023"), String::from("13/31/2023")],
+        )]);
+        let err = df
+            .parse_dates(["Joined"], DType::Date, "%d/%m/%Y", OnError::Raise)
+            .unwrap_err();
+        assert!(matches!(err, PandasError::Conversion { row: 1, .. }));
+        df.parse_dates(["Joined"], DType::Date, "%d/%m/%Y", OnError::Coerce)
+            .unwrap();
+        assert_eq!(
+            column(df.column("Joined").unwrap()),
+            vec![Some(Cell::Date(days_from_civil(2023, 12, 31))), None]
+        );
+    }
+
+    #[test]
+    fn test_as_type_with_format() {
+        let source = "Name,Joined,Age\n\
+                      John,31/12/2023,20\n\
+                      Jane,01/02/2024,19\n";
+        let options = CsvOptions::new().dtype("Joined", DType::Str);
+        let mut df = read_csv_from(source.as_bytes(), &options)
+            .unwrap()
+            .set_index("Name")
+            .unwrap();
+        assert!(df
+            .clone()
+            .as_type([(String::from("Joined"), DType::Date)])
+            .is_err());
+        df.as_type_with_format(
+            [
+                (String::from("Joined"), DType::Date),
+                (String::from("Age"), DType::Float),
+            ],
+            "%d/%m/%Y",
+            OnError::Raise,
+        )
+        .unwrap();
+        assert_eq!(df.header("Joined").unwrap().d_type, DType::Date);
+        assert_eq!(
+            column(df.column("Joined").unwrap()),
+            vec![
+                Some(Cell::Date(days_from_civil(2023, 12, 31))),
+                Some(Cell::Date(days_from_civil(2024, 2, 1)))
+            ]
+        );
+        assert_eq!(
+            column(df.column("Age").unwrap()),
+            vec![Some(Cell::Float(20.0)), Some(Cell::Float(19.0))]
+        );
+        assert!(df.column("Joined").unwrap().labels().is_some());
+    }
+
+    #[test]
+    fn test_components_and_arithmetic() {
+        let df = sales();
+        let time = df.column("Time").unwrap();
+        let ints = |values: &[i64]| {
+            values
+                .iter()
+                .map(|v| Some(Cell::Int(*v)))
+                .chain([None])
+                .collect::<Vec<Option<Cell>>>()
+        };
+        assert_eq!(column(&time.year().unwrap()), ints(&[2024, 2024, 2024]));
+        assert_eq!(column(&time.month().unwrap()), ints(&[1, 1, 3]));
+        assert_eq!(column(&time.day().unwrap()), ints(&[30, 31, 2]));
+        assert_eq!(column(&time.weekday().unwrap()), ints(&[1, 2, 5]));
+        assert_eq!(column(&time.hour().unwrap()), ints(&[9, 18, 23]));
+        assert!(df.column("Amount").unwrap().year().is_err());
+
+        let date = df.column("Date").unwrap();
+        let later = date + TimeDelta::days(30);
+        assert_eq!(later.d_type(), DType::Date);
+        assert_eq!(later.get(1).unwrap().to_string(), "2024-03-01");
+        let earlier = date - TimeDelta::hours(1);
+        assert_eq!(earlier.d_type(), DType::DateTime);
+        assert_eq!(earlier.get(0).unwrap().to_string(), "2024-01-29 23:00:00");
+        assert_eq!(
+            (time + TimeDelta::minutes(1)).get(2).unwrap().to_string(),
+            "2024-03-03 00:00:00"
+        );
+    }
+
+    #[test]
+    fn test_resample() {
+        let df = sales()
+            .resample("Date", Freq::Month)
+            .agg([("Amount", Agg::Sum), ("Amount", Agg::Count)])
+            .unwrap();
+        assert_eq!(df.shape(), (3, 3));
+        assert_eq!(
+            column(df.column("Date").unwrap())
+                .iter()
+                .map(|c| c.as_ref().unwrap().to_string())
+                .collect::<Vec<String>>(),
+            vec!["2024-01-01", "2024-02-01", "2024-03-01"]
+        );
+        assert_eq!(
+            column(df.column("Amount_sum").unwrap()),
+            [30, 0, 5].map(|v| Some(Cell::Int(v)))
+        );
+
+        let df = sales()
+            .resample("Time", Freq::Week)
+            .agg([("Amount", Agg::Max)])
+            .unwrap();
+        assert_eq!(df.shape(), (5, 2));
+        assert_eq!(
+            df.column("Time").unwrap().get(0).unwrap().to_string(),
+            "2024-01-29 00:00:00"
+        );
+        assert_eq!(df.column("Amount").unwrap().get(1), None);
+        assert!(sales()
+            .resample("Amount", Freq::Day)
+            .agg([("Amount", Agg::Sum)])
+            .is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use std::fmt::Display;
+
+use super::{Cell, DType, DataFrame, Series};
+
+/// Options of the text rendering of a `DataFrame` or a `Series`, see
+/// `DataFrame::to_string_with`.
+///
+/// ```ignore
+/// let options = DisplayOptions::new().max_rows(10).precision(2);
+/// println!("{}", df.to_string_with(&options));
+/// ```
+#[derive(Debug, Clone)]
+pub(crate) struct DisplayOptions {
+    pub(crate) max_rows: Option<usize>,
+    pub(crate) max_width: usize,
+    pub(crate) precision: Option<usize>,
+    pub(crate) na_rep: String,
+    pub(crate) index: bool,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            max_rows: Some(20),
+            max_width: 20,
+            precision: None,
+            na_rep: String::from("None"),
+            index: true,
+        }
+    }
+}
+
+impl DisplayOptions {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of rows shown before the rows in the middle are replaced with
+    /// `...`, half of them from the start and half from the end.
+    pub(crate) fn max_rows(mut self, rows: usize) -> Self {
+        self.max_rows = Some(rows);
+        self
+    }
+
+    /// shows every row, however many there are
+    pub(crate) fn all_rows(mut self) -> Self {
+        self.max_rows = None;
+        self
+    }
+
+    /// number of characters after which values and names are cut off and end
+    /// with `…`
+    pub(crate) fn max_width(mut self, width: usize) -> Self {
+        self.max_width = width.max(1);
+        self
+    }
+
+    /// number of decimals of `Float` values, all of them if not set
+    pub(crate) fn precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// text shown for missing values
+    pub(crate) fn na_rep(mut self, na_rep: &str) -> Self {
+        self.na_rep = na_rep.to_string();
+        self
+    }
+
+    /// whether the original position of every row is shown in front of it
+    pub(crate) fn index(mut self, index: bool) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// `text` with its control characters escaped, cut off at `max_width`
+    /// characters
+    fn truncate(&self, text: String) -> String {
+        let text = escape(text);
+        match text.chars().count() > self.max_width {
+            true => text.chars().take(self.max_width - 1).chain(['…']).collect(),
+            false => text,
+        }
+    }
+
+    fn format(&self, cell: Option<Cell>) -> String {
+        let text = match (cell, self.precision) {
+            (None, _) => self.na_rep.clone(),
+            (Some(Cell::Float(v)), Some(precision)) => format!("{v:.precision$}"),
+            (Some(cell), _) => cell.to_string(),
+        };
+        self.truncate(text)
+    }
+
+    /// Positions of the rows to show out of `len`, with the position in the
+    /// list after which the elided rows go if there are any.
+    fn rows(&self, len: usize) -> (Vec<usize>, Option<usize>) {
+        match self.max_rows {
+            Some(max_rows) if len > max_rows => {
+                let head = max_rows.div_ceil(2);
+                let tail = max_rows - head;
+                let rows = (0..head).chain(len - tail..len).collect();
+                (rows, Some(head))
+            }
+            _ => ((0..len).collect(), None),
+        }
+    }
+}
+
+/// `text` with line breaks, tabs and other control characters escaped, so
+/// that every value stays on one line of the table
+fn escape(text: String) -> String {
+    if !text.chars().any(char::is_control) {
+        return text;
+    }
+    // `\n`, `\r` and `\t` are escaped as such, other characters as `\u{..}`
+    text.chars()
+        .map(|c| match c.is_control() {
+            true => c.escape_default().to_string(),
+            false => c.to_string(),
+        })
+        .collect()
+}
+
+/// A column of rendered values with its title
+struct Rendered {
+    title: String,
+    values: Vec<String>,
+    /// numbers are aligned to the right, everything else to the left
+    right: bool,
+}
+
+impl Rendered {
+    fn new(title: String, series: &Series, rows: &[usize], options: &DisplayOptions) -> Self {
+        Self {
+            title: options.truncate(title),
+            values: rows
+                .iter()
+                .map(|&row| options.format(series.get(row)))
+                .collect(),
+            right: matches!(series.d_type(), DType::Int | DType::Float),
+        }
+    }
+}
+
+/// lays the columns out side by side, `...` marks the elided rows
+fn render(columns: &[Rendered], elided: Option<usize>) -> String {
+    let min_width = if elided.is_some() { 3 } else { 0 };
+    let widths = columns
+        .iter()
+        .map(|column| {
+            column
+                .values
+                .iter()
+                .chain([&column.title])
+                .map(|text| text.chars().count())
+                .max()
+                .unwrap_or_default()
+                .max(min_width)
+        })
+        .collect::<Vec<usize>>();
+    let line = |texts: &mut dyn Iterator<Item = &str>| {
+        let cells = texts
+            .zip(columns.iter().zip(&widths))
+            .map(|(text, (column, &width))| match column.right {
+                true => format!("{text:>width$}"),
+                false => format!("{text:<width$}"),
+            })
+            .collect::<Vec<String>>();
+        cells.join("  ").trim_end().to_string()
+    };
+
+    let mut lines = vec![line(&mut columns.iter().map(|c| c.title.as_str()))];
+    let len = columns.first().map_or(0, |c| c.values.len());
+    for row in 0..len {
+        if elided == Some(row) {
+            lines.push(line(&mut columns.iter().map(|_| "...")));
+        }
+        lines.push(line(&mut columns.iter().map(|c| c.values[row].as_str())));
+    }
+    lines.join("\n")
+}
+
+impl DataFrame {
+    /// the first `n` rows, rows keep their index
+    pub(crate) fn head(&self, n: usize) -> DataFrame {
+        self.take(&(0..n.min(self.shape().0)).collect::<Vec<usize>>())
+    }
+
+    /// the last `n` rows, rows keep their index
+    pub(crate) fn tail(&self, n: usize) -> DataFrame {
+        let len = self.shape().0;
+        self.take(&(len - n.min(len)..len).collect::<Vec<usize>>())
+    }
+
+    /// Renders the data frame as a table with one line per row, following
+    /// the column names. Columns are as wide as their longest value and
+    /// numbers are aligned to the right. Large data frames show their first
+    /// and last rows only, followed by their shape.
+    ///
+    /// ```ignore
+    /// let report = df.to_string_with(&DisplayOptions::new().precision(2));
+    /// ```
+    pub(crate) fn to_string_with(&self, options: &DisplayOptions) -> String {
+        let (len, width) = self.shape();
+        let (rows, elided) = options.rows(len);
+        let mut columns = vec![];
+        if options.index {
+            let title = self.labels.as_ref().map_or("", |labels| &labels.name);
+            let labels = self.labels();
+            columns.push(Rendered::new(title.to_string(), &labels, &rows, options));
+        }
+        for header in &self.headers {
+            let series = &self.data[&header.name];
+            columns.push(Rendered::new(header.name.clone(), series, &rows, options));
+        }
+        let mut text = render(&columns, elided);
+        if elided.is_some() {
+            text.push_str(&format!("\n\n[{len} rows x {width} columns]"));
+        }
+        text
+    }
+}
+
+impl Display for DataFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string_with(&DisplayOptions::default()))
+    }
+}
+
+impl Series {
+    /// Renders the values with their label or position, one per line,
+    /// followed by the `DType` of the series, see `DataFrame::to_string_with`.
+    pub(crate) fn to_string_with(&self, options: &DisplayOptions) -> String {
+        let (rows, elided) = options.rows(self.len());
+        let mut columns = vec![];
+        match (options.index, self.labels()) {
+            (true, Some(labels)) => {
+                columns.push(Rendered::new(String::new(), labels, &rows, options))
+            }
+            (true, None) => columns.push(Rendered {
+                title: String::new(),
+                values: rows.iter().map(|row| row.to_string()).collect(),
+                right: true,
+            }),
+            (false, _) => {}
+        }
+        columns.push(Rendered::new(String::new(), self, &rows, options));
+        // the values have no title line
+        let table = render(&columns, elided);
+        let values = table.split_once('\n').map_or("", |(_, values)| values);
+        let mut text = values.to_string();
+        if elided.is_some() {
+            text.push_str(&format!("\nLength: {}", self.len()));
+        }
+        text.push_str(&format!("\ndtype: {:?}", self.d_type()));
+        text.trim_start_matches('\n').to_string()
+    }
+}
+
+impl Display for Series {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string_with(&DisplayOptions::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::fixtures::read;
+
+    fn long_names() -> DataFrame {
+        let source = "Name,Age,CGPA\n\
+                      John,20,3.5\n\
+                      Satoshi Nakamoto,,3.25\n\
+                      Adam,22,\n\
+                      Jane,19,4\n";
+        read(source)
+    }
+
+    #[test]
+    fn test_display() {
+        let expected = [
+            "   Name               Age  CGPA",
+            "0  John                20   3.5",
+            "1  Satoshi Nakamoto  None  3.25",
+            "2  Adam                22  None",
+            "3  Jane                19     4",
+        ];
+        assert_eq!(long_names().to_string(), expected.join("\n"));
+
+        let options = DisplayOptions::new()
+            .max_width(8)
+            .precision(2)
+            .na_rep("-")
+            .index(false);
+        let expected = [
+            "Name      Age  CGPA",
+            "John       20  3.50",
+            "Satoshi…    -  3.25",
+            "Adam       22     -",
+            "Jane       19  4.00",
+        ];
+        assert_eq!(long_names().to_string_with(&options), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_elided_rows() {
+        let options = DisplayOptions::new().max_rows(2);
+        let expected = [
+            "     Name  Age  CGPA",
+            "  0  John   20   3.5",
+            "...  ...   ...   ...",
+            "  3  Jane   19     4",
+            "",
+            "[4 rows x 3 columns]",
+        ];
+        assert_eq!(long_names().to_string_with(&options), expected.join("\n"));
+
+        let series = long_names().column("Age").unwrap().clone();
+        let expected = [
+            "  0   20",
+            "...  ...",
+            "  3   19",
+            "Length: 4",
+            "dtype: Int",
+        ];
+        assert_eq!(series.to_string_with(&options), expected.join("\n"));
+        let expected = ["0    20", "1  None", "2    22", "3    19", "dtype: Int"];
+        assert_eq!(
+            series.to_string_with(&options.all_rows()),
+            expected.join("\n")
+        );
+    }
+
+    #[test]
+    fn test_head_and_tail() {
+        let df = long_names();
+        assert_eq!(df.head(2).index(), vec![0, 1]);
+        assert_eq!(df.tail(2).index(), vec![2, 3]);
+        assert_eq!(df.tail(10).shape(), (4, 3));
+        assert!(df
+            .tail(1)
+            .to_string()
+            .starts_with("   Name  Age  CGPA\n3  Jane"));
+    }
+
+    #[test]
+    fn test_multi_line_values() {
+        let source = "Name,Note\nJohn,\"Works remotely\nfrom Tokyo\"\nJane,\"a\tb\"\n";
+        let df = read(source);
+        let expected = [
+            "   Name  Note",
+            "0  John  Works remotely\\nfro…",
+            "1  Jane  a\\tb",
+        ];
+        assert_eq!(df.to_string(), expected.join("\n"));
+        let bell = ["\u{7}"]
+            .into_iter()
+            .map(|v| Some(Cell::from(v)))
+            .collect::<Series>();
+        assert_eq!(bell.to_string(), "0  \\u{7}\ndtype: Str");
+    }
+}
@@ -0,0 +1,141 @@
+use std::{fmt::Display, io};
+
+use super::DType;
+
+/// Errors that can be produced while loading or processing a `DataFrame`.
+#[derive(Debug)]
+pub(crate) enum PandasError {
+    Io(io::Error),
+    /// a malformed csv record, `line` is the 1-based line number at which the
+    /// record starts in the source.
+    Csv {
+        line: usize,
+        message: String,
+    },
+    /// a malformed json document, `position` is the byte offset of the error
+    Json {
+        position: usize,
+        message: String,
+    },
+    /// a malformed query or one that does not apply to the columns of the
+    /// data frame, `position` is the byte offset of the error in the query
+    Query {
+        position: usize,
+        message: String,
+    },
+    /// a columnar file that is malformed, corrupted or of another version
+    Columnar(String),
+    ColumnNotFound(String),
+    DuplicateColumn(String),
+    /// a row label that is not in the labels set with `set_index`
+    LabelNotFound(String),
+    /// a row label that appears more than once where rows are matched by label
+    DuplicateLabel(String),
+    LengthMismatch {
+        expected: usize,
+        found: usize,
+    },
+    DTypeMismatch {
+        expected: DType,
+        found: DType,
+    },
+    /// a value that can not be converted into `d_type`, `column` is `None`
+    /// when converting a standalone `Series`.
+    Conversion {
+        column: Option<String>,
+        row: usize,
+        value: String,
+        d_type: DType,
+    },
+    /// a `rolling` window of zero rows
+    EmptyWindow,
+    /// an `Int` result that does not fit in 64 bits, e.g. the `sum` of a
+    /// column
+    Overflow(String),
+    /// categories that are not a reordering of the `expected` categories of
+    /// a `Category` series
+    Categories {
+        expected: Vec<String>,
+        found: Vec<String>,
+    },
+}
+
+pub(crate) type Result<T> = std::result::Result<T, PandasError>;
+
+impl Display for PandasError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PandasError::Io(err) => write!(f, "I/O error: {err}"),
+            PandasError::Csv { line, message } => write!(f, "CSV error at line {line}: {message}"),
+            PandasError::Json { position, message } => {
+                write!(f, "JSON error at position {position}: {message}")
+            }
+            PandasError::Query { position, message } => {
+                write!(f, "query error at position {position}: {message}")
+            }
+            PandasError::Columnar(message) => write!(f, "columnar file error: {message}"),
+            PandasError::ColumnNotFound(name) => write!(f, "column {name:?} does not exist"),
+            PandasError::DuplicateColumn(name) => write!(f, "column {name:?} already exists"),
+            PandasError::LabelNotFound(label) => write!(f, "label {label:?} does not exist"),
+            PandasError::DuplicateLabel(label) => write!(f, "label {label:?} is not unique"),
+            PandasError::LengthMismatch { expected, found } => {
+                write!(f, "expected {expected} values, found {found}")
+            }
+            PandasError::DTypeMismatch { expected, found } => {
+                write!(f, "expected a {expected:?} value, found {found:?}")
+            }
+            PandasError::Conversion {
+                column,
+                row,
+                value,
+                d_type,
+            } => {
+                write!(f, "can not convert {value:?} at row {row}")?;
+                if let Some(column) = column {
+                    write!(f, " of column {column:?}")?;
+                }
+                write!(f, " to {d_type:?}")
+            }
+            PandasError::EmptyWindow => write!(f, "a rolling window needs at least one row"),
+            PandasError::Overflow(what) => write!(f, "integer overflow in the {what}"),
+            PandasError::Categories { expected, found } => {
+                write!(
+                    f,
+                    "expected an order of the categories {expected:?}, found {found:?}"
+                )
+            }
+        }
+    }
+}
+
+impl PandasError {
+    /// attaches the column name to a conversion or overflow error of a
+    /// `Series`
+    pub(crate) fn in_column(self, name: &str) -> Self {
+        match self {
+            PandasError::Overflow(what) => {
+                PandasError::Overflow(format!("{what} of column {name:?}"))
+            }
+            PandasError::Conversion {
+                column: None,
+                row,
+                value,
+                d_type,
+            } => PandasError::Conversion {
+                column: Some(name.to_string()),
+                row,
+                value,
+                d_type,
+            },
+            err => err,
+        }
+    }
+}
+
+impl std::error::Error for PandasError {}
+
+impl From<io::Error> for PandasError {
+    fn from(err: io::Error) -> Self {
+        PandasError::Io(err)
+    }
+}
@@ -0,0 +1,407 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    error::{PandasError, Result},
+    parallel, Cell, DType, DataFrame, HashKey, Header, Series,
+};
+
+/// Aggregations that can be computed over the groups of a `GroupBy`.
+/// Missing values are skipped by every aggregation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Agg {
+    /// number of non-null values
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+    /// sample standard deviation
+    Std,
+    /// first non-null value
+    First,
+    /// last non-null value
+    Last,
+    /// number of distinct non-null values
+    NUnique,
+}
+
+impl Agg {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Agg::Count => "count",
+            Agg::Sum => "sum",
+            Agg::Mean => "mean",
+            Agg::Min => "min",
+            Agg::Max => "max",
+            Agg::Std => "std",
+            Agg::First => "first",
+            Agg::Last => "last",
+            Agg::NUnique => "n_unique",
+        }
+    }
+
+    /// type of the aggregated column for a column of type `d_type`
+    pub(crate) fn output_type(&self, d_type: DType) -> Result<DType> {
+        match (self, d_type) {
+            (Agg::Count | Agg::NUnique, _) => Ok(DType::Int),
+            (Agg::Min | Agg::Max | Agg::First | Agg::Last, d_type) => Ok(d_type),
+            (Agg::Sum, DType::Bool | DType::Int) => Ok(DType::Int),
+            (Agg::Sum | Agg::Mean | Agg::Std, DType::Bool | DType::Int | DType::Float) => {
+                Ok(DType::Float)
+            }
+            (_, found) => Err(PandasError::DTypeMismatch {
+                expected: DType::Float,
+                found,
+            }),
+        }
+    }
+
+    /// Aggregates the non-null values of a column of type `d_type`, returns
+    /// `None` when there is nothing to aggregate. The `Sum` of `Int` values
+    /// fails with `PandasError::Overflow` instead of wrapping around.
+    pub(crate) fn apply(&self, d_type: DType, values: &[&Cell]) -> Result<Option<Cell>> {
+        let numbers = || values.iter().filter_map(|c| c.as_f64());
+        Ok(match self {
+            Agg::Count => Some(Cell::Int(values.len() as i64)),
+            Agg::NUnique => Some(Cell::Int(
+                values
+                    .iter()
+                    .map(|c| HashKey::from(Some(*c)))
+                    .collect::<HashSet<HashKey>>()
+                    .len() as i64,
+            )),
+            Agg::First => values.first().map(|c| (*c).clone()),
+            Agg::Last => values.last().map(|c| (*c).clone()),
+            Agg::Min => values
+                .iter()
+                .copied()
+                .reduce(|a, b| if b < a { b } else { a })
+                .cloned(),
+            Agg::Max => values
+                .iter()
+                .copied()
+                .reduce(|a, b| if b > a { b } else { a })
+                .cloned(),
+            // folded from `0.0` because `Iterator::sum` of no floats is `-0.0`
+            Agg::Sum if d_type == DType::Float => {
+                Some(Cell::Float(numbers().fold(0.0, |sum, v| sum + v)))
+            }
+            Agg::Sum => {
+                let sum = values.iter().try_fold(0i64, |sum, c| match c {
+                    Cell::Int(v) => sum.checked_add(*v),
+                    Cell::Bool(v) => sum.checked_add(*v as i64),
+                    _ => Some(sum),
+                });
+                let sum = sum.ok_or_else(|| PandasError::Overflow(String::from("sum")))?;
+                Some(Cell::Int(sum))
+            }
+            Agg::Mean if values.is_empty() => None,
+            Agg::Mean => Some(Cell::Float(numbers().sum::<f64>() / values.len() as f64)),
+            Agg::Std if values.len() < 2 => None,
+            Agg::Std => {
+                let n = values.len() as f64;
+                let mean = numbers().sum::<f64>() / n;
+                let variance = numbers().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+                Some(Cell::Float(variance.sqrt()))
+            }
+        })
+    }
+}
+
+/// Rows of a `DataFrame` grouped by the values of one or more key columns,
+/// created with `DataFrame::group_by`.
+pub(crate) struct GroupBy<'a> {
+    df: &'a DataFrame,
+    keys: Vec<String>,
+}
+
+impl DataFrame {
+    /// Groups the rows by the values of the `keys` columns, missing values
+    /// form a group of their own.
+    ///
+    /// ```ignore
+    /// let report = df
+    ///     .group_by(["Graduated"])
+    ///     .agg([("CGPA", Agg::Mean), ("Age", Agg::Max), ("SN", Agg::Count)])?;
+    /// ```
+    pub(crate) fn group_by<T, S>(&self, keys: T) -> GroupBy<'_>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        GroupBy {
+            df: self,
+            keys: keys.into_iter().map(|k| k.as_ref().to_string()).collect(),
+        }
+    }
+}
+
+impl GroupBy<'_> {
+    /// Positions of the rows of every group, in order of first appearance.
+    /// The key columns are dictionary encoded first, see `Series::encode`, so
+    /// rows are compared by code instead of by value.
+    pub(crate) fn groups(&self) -> Result<Vec<Vec<usize>>> {
+        let keys = self
+            .keys
+            .iter()
+            .map(|k| self.df.column(k).map(Series::encode))
+            .collect::<Result<Vec<(Vec<usize>, usize)>>>()?;
+        let mut groups: Vec<Vec<usize>> = vec![];
+        if let [(codes, distinct)] = keys.as_slice() {
+            // a single key indexes the groups by its code directly
+            let mut positions: Vec<Option<usize>> = vec![None; *distinct];
+            for (row, &code) in codes.iter().enumerate() {
+                let idx = *positions[code].get_or_insert_with(|| {
+                    groups.push(vec![]);
+                    groups.len() - 1
+                });
+                groups[idx].push(row);
+            }
+            return Ok(groups);
+        }
+        let mut positions: HashMap<Vec<usize>, usize> = HashMap::new();
+        for row in 0..self.df.shape().0 {
+            let key = keys
+                .iter()
+                .map(|(codes, _)| codes[row])
+                .collect::<Vec<usize>>();
+            let idx = *positions.entry(key).or_insert_with(|| {
+                groups.push(vec![]);
+                groups.len() - 1
+            });
+            groups[idx].push(row);
+        }
+        Ok(groups)
+    }
+
+    /// Computes the aggregations for every group. The result has one row per
+    /// group with the key columns followed by one column per aggregation.
+    /// Aggregated columns keep their name unless the same column is aggregated
+    /// more than once or is a key, in which case the name of the aggregation
+    /// is appended, e.g. `CGPA_mean`.
+    pub(crate) fn agg<T, S>(&self, aggs: T) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = (S, Agg)>,
+        S: AsRef<str>,
+    {
+        self.agg_on(aggs, parallel::threads())
+    }
+
+    /// computes the aggregations on up to `threads` threads, see `agg`
+    fn agg_on<T, S>(&self, aggs: T, threads: usize) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = (S, Agg)>,
+        S: AsRef<str>,
+    {
+        let aggs = aggs
+            .into_iter()
+            .map(|(column, agg)| (column.as_ref().to_string(), agg))
+            .collect::<Vec<(String, Agg)>>();
+        let groups = self.groups()?;
+        let mut df = DataFrame::new(Vec::<(String, Vec<String>)>::new());
+        for key in &self.keys {
+            let series = &self.df.data[key];
+            df.headers.push(self.df.header(key)?.clone());
+            let first = groups.iter().map(|rows| rows[0]).collect::<Vec<usize>>();
+            df.data.insert(key.clone(), series.take(&first));
+        }
+        aggregate(self.df, &groups, &aggs, &self.keys, &mut df, threads)?;
+        Ok(df)
+    }
+}
+
+/// Appends one column per aggregation in `aggs` to `out`, computed over the
+/// rows of every group. Columns that are aggregated more than once or that
+/// are also a key in `keys` are suffixed with the name of the aggregation.
+/// The aggregations are computed on up to `threads` threads.
+pub(crate) fn aggregate(
+    df: &DataFrame,
+    groups: &[Vec<usize>],
+    aggs: &[(String, Agg)],
+    keys: &[String],
+    out: &mut DataFrame,
+    threads: usize,
+) -> Result<()> {
+    let columns = parallel::map(
+        threads,
+        aggs.iter().collect(),
+        |(column, agg)| -> Result<(String, DType, Series)> {
+            let d_type = df.header(column)?.d_type;
+            let repeated = aggs.iter().filter(|(c, _)| c == column).count() > 1;
+            let name = match repeated || keys.contains(column) {
+                false => column.clone(),
+                true => format!("{column}_{}", agg.name()),
+            };
+            let series = &df.data[column];
+            let output_type = agg.output_type(d_type)?;
+            let values = groups
+                .iter()
+                .map(|rows| {
+                    let values = rows
+                        .iter()
+                        .filter_map(|&row| series.get(row))
+                        .collect::<Vec<Cell>>();
+                    agg.apply(d_type, &values.iter().collect::<Vec<&Cell>>())
+                })
+                .collect::<Result<Vec<Option<Cell>>>>()
+                .map_err(|err| err.in_column(column))?;
+            Ok((name, output_type, Series::from_cells(output_type, values)))
+        },
+    );
+    for column in columns {
+        let (name, d_type, series) = column?;
+        out.headers.push(Header {
+            name: name.clone(),
+            d_type,
+        });
+        out.data.insert(name, series);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::fixtures::{read, students};
+
+    fn column(df: &DataFrame, name: &str) -> Vec<Option<Cell>> {
+        (0..df.shape().0)
+            .map(|idx| df.column(name).unwrap()[idx].clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_group_by_single_key() {
+        let df = students()
+            .group_by(["Graduated"])
+            .agg([("CGPA", Agg::Mean), ("Age", Agg::Max), ("SN", Agg::Count)])
+            .unwrap();
+        assert_eq!(df.shape(), (2, 4));
+        assert_eq!(df.headers[0].d_type, DType::Bool);
+        assert_eq!(
+            column(&df, "Graduated"),
+            vec![Some(Cell::Bool(true)), Some(Cell::Bool(false))]
+        );
+        assert_eq!(
+            column(&df, "CGPA"),
+            vec![Some(Cell::Float(10.0 / 3.0)), Some(Cell::Float(3.0))]
+        );
+        assert_eq!(
+            column(&df, "Age"),
+            vec![Some(Cell::Int(23)), Some(Cell::Int(21))]
+        );
+        assert_eq!(
+            column(&df, "SN"),
+            vec![Some(Cell::Int(3)), Some(Cell::Int(2))]
+        );
+    }
+
+    #[test]
+    fn test_group_by_multiple_keys() {
+        let df = students()
+            .group_by(["Dept", "Graduated"])
+            .agg([
+                ("Age", Agg::Sum),
+                ("Name", Agg::First),
+                ("Name", Agg::Last),
+                ("CGPA", Agg::NUnique),
+                ("CGPA", Agg::Std),
+            ])
+            .unwrap();
+        let names: Vec<String> = df.headers.iter().map(|h| h.name.clone()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Dept",
+                "Graduated",
+                "Age",
+                "Name_first",
+                "Name_last",
+                "CGPA_n_unique",
+                "CGPA_std"
+            ]
+        );
+        assert_eq!(df.shape(), (4, 7));
+        assert_eq!(
+            column(&df, "Age"),
+            vec![
+                Some(Cell::Int(42)),
+                Some(Cell::Int(19)),
+                Some(Cell::Int(21)),
+                Some(Cell::Int(23))
+            ]
+        );
+        assert_eq!(column(&df, "Name_last")[0], Some(Cell::Str("Adam".into())));
+        assert_eq!(column(&df, "CGPA_n_unique")[2], Some(Cell::Int(0)));
+        assert_eq!(column(&df, "CGPA_std")[0], Some(Cell::Float(0.5f64.sqrt())));
+        assert_eq!(column(&df, "CGPA_std")[1], None);
+    }
+
+    #[test]
+    fn test_group_by_category() {
+        let mut df = students();
+        let expected = df.group_by(["Dept", "Graduated"]).groups().unwrap();
+        df.as_type([(String::from("Dept"), DType::Category)])
+            .unwrap();
+        assert_eq!(
+            df.group_by(["Dept", "Graduated"]).groups().unwrap(),
+            expected
+        );
+        let df = df.group_by(["Dept"]).agg([("Age", Agg::Max)]).unwrap();
+        assert_eq!(df.headers[0].d_type, DType::Category);
+        assert_eq!(column(&df, "Dept")[0], Some(Cell::Category("CS".into())));
+    }
+
+    #[test]
+    fn test_group_by_threads() {
+        let aggs = [
+            ("CGPA", Agg::Mean),
+            ("Age", Agg::Sum),
+            ("Name", Agg::First),
+            ("SN", Agg::Count),
+        ];
+        let df = students();
+        let by_dept = df.group_by(["Dept"]);
+        let report = |threads| {
+            let df = by_dept.agg_on(aggs, threads).unwrap();
+            let err = by_dept
+                .agg_on(
+                    [("Age", Agg::Sum), ("Name", Agg::Mean), ("Dept", Agg::Std)],
+                    threads,
+                )
+                .unwrap_err();
+            (df.to_string(), err.to_string())
+        };
+        let serial = report(1);
+        assert_eq!(report(4), serial);
+    }
+
+    #[test]
+    fn test_group_by_errors() {
+        let df = students();
+        assert!(matches!(
+            df.group_by(["Grade"]).agg([("Age", Agg::Sum)]),
+            Err(PandasError::ColumnNotFound(_))
+        ));
+        assert!(matches!(
+            df.group_by(["Dept"]).agg([("Name", Agg::Mean)]),
+            Err(PandasError::DTypeMismatch {
+                found: DType::Str,
+                ..
+            })
+        ));
+
+        let source = format!("Dept,Amount\nCS,{}\nCS,1\nEE,1\n", i64::MAX);
+        let df = read(&source);
+        let err = df
+            .group_by(["Dept"])
+            .agg([("Amount", Agg::Sum)])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "integer overflow in the sum of column \"Amount\""
+        );
+        let df = df.query("Dept == 'EE'").unwrap();
+        assert!(df.group_by(["Dept"]).agg([("Amount", Agg::Sum)]).is_ok());
+    }
+}
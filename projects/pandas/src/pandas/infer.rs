@@ -0,0 +1,137 @@
+use super::{datetime::parse_iso, error::Result, parallel, DType, DataFrame, Series};
+
+/// the kinds of values a single string cell can be parsed into
+struct Candidates {
+    bool: bool,
+    int: bool,
+    float: bool,
+    date: bool,
+    datetime: bool,
+    // `true` or `false` literals, used to tell boolean flags from 0/1 integers
+    bool_literal: bool,
+}
+
+impl Candidates {
+    fn of(value: &str) -> Self {
+        let bool_literal = matches!(value, "true" | "false");
+        let int = value.parse::<i64>().is_ok();
+        // skips parsing text that can not start with a four digit year
+        let iso = value.len() >= 10 && value.as_bytes()[..4].iter().all(u8::is_ascii_digit);
+        let date = iso && parse_iso(value, DType::Date).is_some();
+        Self {
+            bool: bool_literal || matches!(value, "0" | "1"),
+            int,
+            // words like `nan` or `Infinity` are valid floats but are rarely
+            // meant as numbers, only the spellings of `write_csv` are kept
+            float: int
+                || matches!(value, "NaN" | "inf" | "-inf")
+                || (value.parse::<f64>().is_ok() && value.chars().any(|c| c.is_ascii_digit())),
+            date,
+            datetime: date || (iso && parse_iso(value, DType::DateTime).is_some()),
+            bool_literal,
+        }
+    }
+}
+
+impl Series {
+    /// Infers the narrowest `DType` that every non-null value of the series can
+    /// be converted into, looking at the first `sample` rows only if provided.
+    ///
+    /// `0` and `1` are treated as booleans only when the column also contains
+    /// `true` or `false`, otherwise they are integers. ISO 8601 dates give
+    /// `Date` and dates with a time give `DateTime`, see `parse_iso`.
+    pub(crate) fn infer_type(&self, sample: Option<usize>) -> DType {
+        if self.d_type() != DType::Str {
+            // typed values already have their type
+            return self.d_type();
+        }
+        let (mut bool, mut int, mut float) = (true, true, true);
+        let (mut date, mut datetime) = (true, true);
+        let mut bool_literal = false;
+        let mut values = 0;
+        let rows = self.len().min(sample.unwrap_or(usize::MAX));
+        for value in (0..rows).filter_map(|row| self.str_at(row)) {
+            let candidates = Candidates::of(value);
+            bool &= candidates.bool;
+            int &= candidates.int;
+            float &= candidates.float;
+            date &= candidates.date;
+            datetime &= candidates.datetime;
+            bool_literal |= candidates.bool_literal;
+            values += 1;
+            if !(bool || int || float || datetime) {
+                return DType::Str;
+            }
+        }
+        match values {
+            // nothing to infer from, keep the values as they are
+            0 => DType::Str,
+            _ if bool && bool_literal => DType::Bool,
+            _ if int => DType::Int,
+            _ if float => DType::Float,
+            _ if date => DType::Date,
+            _ if datetime => DType::DateTime,
+            _ => DType::Str,
+        }
+    }
+}
+
+impl DataFrame {
+    /// Infers and converts the `DType` of every string column, see
+    /// `Series::infer_type`. Fails if a value outside of the sample can not be
+    /// converted into the inferred type.
+    pub(crate) fn infer_types(&mut self, sample: Option<usize>) -> Result<()> {
+        let headers = self
+            .headers
+            .iter()
+            .filter(|h| matches!(h.d_type, DType::Str))
+            .collect();
+        let inferred = parallel::map(parallel::threads(), headers, |h| {
+            (h.name.clone(), self.data[&h.name].infer_type(sample))
+        })
+        .into_iter()
+        .filter(|(_, d_type)| !matches!(d_type, DType::Str))
+        .collect::<Vec<(String, DType)>>();
+        self.as_type(inferred)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(values: &[&str]) -> Series {
+        Series::new(values.iter().map(|v| v.to_string()).collect())
+    }
+
+    #[test]
+    fn test_infer_type() {
+        assert_eq!(series(&["1", "2", "-3"]).infer_type(None), DType::Int);
+        assert_eq!(series(&["1", "2.5"]).infer_type(None), DType::Float);
+        assert_eq!(series(&["1e3", "0"]).infer_type(None), DType::Float);
+        assert_eq!(series(&["true", "0", "1"]).infer_type(None), DType::Bool);
+        assert_eq!(series(&["0", "1", "1"]).infer_type(None), DType::Int);
+        assert_eq!(series(&["true", "2"]).infer_type(None), DType::Str);
+        assert_eq!(series(&["1", "nan"]).infer_type(None), DType::Str);
+        assert_eq!(series(&["1", "Infinity"]).infer_type(None), DType::Str);
+        assert_eq!(series(&["1", "NaN", "-inf"]).infer_type(None), DType::Float);
+        assert_eq!(series(&["Doe", "1"]).infer_type(None), DType::Str);
+        assert_eq!(series(&[]).infer_type(None), DType::Str);
+        let dates = series(&["2024-01-31", "2024-02-29"]);
+        assert_eq!(dates.infer_type(None), DType::Date);
+        let times = series(&["2024-01-31", "2024-02-29 08:30"]);
+        assert_eq!(times.infer_type(None), DType::DateTime);
+        assert_eq!(series(&["2024-02-30"]).infer_type(None), DType::Str);
+    }
+
+    #[test]
+    fn test_infer_type_sample_and_nulls() {
+        let mut s = series(&["1", "2", "x"]);
+        assert_eq!(s.infer_type(Some(2)), DType::Int);
+        assert_eq!(s.infer_type(None), DType::Str);
+
+        s = series(&["1.5"]);
+        s.push(None);
+        assert_eq!(s.infer_type(None), DType::Float);
+    }
+}
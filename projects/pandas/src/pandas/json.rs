@@ -0,0 +1,557 @@
+use std::{collections::HashMap, fmt::Write};
+
+use super::{
+    error::{PandasError, Result},
+    Cell, DType, DataFrame, Header, Series,
+};
+
+/// Layout of the json document produced by `DataFrame::to_json`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Orient {
+    /// an array with one object per row: `[{"SN": 1, "Name": "John"}, ...]`
+    Records,
+    /// an object with one object per column, keyed by the row index:
+    /// `{"SN": {"0": 1, "1": 2}, "Name": {"0": "John", "1": "Jane"}}`
+    Columns,
+}
+
+/// arrays and objects nested deeper than this are rejected instead of
+/// overflowing the stack
+const MAX_DEPTH: usize = 128;
+
+/// A parsed json value, objects keep the order of their keys
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+fn write_str(out: &mut String, value: &str) {
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch.is_control() => {
+                let _ = write!(out, "\\u{:04x}", ch as u32);
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+fn write_cell(out: &mut String, cell: &Option<Cell>) {
+    match cell {
+        None => out.push_str("null"),
+        Some(Cell::Bool(v)) => out.push_str(&v.to_string()),
+        Some(Cell::Int(v)) => out.push_str(&v.to_string()),
+        // json has no representation for NaN and infinity
+        Some(Cell::Float(v)) if !v.is_finite() => out.push_str("null"),
+        Some(Cell::Float(v)) => out.push_str(&format!("{v:?}")),
+        Some(Cell::Str(v) | Cell::Category(v)) => write_str(out, v),
+        Some(cell @ (Cell::Date(_) | Cell::DateTime(_))) => write_str(out, &cell.to_string()),
+    }
+}
+
+/// A recursive descent parser over a json document
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    /// number of arrays and objects around the current position
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, message: &str) -> Result<T> {
+        Err(PandasError::Json {
+            position: self.pos,
+            message: message.to_string(),
+        })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(ch) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(ch) if ch == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => self.error(&format!("expected {expected:?}")),
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: Json) -> Result<Json> {
+        match self.source[self.pos..].starts_with(literal) {
+            true => {
+                self.pos += literal.len();
+                Ok(value)
+            }
+            false => self.error("invalid literal"),
+        }
+    }
+
+    /// parses an array or an object with `f`, failing past `MAX_DEPTH` levels
+    fn nested<F>(&mut self, f: F) -> Result<Json>
+    where
+        F: FnOnce(&mut Self) -> Result<Json>,
+    {
+        if self.depth == MAX_DEPTH {
+            return self.error(&format!("nested more than {MAX_DEPTH} levels deep"));
+        }
+        self.depth += 1;
+        let value = f(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_value(&mut self) -> Result<Json> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => self.parse_literal("null", Json::Null),
+            Some('t') => self.parse_literal("true", Json::Bool(true)),
+            Some('f') => self.parse_literal("false", Json::Bool(false)),
+            Some('"') => Ok(Json::Str(self.parse_string()?)),
+            Some('[') => self.nested(Self::parse_array),
+            Some('{') => self.nested(Self::parse_object),
+            Some(ch) if ch == '-' || ch.is_ascii_digit() => self.parse_number(),
+            Some(_) => self.error("unexpected character"),
+            None => self.error("unexpected end of input"),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Json> {
+        let start = self.pos;
+        while let Some(ch) = self
+            .peek()
+            .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            self.pos += ch.len_utf8();
+        }
+        let text = &self.source[start..self.pos];
+        if let Ok(v) = text.parse::<i64>() {
+            return Ok(Json::Int(v));
+        }
+        match text.parse::<f64>() {
+            Ok(v) => Ok(Json::Float(v)),
+            Err(_) => {
+                self.pos = start;
+                self.error("invalid number")
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            let ch = match self.peek() {
+                Some(ch) => ch,
+                None => return self.error("unterminated string"),
+            };
+            self.pos += ch.len_utf8();
+            match ch {
+                '"' => return Ok(value),
+                '\\' => {
+                    let escaped = match self.peek() {
+                        Some(ch) => ch,
+                        None => return self.error("unterminated string"),
+                    };
+                    self.pos += 1;
+                    match escaped {
+                        '"' | '\\' | '/' => value.push(escaped),
+                        'n' => value.push('\n'),
+                        'r' => value.push('\r'),
+                        't' => value.push('\t'),
+                        'b' => value.push('\u{8}'),
+                        'f' => value.push('\u{c}'),
+                        'u' => value.push(self.parse_unicode()?),
+                        _ => return self.error("invalid escape sequence"),
+                    }
+                }
+                ch => value.push(ch),
+            }
+        }
+    }
+
+    /// parses the four hexadecimal digits of a `\u` escape
+    fn parse_hex(&mut self) -> Option<u32> {
+        let code = self
+            .source
+            .get(self.pos..self.pos + 4)
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok());
+        self.pos += 4;
+        code
+    }
+
+    /// parses the digits of a `\u` escape, including utf-16 surrogate pairs
+    fn parse_unicode(&mut self) -> Result<char> {
+        let high = match self.parse_hex() {
+            Some(code) => code,
+            None => return self.error("invalid unicode escape"),
+        };
+        let code = match (0xD800..0xDC00).contains(&high) {
+            true if self.source[self.pos..].starts_with("\\u") => {
+                self.pos += 2;
+                match self.parse_hex() {
+                    Some(low) if (0xDC00..0xE000).contains(&low) => {
+                        0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                    }
+                    _ => return self.error("invalid unicode escape"),
+                }
+            }
+            _ => high,
+        };
+        match char::from_u32(code) {
+            Some(ch) => Ok(ch),
+            None => self.error("invalid unicode escape"),
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Json> {
+        self.expect('[')?;
+        let mut items = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return self.error("expected ',' or ']'"),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Json> {
+        self.expect('{')?;
+        let mut items = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Json::Object(items));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            items.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(items));
+                }
+                _ => return self.error("expected ',' or '}'"),
+            }
+        }
+    }
+}
+
+/// Builds a typed series from json values, the type is the narrowest one that
+/// fits every non-null value.
+fn to_series(values: Vec<Json>) -> Result<(DType, Series)> {
+    let mut d_type: Option<DType> = None;
+    for value in &values {
+        let value_type = match value {
+            Json::Null => continue,
+            Json::Bool(_) => DType::Bool,
+            Json::Int(_) => DType::Int,
+            Json::Float(_) => DType::Float,
+            Json::Str(_) => DType::Str,
+            Json::Array(_) | Json::Object(_) => {
+                return Err(PandasError::Json {
+                    position: 0,
+                    message: String::from("nested values are not supported"),
+                })
+            }
+        };
+        d_type = Some(match (d_type, value_type) {
+            (None, t) => t,
+            // booleans and numbers are not mixed, fall back to strings
+            (Some(DType::Bool), t) | (Some(t), DType::Bool) if t != DType::Bool => DType::Str,
+            (Some(a), b) => a.common(b),
+        });
+    }
+    let d_type = d_type.unwrap_or(DType::Str);
+    let series = Series::from_cells(
+        d_type,
+        values.into_iter().map(|value| match value {
+            Json::Null => None,
+            Json::Bool(v) => Some(Cell::Bool(v)),
+            Json::Int(v) => Some(Cell::Int(v)),
+            Json::Float(v) => Some(Cell::Float(v)),
+            Json::Str(v) => Some(Cell::Str(v)),
+            Json::Array(_) | Json::Object(_) => unreachable!(),
+        }),
+    );
+    Ok((d_type, series))
+}
+
+impl DataFrame {
+    /// Serializes the data frame as json, columns are written in `headers`
+    /// order. Missing values, `NaN` and infinite floats are written as `null`.
+    pub(crate) fn to_json(&self, orient: Orient) -> String {
+        let mut out = String::new();
+        match orient {
+            Orient::Records => {
+                out.push('[');
+                for row in 0..self.shape().0 {
+                    if row > 0 {
+                        out.push(',');
+                    }
+                    out.push('{');
+                    for (idx, (header, cell)) in
+                        self.headers.iter().zip(self.get_item_at(row)).enumerate()
+                    {
+                        if idx > 0 {
+                            out.push(',');
+                        }
+                        write_str(&mut out, &header.name);
+                        out.push(':');
+                        write_cell(&mut out, &cell);
+                    }
+                    out.push('}');
+                }
+                out.push(']');
+            }
+            Orient::Columns => {
+                let index = self.index();
+                out.push('{');
+                for (idx, header) in self.headers.iter().enumerate() {
+                    if idx > 0 {
+                        out.push(',');
+                    }
+                    write_str(&mut out, &header.name);
+                    out.push_str(":{");
+                    let series = &self.data[&header.name];
+                    for (row, label) in index.iter().enumerate() {
+                        if row > 0 {
+                            out.push(',');
+                        }
+                        write_str(&mut out, &label.to_string());
+                        out.push(':');
+                        write_cell(&mut out, &series.get(row));
+                    }
+                    out.push('}');
+                }
+                out.push('}');
+            }
+        }
+        out
+    }
+
+    /// Parses a json document written in either `Orient`. Column types are
+    /// inferred from the json values, columns mixing booleans or strings with
+    /// other values are loaded as `DType::Str`.
+    ///
+    /// ```ignore
+    /// let df = DataFrame::from_json(&df.to_json(Orient::Records))?;
+    /// ```
+    pub(crate) fn from_json(source: &str) -> Result<DataFrame> {
+        let mut parser = Parser {
+            source,
+            pos: 0,
+            depth: 0,
+        };
+        let document = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.pos < source.len() {
+            return parser.error("unexpected trailing characters");
+        }
+
+        let mut names: Vec<String> = vec![];
+        let mut columns: Vec<Vec<Json>> = vec![];
+        let mut index: Vec<usize> = vec![];
+        match document {
+            Json::Array(records) => {
+                let rows = records.len();
+                for (row, record) in records.into_iter().enumerate() {
+                    let fields = match record {
+                        Json::Object(fields) => fields,
+                        _ => return parser.error("records must be json objects"),
+                    };
+                    for (name, value) in fields {
+                        let column = match names.iter().position(|n| *n == name) {
+                            Some(column) => column,
+                            None => {
+                                names.push(name);
+                                columns.push(vec![Json::Null; rows]);
+                                columns.len() - 1
+                            }
+                        };
+                        columns[column][row] = value;
+                    }
+                }
+                index.extend(0..rows);
+            }
+            Json::Object(fields) => {
+                // row of every index label, in order of first appearance
+                let mut rows: HashMap<usize, usize> = HashMap::new();
+                let mut cells: Vec<Vec<(usize, Json)>> = vec![];
+                for (name, values) in fields {
+                    let values = match values {
+                        Json::Object(values) => values,
+                        _ => return parser.error("columns must be json objects"),
+                    };
+                    let mut column = vec![];
+                    for (label, value) in values {
+                        let label = match label.parse::<usize>() {
+                            Ok(label) => label,
+                            Err(_) => return parser.error("index labels must be integers"),
+                        };
+                        let row = *rows.entry(label).or_insert_with(|| {
+                            index.push(label);
+                            index.len() - 1
+                        });
+                        column.push((row, value));
+                    }
+                    names.push(name);
+                    cells.push(column);
+                }
+                for column in cells {
+                    let mut values = vec![Json::Null; index.len()];
+                    for (row, value) in column {
+                        values[row] = value;
+                    }
+                    columns.push(values);
+                }
+            }
+            _ => return parser.error("expected a json array or object"),
+        }
+
+        let mut df = DataFrame::new(Vec::<(String, Vec<String>)>::new());
+        for (name, values) in names.into_iter().zip(columns) {
+            let (d_type, series) = to_series(values)?;
+            df.headers.push(Header {
+                name: name.clone(),
+                d_type,
+            });
+            df.data.insert(name, series);
+        }
+        if !index.iter().copied().eq(0..index.len()) {
+            df.index = Some(index);
+        }
+        Ok(df)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::fixtures::read;
+
+    fn quoted() -> DataFrame {
+        let source = "SN,Name,CGPA,Graduated\n\
+                      1,\"John \"\"JD\"\" Doe\",3.0,true\n\
+                      2,Jane,,false\n";
+        read(source)
+    }
+
+    #[test]
+    fn test_to_json() {
+        let df = quoted();
+        assert_eq!(
+            df.to_json(Orient::Records),
+            "[{\"SN\":1,\"Name\":\"John \\\"JD\\\" Doe\",\"CGPA\":3.0,\"Graduated\":true},\
+             {\"SN\":2,\"Name\":\"Jane\",\"CGPA\":null,\"Graduated\":false}]"
+        );
+        assert_eq!(
+            df.select(["SN", "CGPA"]).unwrap().to_json(Orient::Columns),
+            "{\"SN\":{\"0\":1,\"1\":2},\"CGPA\":{\"0\":3.0,\"1\":null}}"
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let df = quoted().sort_values(["SN"], &[false]).unwrap();
+        for orient in [Orient::Records, Orient::Columns] {
+            let copy = DataFrame::from_json(&df.to_json(orient)).unwrap();
+            assert_eq!(copy.shape(), df.shape());
+            assert_eq!(copy.to_json(Orient::Records), df.to_json(Orient::Records));
+            let types: Vec<DType> = copy.headers.iter().map(|h| h.d_type).collect();
+            assert_eq!(
+                types,
+                vec![DType::Int, DType::Str, DType::Float, DType::Bool]
+            );
+        }
+        let copy = DataFrame::from_json(&df.to_json(Orient::Columns)).unwrap();
+        assert_eq!(copy.index(), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let source = r#" [ {"a": 1, "b": "xé\n"}, {"a": 2.5, "c": true}, {} ] "#;
+        let df = DataFrame::from_json(source).unwrap();
+        assert_eq!(df.shape(), (3, 3));
+        assert_eq!(df.header("a").unwrap().d_type, DType::Float);
+        assert_eq!(df.column("a").unwrap()[0], Some(Cell::Float(1.0)));
+        assert_eq!(df.column("b").unwrap()[0], Some(Cell::Str("xé\n".into())));
+        assert_eq!(df.column("c").unwrap()[0], None);
+
+        let df = DataFrame::from_json(r#"[{"a": 1}, {"a": true}]"#).unwrap();
+        assert_eq!(df.header("a").unwrap().d_type, DType::Str);
+    }
+
+    #[test]
+    fn test_from_json_errors() {
+        assert!(matches!(
+            DataFrame::from_json("[{\"a\": 1},"),
+            Err(PandasError::Json { position: 10, .. })
+        ));
+        assert!(matches!(
+            DataFrame::from_json("[1, 2]"),
+            Err(PandasError::Json { .. })
+        ));
+        assert!(matches!(
+            DataFrame::from_json("{\"a\": {\"x\": 1}}"),
+            Err(PandasError::Json { .. })
+        ));
+        assert!(matches!(
+            DataFrame::from_json("[] []"),
+            Err(PandasError::Json { position: 3, .. })
+        ));
+        let deep = "[".repeat(200_000);
+        assert!(matches!(
+            DataFrame::from_json(&deep),
+            Err(PandasError::Json { position: 128, .. })
+        ));
+        // values may be nested up to the limit, arrays are still not cells
+        let nested = format!("[{{\"a\": {}1{}}}]", "[".repeat(126), "]".repeat(126));
+        assert!(matches!(
+            DataFrame::from_json(&nested),
+            Err(PandasError::Json { message, .. }) if !message.contains("levels deep")
+        ));
+        let nested = format!("[{{\"a\": {}1{}}}]", "[".repeat(127), "]".repeat(127));
+        assert!(matches!(
+            DataFrame::from_json(&nested),
+            Err(PandasError::Json { message, .. }) if message.contains("levels deep")
+        ));
+    }
+}
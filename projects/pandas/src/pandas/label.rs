@@ -0,0 +1,608 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    ops::{Bound, RangeBounds},
+    sync::{Arc, OnceLock},
+};
+
+use super::{
+    error::{PandasError, Result},
+    merge::Join,
+    sort::compare,
+    Cell, DataFrame, HashKey, OnError, Series,
+};
+
+/// The labels of the rows of a data frame, the values of the column passed to
+/// `DataFrame::set_index`. The rows of every label and the labels in sorted
+/// order are built by the first lookup that needs them.
+#[derive(Debug, Clone)]
+pub(crate) struct Labels {
+    pub(crate) name: String,
+    pub(crate) values: Series,
+    /// rows of every label, missing labels included
+    positions: OnceLock<HashMap<HashKey, Vec<usize>>>,
+    /// the labels that are not missing in ascending order, with their row
+    sorted: OnceLock<Vec<(Cell, usize)>>,
+}
+
+impl Labels {
+    pub(crate) fn new(name: &str, mut values: Series) -> Self {
+        values.labels = None;
+        Self {
+            name: name.to_string(),
+            values,
+            positions: OnceLock::new(),
+            sorted: OnceLock::new(),
+        }
+    }
+
+    /// appends the label of a row pushed into a labelled data frame
+    pub(crate) fn push(&mut self, label: Option<Cell>) {
+        self.values.push(label);
+        self.positions = OnceLock::new();
+        self.sorted = OnceLock::new();
+    }
+
+    /// the labels of the rows at the given positions
+    pub(crate) fn take(&self, indices: &[usize]) -> Labels {
+        Labels::new(&self.name, self.values.take(indices))
+    }
+
+    fn positions(&self) -> &HashMap<HashKey, Vec<usize>> {
+        self.positions.get_or_init(|| {
+            let mut positions: HashMap<HashKey, Vec<usize>> = HashMap::new();
+            for row in 0..self.values.len() {
+                positions
+                    .entry(self.values.hash_key(row))
+                    .or_default()
+                    .push(row);
+            }
+            positions
+        })
+    }
+
+    fn sorted(&self) -> &[(Cell, usize)] {
+        self.sorted.get_or_init(|| {
+            let mut sorted = (0..self.values.len())
+                .filter_map(|row| self.values.get(row).map(|label| (label, row)))
+                .collect::<Vec<(Cell, usize)>>();
+            // rows with equal labels keep their order
+            sorted.sort_by(|(a, _), (b, _)| compare(a, b));
+            sorted
+        })
+    }
+
+    /// `label` converted into the type of the labels. A conversion that
+    /// loses information, like `1.5` into the `Int` label `1`, can not match
+    /// any label and fails with `PandasError::LabelNotFound`.
+    fn convert(&self, label: Cell) -> Result<Cell> {
+        let d_type = self.values.d_type();
+        if label.d_type() == d_type {
+            return Ok(label);
+        }
+        let converted = label.cast(d_type).ok_or(PandasError::DTypeMismatch {
+            expected: d_type,
+            found: label.d_type(),
+        })?;
+        match converted.cast(label.d_type()) == Some(label.clone()) {
+            true => Ok(converted),
+            false => Err(PandasError::LabelNotFound(label.to_string())),
+        }
+    }
+
+    /// the rows with the label `label`
+    fn find(&self, label: Cell) -> Result<&[usize]> {
+        let label = self.convert(label)?;
+        self.positions()
+            .get(&HashKey::from(Some(&label)))
+            .map(Vec::as_slice)
+            .ok_or_else(|| PandasError::LabelNotFound(label.to_string()))
+    }
+
+    /// the rows with a label between `start` and `end`, in label order
+    fn range(&self, start: Bound<Cell>, end: Bound<Cell>) -> Result<Vec<usize>> {
+        let sorted = self.sorted();
+        let first = match start {
+            Bound::Included(label) => {
+                let label = self.convert(label)?;
+                sorted.partition_point(|(value, _)| compare(value, &label).is_lt())
+            }
+            Bound::Excluded(label) => {
+                let label = self.convert(label)?;
+                sorted.partition_point(|(value, _)| compare(value, &label).is_le())
+            }
+            Bound::Unbounded => 0,
+        };
+        let last = match end {
+            Bound::Included(label) => {
+                let label = self.convert(label)?;
+                sorted.partition_point(|(value, _)| compare(value, &label).is_le())
+            }
+            Bound::Excluded(label) => {
+                let label = self.convert(label)?;
+                sorted.partition_point(|(value, _)| compare(value, &label).is_lt())
+            }
+            Bound::Unbounded => sorted.len(),
+        };
+        Ok(sorted[first..last.max(first)]
+            .iter()
+            .map(|(_, row)| *row)
+            .collect())
+    }
+
+    /// the rows of every label, failing if a label appears more than once
+    fn unique(&self) -> Result<&HashMap<HashKey, Vec<usize>>> {
+        let positions = self.positions();
+        if positions.len() == self.values.len() {
+            return Ok(positions);
+        }
+        let row = (0..self.values.len())
+            .find(|&row| positions[&self.values.hash_key(row)].len() > 1)
+            .expect("a label appears more than once");
+        let label = self
+            .values
+            .get(row)
+            .map_or(String::from("None"), |l| l.to_string());
+        Err(PandasError::DuplicateLabel(label))
+    }
+
+    /// whether both have the same labels in the same order
+    fn same(&self, other: &Labels) -> bool {
+        self.values.len() == other.values.len()
+            && (0..self.values.len())
+                .all(|row| self.values.hash_key(row) == other.values.hash_key(row))
+    }
+
+    /// The labels of `self` and `other` combined by `join`: the labels of one
+    /// side, the labels on both sides for `Inner`, or the labels of `self`
+    /// followed by the other labels for `Outer`. Labels must be unique on
+    /// both sides.
+    fn join(&self, other: &Labels, join: Join) -> Result<Labels> {
+        self.unique()?;
+        other.unique()?;
+        let d_type = self.values.d_type();
+        let other_values = other.values.cast(d_type, OnError::Raise)?;
+        let keys = |series: &Series| {
+            (0..series.len())
+                .map(|row| series.hash_key(row))
+                .collect::<HashSet<HashKey>>()
+        };
+        let rows = |series: &Series, keep: &dyn Fn(HashKey) -> bool| {
+            (0..series.len())
+                .filter(|&row| keep(series.hash_key(row)))
+                .map(|row| series.get(row))
+                .collect::<Vec<Option<Cell>>>()
+        };
+        let cells = match join {
+            Join::Left => rows(&self.values, &|_| true),
+            Join::Right => rows(&other_values, &|_| true),
+            Join::Inner => {
+                let other_keys = keys(&other_values);
+                rows(&self.values, &|key| other_keys.contains(&key))
+            }
+            Join::Outer => {
+                let own_keys = keys(&self.values);
+                let mut cells = rows(&self.values, &|_| true);
+                cells.extend(rows(&other_values, &|key| !own_keys.contains(&key)));
+                cells
+            }
+        };
+        let mut values = Series::with_capacity(d_type, cells.len());
+        cells.into_iter().for_each(|cell| values.push_cell(cell));
+        Ok(Labels::new(&self.name, values))
+    }
+
+    /// the row of every label in `labels`, `None` for labels that do not
+    /// exist, failing if the labels of `self` are not unique
+    fn rows(&self, labels: &Series) -> Result<Vec<Option<usize>>> {
+        let positions = self.unique()?;
+        let labels = labels.cast(self.values.d_type(), OnError::Raise)?;
+        Ok((0..labels.len())
+            .map(|row| positions.get(&labels.hash_key(row)).map(|rows| rows[0]))
+            .collect())
+    }
+}
+
+impl Series {
+    /// The labels of the rows, set on the columns of a data frame with
+    /// labels and kept by arithmetic and comparisons.
+    pub(crate) fn labels(&self) -> Option<&Series> {
+        self.labels.as_ref().map(|labels| &labels.values)
+    }
+
+    /// `self` and `other` reindexed to the labels of both, see
+    /// `DataFrame::align`. `None` when their rows are paired up by position
+    /// instead: if either has no labels or both have the same labels.
+    pub(crate) fn align(&self, other: &Series) -> Result<Option<(Series, Series)>> {
+        let (Some(left), Some(right)) = (&self.labels, &other.labels) else {
+            return Ok(None);
+        };
+        if Arc::ptr_eq(left, right) || left.same(right) {
+            return Ok(None);
+        }
+        let labels = Arc::new(left.join(right, Join::Outer)?);
+        let reindex = |series: &Series, own: &Labels| -> Result<Series> {
+            let mut series = take_or_null(series, &own.rows(&labels.values)?);
+            series.labels = Some(labels.clone());
+            Ok(series)
+        };
+        Ok(Some((reindex(self, left)?, reindex(other, right)?)))
+    }
+}
+
+/// the values at `rows` of `series`, missing where a row is `None`
+fn take_or_null(series: &Series, rows: &[Option<usize>]) -> Series {
+    if series.len() == 0 {
+        let mut out = Series::with_capacity(series.d_type(), rows.len());
+        rows.iter().for_each(|_| out.push_cell(None));
+        return out;
+    }
+    let mut out = series.take(&rows.iter().map(|row| row.unwrap_or(0)).collect::<Vec<_>>());
+    for (idx, row) in rows.iter().enumerate() {
+        if row.is_none() {
+            out.validity.set(idx, false);
+        }
+    }
+    out
+}
+
+impl DataFrame {
+    /// sets the labels of every column to the labels of the data frame
+    pub(crate) fn share_labels(&mut self) {
+        for series in self.data.values_mut() {
+            series.labels = self.labels.clone();
+        }
+    }
+
+    /// Moves the column `name` into the labels of the rows, replacing the
+    /// current labels. Rows can then be looked up by label with `loc`, and
+    /// `reset_index` turns the labels back into a column.
+    ///
+    /// ```ignore
+    /// let students = df.set_index("First Name")?;
+    /// let jane = students.loc("Jane")?;
+    /// ```
+    pub(crate) fn set_index(&self, name: &str) -> Result<DataFrame> {
+        let values = self.column(name)?.clone();
+        let mut df = self.clone();
+        df.headers.retain(|h| h.name != name);
+        df.data.remove(name);
+        df.index = None;
+        df.labels = Some(Arc::new(Labels::new(name, values)));
+        df.share_labels();
+        Ok(df)
+    }
+
+    /// The label of every row: the values set with `set_index`, or the index
+    /// as `Int` values if there are none.
+    pub(crate) fn labels(&self) -> Cow<'_, Series> {
+        match &self.labels {
+            Some(labels) => Cow::Borrowed(&labels.values),
+            None => Cow::Owned(
+                self.index()
+                    .into_iter()
+                    .map(|idx| Some(Cell::Int(idx as i64)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// the labels set with `set_index`, or labels made from the index
+    pub(crate) fn row_labels(&self) -> Cow<'_, Labels> {
+        match &self.labels {
+            Some(labels) => Cow::Borrowed(&**labels),
+            None => Cow::Owned(Labels::new("index", self.labels().into_owned())),
+        }
+    }
+
+    /// Returns the rows with the label `label`, which is converted into the
+    /// type of the labels. Without labels rows are looked up by their index.
+    pub(crate) fn loc(&self, label: impl Into<Cell>) -> Result<DataFrame> {
+        let labels = self.row_labels();
+        let rows = labels.find(label.into())?.to_vec();
+        Ok(self.take(&rows))
+    }
+
+    /// Returns the rows with a label within `range`, in ascending order of
+    /// their labels. Rows with a missing label are left out.
+    ///
+    /// ```ignore
+    /// let march = sales.set_index("Date")?.loc_range("2024-03-01"..="2024-03-31")?;
+    /// ```
+    pub(crate) fn loc_range<L, R>(&self, range: R) -> Result<DataFrame>
+    where
+        L: Into<Cell> + Clone,
+        R: RangeBounds<L>,
+    {
+        let bound = |bound: Bound<&L>| match bound {
+            Bound::Included(label) => Bound::Included(label.clone().into()),
+            Bound::Excluded(label) => Bound::Excluded(label.clone().into()),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let rows = self
+            .row_labels()
+            .range(bound(range.start_bound()), bound(range.end_bound()))?;
+        Ok(self.take(&rows))
+    }
+
+    /// Returns one row per value of `labels`, in that order: the row with
+    /// that label, or a row of missing values if there is none. The labels of
+    /// the data frame must be unique.
+    pub(crate) fn reindex(&self, labels: &Series) -> Result<DataFrame> {
+        let own = self.row_labels();
+        let rows = own.rows(labels)?;
+        let labels = labels.cast(own.values.d_type(), OnError::Raise)?;
+        let mut df = DataFrame {
+            headers: self.headers.clone(),
+            data: self
+                .data
+                .iter()
+                .map(|(name, series)| (name.clone(), take_or_null(series, &rows)))
+                .collect(),
+            index: None,
+            labels: Some(Arc::new(Labels::new(&own.name, labels))),
+        };
+        df.share_labels();
+        Ok(df)
+    }
+
+    /// Aligns the rows of two data frames by label. Both data frames are
+    /// reindexed to the labels on the `join` side, or to the labels on both
+    /// sides for `Inner` and `Outer`, with the labels of `self` first. Labels
+    /// must be unique on both sides. Arithmetic between columns with
+    /// different labels aligns them with `Join::Outer` by itself.
+    ///
+    /// ```ignore
+    /// let (q1, q2) = q1.align(&q2, Join::Inner)?;
+    /// ```
+    pub(crate) fn align(&self, other: &DataFrame, join: Join) -> Result<(DataFrame, DataFrame)> {
+        let labels = self.row_labels().join(&other.row_labels(), join)?;
+        Ok((
+            self.reindex(&labels.values)?,
+            other.reindex(&labels.values)?,
+        ))
+    }
+}
+
+/// The data frames reindexed to the labels of all of them, the labels of the
+/// first data frame followed by the new labels of every other. Data frames
+/// without labels use their index, see `DataFrame::loc`.
+pub(crate) fn align_all(frames: &[DataFrame]) -> Result<Vec<DataFrame>> {
+    let Some((first, rest)) = frames.split_first() else {
+        return Ok(vec![]);
+    };
+    let mut labels = first.row_labels().into_owned();
+    for frame in rest {
+        labels = labels.join(&frame.row_labels(), Join::Outer)?;
+    }
+    frames
+        .iter()
+        .map(|frame| frame.reindex(&labels.values))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::{
+        csv::{read_csv_from, CsvOptions},
+        fixtures::read,
+        ops::Arith,
+    };
+
+    fn unsorted() -> DataFrame {
+        let source = "SN,Name,Joined,CGPA\n\
+                      3,Jane,2022-09-01,3.0\n\
+                      1,John,2021-09-01,3.5\n\
+                      4,Jane,2023-01-15,\n\
+                      2,Adam,2021-09-01,2.5\n";
+        read(source)
+    }
+
+    fn values(df: &DataFrame, column: &str) -> Vec<String> {
+        df.column(column)
+            .unwrap()
+            .iter()
+            .map(|cell| cell.map_or(String::from("None"), |c| c.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_loc() {
+        let df = unsorted().set_index("Name").unwrap();
+        assert!(df.header("Name").is_err());
+        assert_eq!(values(&df.loc("Jane").unwrap(), "SN"), ["3", "4"]);
+        assert!(matches!(
+            df.loc("Eve"),
+            Err(PandasError::LabelNotFound(label)) if label == "Eve"
+        ));
+
+        let df = unsorted().set_index("SN").unwrap();
+        assert_eq!(values(&df.loc(4).unwrap(), "Name"), ["Jane"]);
+        assert_eq!(values(&df.loc(1.0).unwrap(), "Name"), ["John"]);
+        // labels that only match after losing information are not found
+        assert!(matches!(
+            df.loc(1.5),
+            Err(PandasError::LabelNotFound(label)) if label == "1.5"
+        ));
+        assert!(matches!(
+            df.loc(f64::NAN),
+            Err(PandasError::LabelNotFound(_))
+        ));
+        assert!(matches!(
+            df.loc("one"),
+            Err(PandasError::DTypeMismatch { .. })
+        ));
+
+        // without labels rows are found by their index
+        let df = unsorted().query("SN > 1").unwrap();
+        assert_eq!(values(&df.loc(2).unwrap(), "SN"), ["4"]);
+        assert!(df.loc(1).is_err());
+    }
+
+    #[test]
+    fn test_loc_range() {
+        let df = unsorted().set_index("SN").unwrap();
+        assert_eq!(
+            values(&df.loc_range(2..=3).unwrap(), "Name"),
+            ["Adam", "Jane"]
+        );
+        assert_eq!(
+            values(&df.loc_range(..3).unwrap(), "Name"),
+            ["John", "Adam"]
+        );
+        assert_eq!(values(&df.loc_range(3..).unwrap(), "CGPA"), ["3", "None"]);
+        assert_eq!(df.loc_range(5..).unwrap().shape(), (0, 3));
+        assert!(df.loc_range(1.5..).is_err());
+
+        let df = unsorted().set_index("Joined").unwrap();
+        let joined = df.loc_range("2021-09-01".."2023-01-01").unwrap();
+        assert_eq!(values(&joined, "SN"), ["1", "2", "3"]);
+        assert!(df.loc_range("first".."last").is_err());
+    }
+
+    #[test]
+    fn test_sort_and_reset_labels() {
+        let df = unsorted().set_index("SN").unwrap();
+        let sorted = df.sort_index(true);
+        assert_eq!(values(&sorted, "Name"), ["John", "Adam", "Jane", "Jane"]);
+        assert_eq!(
+            sorted.head(2).to_string(),
+            [
+                "SN  Name  Joined      CGPA",
+                " 1  John  2021-09-01   3.5",
+                " 2  Adam  2021-09-01   2.5",
+            ]
+            .join("\n")
+        );
+
+        let reset = sorted.reset_index(false).unwrap();
+        assert!(reset.labels.is_none());
+        assert_eq!(reset.headers[0].name, "SN");
+        assert_eq!(values(&reset, "SN"), ["1", "2", "3", "4"]);
+        assert_eq!(reset.index(), vec![0, 1, 2, 3]);
+        assert_eq!(sorted.reset_index(true).unwrap().shape(), (4, 3));
+        let names = reset.set_index("Name").unwrap().reset_index(false).unwrap();
+        assert_eq!(names.headers[0].name, "Name");
+        let cgpa = df.column("CGPA").unwrap().clone();
+        let clash = df.with_column("SN", cgpa).unwrap();
+        assert!(matches!(
+            clash.reset_index(false),
+            Err(PandasError::DuplicateColumn(name)) if name == "SN"
+        ));
+    }
+
+    #[test]
+    fn test_align() {
+        let read = |source: &str| {
+            read_csv_from(source.as_bytes(), &CsvOptions::new())
+                .unwrap()
+                .set_index("Product")
+                .unwrap()
+        };
+        let q1 = read("Product,Amount\napple,10\npear,5\nplum,7\n");
+        let q2 = read("Product,Amount\npear,1\nfig,2\napple,3\n");
+
+        let (a, b) = q1.align(&q2, Join::Outer).unwrap();
+        let labels = |df: &DataFrame| {
+            df.labels()
+                .iter()
+                .flatten()
+                .map(|l| l.to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(labels(&a), ["apple", "pear", "plum", "fig"]);
+        assert_eq!(labels(&b), labels(&a));
+        let total = a.column("Amount").unwrap() + b.column("Amount").unwrap();
+        assert_eq!(
+            total.iter().collect::<Vec<_>>(),
+            [Some(Cell::Int(13)), Some(Cell::Int(6)), None, None]
+        );
+        let (a, _) = q1.align(&q2, Join::Inner).unwrap();
+        assert_eq!(labels(&a), ["apple", "pear"]);
+        let (_, b) = q1.align(&q2, Join::Right).unwrap();
+        assert_eq!(values(&b, "Amount"), ["1", "2", "3"]);
+
+        let mut products = q2.labels().into_owned();
+        products.push(Some(Cell::Str("kiwi".into())));
+        assert_eq!(
+            values(&q1.reindex(&products).unwrap(), "Amount"),
+            ["5", "None", "10", "None"]
+        );
+        let duplicates = unsorted().set_index("Name").unwrap();
+        assert!(matches!(
+            duplicates.align(&duplicates, Join::Left),
+            Err(PandasError::DuplicateLabel(label)) if label == "Jane"
+        ));
+    }
+
+    #[test]
+    fn test_arith_aligns_labels() {
+        let read = |source: &str| {
+            read_csv_from(source.as_bytes(), &CsvOptions::new())
+                .unwrap()
+                .set_index("Product")
+                .unwrap()
+        };
+        let q1 = read("Product,Amount,Price\napple,10,2\npear,5,3\nplum,7,1\n");
+        let q2 = read("Product,Amount\npear,1\nfig,2\napple,3\n");
+
+        let total = q1.column("Amount").unwrap() + q2.column("Amount").unwrap();
+        assert_eq!(
+            total.labels().unwrap().iter().collect::<Vec<_>>(),
+            ["apple", "pear", "plum", "fig"].map(|l| Some(Cell::from(l)))
+        );
+        assert_eq!(
+            total.iter().collect::<Vec<_>>(),
+            [Some(Cell::Int(13)), Some(Cell::Int(6)), None, None]
+        );
+        assert_eq!(
+            total.to_string(),
+            "apple    13\npear      6\nplum   None\nfig    None\ndtype: Int"
+        );
+        let more = q1
+            .column("Amount")
+            .unwrap()
+            .gt(q2.column("Amount").unwrap())
+            .unwrap();
+        assert_eq!(more.get(0), Some(Cell::Bool(true)));
+        assert_eq!(more.len(), 4);
+
+        // columns of the same data frame are paired up by position
+        let revenue = q1.column("Amount").unwrap() * q1.column("Price").unwrap();
+        assert_eq!(
+            revenue.iter().collect::<Vec<_>>(),
+            [Some(Cell::Int(20)), Some(Cell::Int(15)), Some(Cell::Int(7))]
+        );
+        let sorted = q1.sort_values(["Amount"], &[true]).unwrap();
+        let doubled = sorted.column("Amount").unwrap() + q1.column("Amount").unwrap();
+        assert_eq!(
+            doubled.iter().collect::<Vec<_>>(),
+            [
+                Some(Cell::Int(10)),
+                Some(Cell::Int(14)),
+                Some(Cell::Int(20))
+            ]
+        );
+        // series without labels are paired up by position
+        let plain = q1.column("Amount").unwrap().take(&[2, 1, 0]) + q2.column("Amount").unwrap();
+        assert_eq!(plain.get(0), Some(Cell::Int(8)));
+        assert!(unsorted()
+            .set_index("Name")
+            .unwrap()
+            .column("SN")
+            .unwrap()
+            .arith(Arith::Add, q1.column("Amount").unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_labels_without_columns() {
+        let df = unsorted().select(["Name"]).unwrap();
+        let names = df.set_index("Name").unwrap();
+        assert_eq!(names.shape(), (4, 0));
+        assert_eq!(names.head(2).shape(), (2, 0));
+        assert_eq!(names.tail(1).to_string(), "Name\nAdam");
+        assert_eq!(names.reset_index(false).unwrap().shape(), (4, 1));
+    }
+}
@@ -0,0 +1,474 @@
+use std::{fmt::Display, rc::Rc};
+
+use super::{
+    csv::{read_csv_with, CsvOptions},
+    error::Result,
+    groupby::Agg,
+    query::Query,
+    DataFrame,
+};
+
+/// Where the rows of a plan come from
+#[derive(Debug, Clone)]
+enum Source {
+    Csv { path: String, options: CsvOptions },
+    Frame(Rc<DataFrame>),
+}
+
+/// A logical plan: a tree of operations with the source at its leaf. Plans
+/// are only executed by `LazyFrame::collect`.
+#[derive(Debug, Clone)]
+enum Plan {
+    /// Reads the source, keeping only `columns` if set and only the rows
+    /// matching `predicate` if set. Both are filled in by `optimize`.
+    Scan {
+        source: Source,
+        columns: Option<Vec<String>>,
+        predicate: Option<Query>,
+    },
+    Select {
+        input: Box<Plan>,
+        columns: Vec<String>,
+    },
+    Filter {
+        input: Box<Plan>,
+        predicate: Query,
+    },
+    GroupBy {
+        input: Box<Plan>,
+        keys: Vec<String>,
+        aggs: Vec<(String, Agg)>,
+    },
+}
+
+/// `a` and `b` combined with `and`, whichever is set otherwise
+fn fuse(a: Option<Query>, b: Option<Query>) -> Option<Query> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.and(&b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// `columns` followed by the names of `more` that are not in it yet
+fn union(mut columns: Vec<String>, more: Vec<String>) -> Vec<String> {
+    for column in more {
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    columns
+}
+
+impl Plan {
+    fn boxed(self) -> Box<Plan> {
+        Box::new(self)
+    }
+
+    /// Filters the rows of the plan with `predicate` as close to the source
+    /// as possible, fusing consecutive filters into one. A filter only moves
+    /// below a `Select` or a `GroupBy` if every column it uses is kept, or
+    /// is a group key.
+    fn push_predicate(self, predicate: Option<Query>) -> Plan {
+        // the filter stays above `plan` unless every column it uses is in `kept`
+        let split = |predicate: Option<Query>, kept: &[String]| match predicate {
+            Some(p) if p.columns().iter().all(|c| kept.contains(c)) => (Some(p), None),
+            p => (None, p),
+        };
+        let (plan, remaining) = match self {
+            Plan::Scan {
+                source,
+                columns,
+                predicate: current,
+            } => (
+                Plan::Scan {
+                    source,
+                    columns,
+                    predicate: fuse(current, predicate),
+                },
+                None,
+            ),
+            Plan::Filter {
+                input,
+                predicate: current,
+            } => (input.push_predicate(fuse(Some(current), predicate)), None),
+            Plan::Select { input, columns } => {
+                let (pushed, remaining) = split(predicate, &columns);
+                let input = input.push_predicate(pushed).boxed();
+                (Plan::Select { input, columns }, remaining)
+            }
+            Plan::GroupBy { input, keys, aggs } => {
+                let (pushed, remaining) = split(predicate, &keys);
+                let input = input.push_predicate(pushed).boxed();
+                (Plan::GroupBy { input, keys, aggs }, remaining)
+            }
+        };
+        match remaining {
+            Some(predicate) => Plan::Filter {
+                input: plan.boxed(),
+                predicate,
+            },
+            None => plan,
+        }
+    }
+
+    /// Makes the source load only the columns that are `needed` by the
+    /// operations above it, every column if `None`.
+    fn push_projection(self, needed: Option<Vec<String>>) -> Plan {
+        match self {
+            Plan::Scan {
+                source, predicate, ..
+            } => Plan::Scan {
+                columns: needed
+                    .map(|n| union(n, predicate.iter().flat_map(Query::columns).collect())),
+                source,
+                predicate,
+            },
+            Plan::Select { input, columns } => Plan::Select {
+                input: input.push_projection(Some(columns.clone())).boxed(),
+                columns,
+            },
+            Plan::Filter { input, predicate } => Plan::Filter {
+                input: input
+                    .push_projection(needed.map(|n| union(n, predicate.columns())))
+                    .boxed(),
+                predicate,
+            },
+            Plan::GroupBy { input, keys, aggs } => {
+                let used = aggs.iter().map(|(column, _)| column.clone()).collect();
+                Plan::GroupBy {
+                    input: input
+                        .push_projection(Some(union(keys.clone(), used)))
+                        .boxed(),
+                    keys,
+                    aggs,
+                }
+            }
+        }
+    }
+
+    fn optimize(self) -> Plan {
+        self.push_predicate(None).push_projection(None)
+    }
+
+    fn execute(self) -> Result<DataFrame> {
+        match self {
+            Plan::Scan {
+                source,
+                columns,
+                predicate,
+            } => {
+                let df = match (source, columns) {
+                    (Source::Csv { path, options }, Some(columns)) => {
+                        read_csv_with(&path, &options.columns(columns))?
+                    }
+                    (Source::Csv { path, options }, None) => read_csv_with(&path, &options)?,
+                    (Source::Frame(df), Some(columns)) => df.select(columns)?,
+                    (Source::Frame(df), None) => Rc::unwrap_or_clone(df),
+                };
+                match predicate {
+                    Some(predicate) => predicate.filter(&df),
+                    None => Ok(df),
+                }
+            }
+            Plan::Select { input, columns } => input.execute()?.select(columns),
+            Plan::Filter { input, predicate } => predicate.filter(&input.execute()?),
+            Plan::GroupBy { input, keys, aggs } => input.execute()?.group_by(keys).agg(aggs),
+        }
+    }
+
+    /// writes the plan with one operation per line, inputs indented below
+    /// the operation that uses them
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let indent = "  ".repeat(depth);
+        match self {
+            Plan::Scan {
+                source,
+                columns,
+                predicate,
+            } => {
+                match source {
+                    Source::Csv { path, .. } => write!(f, "{indent}CSV SCAN {path}")?,
+                    Source::Frame(df) => {
+                        let (rows, columns) = df.shape();
+                        write!(f, "{indent}DATA FRAME {rows} rows x {columns} columns")?
+                    }
+                }
+                if let Some(columns) = columns {
+                    write!(f, "\n{indent}  PROJECT {columns:?}")?;
+                }
+                if let Some(predicate) = predicate {
+                    write!(f, "\n{indent}  SELECTION {predicate}")?;
+                }
+                Ok(())
+            }
+            Plan::Select { input, columns } => {
+                writeln!(f, "{indent}SELECT {columns:?}")?;
+                input.write(f, depth + 1)
+            }
+            Plan::Filter { input, predicate } => {
+                writeln!(f, "{indent}FILTER {predicate}")?;
+                input.write(f, depth + 1)
+            }
+            Plan::GroupBy { input, keys, aggs } => {
+                writeln!(f, "{indent}GROUP BY {keys:?} AGG {aggs:?}")?;
+                input.write(f, depth + 1)
+            }
+        }
+    }
+}
+
+/// A data frame whose operations are recorded as a plan instead of being run
+/// one by one. `collect` optimises the plan and executes it: filters run as
+/// early as possible, consecutive filters are evaluated as one, and a csv
+/// source only loads the columns the plan uses.
+///
+/// ```ignore
+/// let report = pd::scan_csv("students.csv", &CsvOptions::new())
+///     .filter("Age > 19")?
+///     .group_by(["Graduated"])
+///     .agg([("CGPA", Agg::Mean)])
+///     .collect()?;
+/// ```
+#[derive(Debug, Clone)]
+pub(crate) struct LazyFrame {
+    plan: Plan,
+}
+
+/// Groups of a `LazyFrame`, created with `LazyFrame::group_by`
+#[derive(Debug, Clone)]
+pub(crate) struct LazyGroupBy {
+    input: LazyFrame,
+    keys: Vec<String>,
+}
+
+/// A `LazyFrame` that reads the csv file at `path` when it is collected
+pub(crate) fn scan_csv(path: &str, options: &CsvOptions) -> LazyFrame {
+    LazyFrame {
+        plan: Plan::Scan {
+            source: Source::Csv {
+                path: path.to_string(),
+                options: options.clone(),
+            },
+            columns: None,
+            predicate: None,
+        },
+    }
+}
+
+impl DataFrame {
+    /// a `LazyFrame` over the data frame, which is not copied
+    pub(crate) fn lazy(self) -> LazyFrame {
+        LazyFrame {
+            plan: Plan::Scan {
+                source: Source::Frame(Rc::new(self)),
+                columns: None,
+                predicate: None,
+            },
+        }
+    }
+}
+
+impl LazyFrame {
+    /// keeps only the given columns, in that order, see `DataFrame::select`
+    pub(crate) fn select<T, S>(self, columns: T) -> LazyFrame
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        LazyFrame {
+            plan: Plan::Select {
+                input: self.plan.boxed(),
+                columns: columns
+                    .into_iter()
+                    .map(|c| c.as_ref().to_string())
+                    .collect(),
+            },
+        }
+    }
+
+    /// Keeps the rows for which the expression `query` is `true`, see
+    /// `DataFrame::query`. The expression is parsed immediately and checked
+    /// against the columns when the plan is collected.
+    pub(crate) fn filter(self, query: &str) -> Result<LazyFrame> {
+        Ok(LazyFrame {
+            plan: Plan::Filter {
+                input: self.plan.boxed(),
+                predicate: Query::parse(query)?,
+            },
+        })
+    }
+
+    /// groups the rows by the `keys` columns, see `DataFrame::group_by`
+    pub(crate) fn group_by<T, S>(self, keys: T) -> LazyGroupBy
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        LazyGroupBy {
+            input: self,
+            keys: keys.into_iter().map(|k| k.as_ref().to_string()).collect(),
+        }
+    }
+
+    /// The optimised plan, one operation per line. The source is at the
+    /// bottom with the columns it loads (`PROJECT`) and the filter applied
+    /// while reading it (`SELECTION`).
+    pub(crate) fn explain(&self) -> String {
+        LazyFrame {
+            plan: self.plan.clone().optimize(),
+        }
+        .to_string()
+    }
+
+    /// optimises and executes the plan
+    pub(crate) fn collect(self) -> Result<DataFrame> {
+        self.plan.optimize().execute()
+    }
+}
+
+/// The plan as it was written, see `explain` for the optimised plan.
+impl Display for LazyFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.plan.write(f, 0)
+    }
+}
+
+impl LazyGroupBy {
+    /// computes one aggregation per column for every group, see `GroupBy::agg`
+    pub(crate) fn agg<T, S>(self, aggs: T) -> LazyFrame
+    where
+        T: IntoIterator<Item = (S, Agg)>,
+        S: AsRef<str>,
+    {
+        LazyFrame {
+            plan: Plan::GroupBy {
+                input: self.input.plan.boxed(),
+                keys: self.keys,
+                aggs: aggs
+                    .into_iter()
+                    .map(|(column, agg)| (column.as_ref().to_string(), agg))
+                    .collect(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::{csv::read_csv_from, error::PandasError, fixtures::students, Cell};
+
+    const STUDENTS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/sample/students.csv");
+
+    fn names(df: &DataFrame) -> Vec<String> {
+        df.headers.iter().map(|h| h.name.clone()).collect()
+    }
+
+    fn cells(df: &DataFrame, column: &str) -> Vec<Option<Cell>> {
+        df.column(column).unwrap().iter().collect()
+    }
+
+    #[test]
+    fn test_collect_matches_eager() {
+        let lazy = students()
+            .lazy()
+            .select(["Name", "Age", "Graduated"])
+            .filter("Age > 19")
+            .unwrap()
+            .filter("Graduated")
+            .unwrap()
+            .select(["Name"]);
+        let df = lazy.collect().unwrap();
+        let eager = students()
+            .select(["Name", "Age", "Graduated"])
+            .unwrap()
+            .query("Age > 19")
+            .unwrap()
+            .query("Graduated")
+            .unwrap()
+            .select(["Name"])
+            .unwrap();
+        assert_eq!(names(&df), ["Name"]);
+        assert_eq!(df.index(), eager.index());
+        assert_eq!(cells(&df, "Name"), cells(&eager, "Name"));
+    }
+
+    #[test]
+    fn test_explain() {
+        let lazy = students()
+            .lazy()
+            .filter("Age > 18")
+            .unwrap()
+            .group_by(["Graduated"])
+            .agg([("CGPA", Agg::Mean)])
+            .filter("Graduated == false")
+            .unwrap()
+            .select(["CGPA"]);
+        let written = [
+            "SELECT [\"CGPA\"]",
+            "  FILTER Graduated == false",
+            "    GROUP BY [\"Graduated\"] AGG [(\"CGPA\", Mean)]",
+            "      FILTER Age > 18",
+            "        DATA FRAME 5 rows x 6 columns",
+        ];
+        assert_eq!(lazy.to_string(), written.join("\n"));
+        // the filter on a group key moves below the aggregation and is fused
+        let optimized = [
+            "SELECT [\"CGPA\"]",
+            "  GROUP BY [\"Graduated\"] AGG [(\"CGPA\", Mean)]",
+            "    DATA FRAME 5 rows x 6 columns",
+            "      PROJECT [\"Graduated\", \"CGPA\", \"Age\"]",
+            "      SELECTION (Age > 18) and (Graduated == false)",
+        ];
+        assert_eq!(lazy.explain(), optimized.join("\n"));
+        let df = lazy.collect().unwrap();
+        assert_eq!(cells(&df, "CGPA"), [Some(Cell::Float(3.0))]);
+    }
+
+    #[test]
+    fn test_filter_stays_above_aggregates() {
+        let lazy = students()
+            .lazy()
+            .group_by(["Graduated"])
+            .agg([("Age", Agg::Max)])
+            .filter("Age > 21")
+            .unwrap();
+        assert!(lazy.explain().starts_with("FILTER Age > 21\n  GROUP BY"));
+        let df = lazy.collect().unwrap();
+        assert_eq!(cells(&df, "Graduated"), [Some(Cell::Bool(true))]);
+    }
+
+    #[test]
+    fn test_scan_csv() {
+        let lazy = scan_csv(STUDENTS, &CsvOptions::new())
+            .filter("CGPA > 3.815")
+            .unwrap()
+            .select(["First Name"]);
+        let explained = lazy.explain();
+        assert!(
+            explained.ends_with("PROJECT [\"First Name\", \"CGPA\"]\n    SELECTION CGPA > 3.815")
+        );
+        let df = lazy.collect().unwrap();
+        let eager = read_csv_from(
+            std::fs::read(STUDENTS).unwrap().as_slice(),
+            &CsvOptions::new(),
+        )
+        .unwrap()
+        .query("CGPA > 3.815")
+        .unwrap()
+        .select(["First Name"])
+        .unwrap();
+        assert_eq!(df.index(), eager.index());
+        assert_eq!(cells(&df, "First Name"), cells(&eager, "First Name"));
+
+        let missing = scan_csv(STUDENTS, &CsvOptions::new()).select(["Grade"]);
+        assert!(matches!(
+            missing.collect(),
+            Err(PandasError::ColumnNotFound(_))
+        ));
+        assert!(matches!(
+            students().lazy().filter("Age >"),
+            Err(PandasError::Query { position: 5, .. })
+        ));
+    }
+}
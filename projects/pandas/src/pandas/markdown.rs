@@ -0,0 +1,93 @@
+use super::{Cell, DType, DataFrame};
+
+/// escapes the characters that would break a markdown table cell
+fn escape(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', "<br>")
+}
+
+impl DataFrame {
+    /// Renders the data frame as a markdown table with columns in `headers`
+    /// order. Numeric columns are right aligned and missing values are empty.
+    ///
+    /// ```text
+    /// | Name | Age |
+    /// | :--- | --: |
+    /// | John |  20 |
+    /// ```
+    pub(crate) fn to_markdown(&self) -> String {
+        let rows = (0..self.shape().0)
+            .map(|row| {
+                self.get_item_at(row)
+                    .iter()
+                    .map(|cell| match cell {
+                        Some(Cell::Float(v)) => format!("{v:?}"),
+                        Some(cell) => escape(&cell.to_string()),
+                        None => String::new(),
+                    })
+                    .collect::<Vec<String>>()
+            })
+            .collect::<Vec<Vec<String>>>();
+        let names = self
+            .headers
+            .iter()
+            .map(|h| escape(&h.name))
+            .collect::<Vec<String>>();
+        let widths = names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                rows.iter()
+                    .map(|row| row[idx].chars().count())
+                    .chain([name.chars().count(), 3])
+                    .max()
+                    .unwrap_or(3)
+            })
+            .collect::<Vec<usize>>();
+        let numeric = self
+            .headers
+            .iter()
+            .map(|h| matches!(h.d_type, DType::Int | DType::Float))
+            .collect::<Vec<bool>>();
+
+        let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        let pad = |idx: usize, value: &String| match numeric[idx] {
+            true => format!("{value:>width$}", width = widths[idx]),
+            false => format!("{value:<width$}", width = widths[idx]),
+        };
+        let mut out = line(names.iter().enumerate().map(|(i, n)| pad(i, n)).collect());
+        out.push_str(&line(
+            widths
+                .iter()
+                .zip(&numeric)
+                .map(|(width, numeric)| match numeric {
+                    true => format!("{}:", "-".repeat(width - 1)),
+                    false => format!(":{}", "-".repeat(width - 1)),
+                })
+                .collect(),
+        ));
+        for row in &rows {
+            out.push_str(&line(
+                row.iter().enumerate().map(|(i, v)| pad(i, v)).collect(),
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pandas::fixtures::read;
+
+    #[test]
+    fn test_to_markdown() {
+        let source = "Name,Age,CGPA\n\"Doe | John\",20,3.5\nJane,,4\n";
+        let df = read(source);
+        assert_eq!(
+            df.to_markdown(),
+            "| Name        | Age | CGPA |\n\
+             | :---------- | --: | ---: |\n\
+             | Doe \\| John |  20 |  3.5 |\n\
+             | Jane        |     |  4.0 |\n"
+        );
+    }
+}
@@ -0,0 +1,423 @@
+use std::{collections::HashMap, sync::Arc};
+
+use super::{
+    error::{PandasError, Result},
+    label::align_all,
+    DType, DataFrame, HashKey, Header, Labels, OnError, Series,
+};
+
+/// Which rows are kept by `DataFrame::merge`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Join {
+    /// rows whose keys are present on both sides
+    Inner,
+    /// every row of the left data frame
+    Left,
+    /// every row of the right data frame
+    Right,
+    /// every row of both data frames
+    Outer,
+}
+
+/// Direction along which `concat` combines data frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Axis {
+    /// append the rows of every data frame
+    Rows,
+    /// place the columns of every data frame side by side
+    Columns,
+}
+
+/// converts the column `name` into `d_type` if it has a different type
+fn cast_column(df: &DataFrame, name: &str, d_type: DType) -> Result<Series> {
+    let series = df.column(name)?;
+    match df.header(name)?.d_type == d_type {
+        true => Ok(series.clone()),
+        false => series
+            .cast(d_type, OnError::Raise)
+            .map_err(|err| err.in_column(name)),
+    }
+}
+
+/// hashable key of every row, `None` for rows with a missing key value
+fn row_keys(keys: &[Series], len: usize) -> Vec<Option<Vec<HashKey>>> {
+    (0..len)
+        .map(|row| {
+            keys.iter()
+                .map(|s| s.is_valid(row).then(|| s.hash_key(row)))
+                .collect()
+        })
+        .collect()
+}
+
+impl DataFrame {
+    /// Joins the rows of `self` and `other` with equal values in the `on`
+    /// columns, see `merge_with`. Clashing column names are suffixed with `_x`
+    /// and `_y`.
+    ///
+    /// ```ignore
+    /// let df = students.merge(&grades, ["SN"], Join::Left)?;
+    /// ```
+    pub(crate) fn merge<T, S>(&self, other: &DataFrame, on: T, how: Join) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.merge_with(other, on, how, ("_x", "_y"))
+    }
+
+    /// Joins the rows of `self` and `other` with equal values in the `on`
+    /// columns. The result contains the key columns followed by the other
+    /// columns of `self` and then of `other`, columns that exist on both sides
+    /// are renamed with the given suffixes.
+    ///
+    /// Key columns with different types on each side are converted into their
+    /// common type first. Rows with a missing key value never match.
+    pub(crate) fn merge_with<T, S>(
+        &self,
+        other: &DataFrame,
+        on: T,
+        how: Join,
+        suffixes: (&str, &str),
+    ) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let on = on
+            .into_iter()
+            .map(|k| k.as_ref().to_string())
+            .collect::<Vec<String>>();
+        let mut key_headers = vec![];
+        let (mut left_keys, mut right_keys) = (vec![], vec![]);
+        for key in &on {
+            let d_type = self.header(key)?.d_type.common(other.header(key)?.d_type);
+            left_keys.push(cast_column(self, key, d_type)?);
+            right_keys.push(cast_column(other, key, d_type)?);
+            key_headers.push(Header {
+                name: key.clone(),
+                d_type,
+            });
+        }
+        let (left_len, right_len) = (self.shape().0, other.shape().0);
+        let left_rows = row_keys(&left_keys, left_len);
+        let right_rows = row_keys(&right_keys, right_len);
+
+        // positions of the matching rows, `None` when a side has no match
+        let mut pairs: Vec<(Option<usize>, Option<usize>)> = vec![];
+        match how {
+            Join::Inner | Join::Left | Join::Outer => {
+                let mut index: HashMap<&Vec<HashKey>, Vec<usize>> = HashMap::new();
+                right_rows.iter().enumerate().for_each(|(row, key)| {
+                    if let Some(key) = key {
+                        index.entry(key).or_default().push(row);
+                    }
+                });
+                let mut matched = vec![false; right_len];
+                for (row, key) in left_rows.iter().enumerate() {
+                    match key.as_ref().and_then(|key| index.get(key)) {
+                        Some(rows) => rows.iter().for_each(|&r| {
+                            matched[r] = true;
+                            pairs.push((Some(row), Some(r)));
+                        }),
+                        None if how != Join::Inner => pairs.push((Some(row), None)),
+                        None => {}
+                    }
+                }
+                if how == Join::Outer {
+                    pairs.extend(
+                        (0..right_len)
+                            .filter(|&r| !matched[r])
+                            .map(|r| (None, Some(r))),
+                    );
+                }
+            }
+            Join::Right => {
+                let mut index: HashMap<&Vec<HashKey>, Vec<usize>> = HashMap::new();
+                left_rows.iter().enumerate().for_each(|(row, key)| {
+                    if let Some(key) = key {
+                        index.entry(key).or_default().push(row);
+                    }
+                });
+                for (row, key) in right_rows.iter().enumerate() {
+                    match key.as_ref().and_then(|key| index.get(key)) {
+                        Some(rows) => rows.iter().for_each(|&l| pairs.push((Some(l), Some(row)))),
+                        None => pairs.push((None, Some(row))),
+                    }
+                }
+            }
+        }
+
+        let mut df = DataFrame::new(Vec::<(String, Vec<String>)>::new());
+        for (idx, header) in key_headers.into_iter().enumerate() {
+            let series = Series::from_cells(
+                header.d_type,
+                pairs.iter().map(|pair| match pair {
+                    (Some(l), _) => left_keys[idx].get(*l),
+                    (None, Some(r)) => right_keys[idx].get(*r),
+                    (None, None) => None,
+                }),
+            );
+            df.data.insert(header.name.clone(), series);
+            df.headers.push(header);
+        }
+        let sides = [
+            (self, other, suffixes.0, true),
+            (other, self, suffixes.1, false),
+        ];
+        for (side, opposite, suffix, left) in sides {
+            for header in side.headers.iter().filter(|h| !on.contains(&h.name)) {
+                let series = &side.data[&header.name];
+                let name = match opposite.data.contains_key(&header.name) {
+                    true => format!("{}{suffix}", header.name),
+                    false => header.name.clone(),
+                };
+                if df.data.contains_key(&name) {
+                    return Err(PandasError::DuplicateColumn(name));
+                }
+                let values = Series::from_cells(
+                    header.d_type,
+                    pairs.iter().map(|(l, r)| match left {
+                        true => l.and_then(|l| series.get(l)),
+                        false => r.and_then(|r| series.get(r)),
+                    }),
+                );
+                df.data.insert(name.clone(), values);
+                df.headers.push(Header {
+                    name,
+                    d_type: header.d_type,
+                });
+            }
+        }
+        Ok(df)
+    }
+}
+
+/// Combines data frames along rows or columns.
+///
+/// Along rows, columns are matched by name and ordered by first appearance;
+/// missing columns are filled with `None` and columns with different types are
+/// converted into their common type. Along columns, every data frame must have
+/// the same number of rows and column names must be unique.
+///
+/// If any data frame has labels, see `set_index`, the result has labels too.
+/// Along rows, the labels of every data frame follow each other, with the
+/// index standing in for missing labels. Along columns, rows are matched by
+/// label instead of by position, as with `DataFrame::align` and
+/// `Join::Outer`.
+///
+/// ```ignore
+/// let all = pd::concat(&[first, second], Axis::Rows)?;
+/// ```
+pub(crate) fn concat(frames: &[DataFrame], axis: Axis) -> Result<DataFrame> {
+    let mut df = DataFrame::new(Vec::<(String, Vec<String>)>::new());
+    match axis {
+        Axis::Rows => {
+            for frame in frames {
+                for header in &frame.headers {
+                    match df.headers.iter_mut().find(|h| h.name == header.name) {
+                        Some(h) => h.d_type = h.d_type.common(header.d_type),
+                        None => df.headers.push(header.clone()),
+                    }
+                }
+            }
+            let len = frames.iter().map(|f| f.shape().0).sum();
+            for header in &df.headers {
+                let mut values = Series::with_capacity(header.d_type, len);
+                for frame in frames {
+                    match frame.data.contains_key(&header.name) {
+                        true => cast_column(frame, &header.name, header.d_type)?
+                            .iter()
+                            .for_each(|cell| values.push_cell(cell)),
+                        false => (0..frame.shape().0).for_each(|_| values.push_cell(None)),
+                    }
+                }
+                df.data.insert(header.name.clone(), values);
+            }
+            if let Some(labelled) = frames.iter().find_map(|f| f.labels.as_ref()) {
+                let d_type = frames
+                    .iter()
+                    .map(|f| f.labels().d_type())
+                    .reduce(DType::common)
+                    .unwrap_or(DType::Int);
+                let mut values = Series::with_capacity(d_type, len);
+                for frame in frames {
+                    frame
+                        .labels()
+                        .cast(d_type, OnError::Raise)?
+                        .iter()
+                        .for_each(|cell| values.push_cell(cell));
+                }
+                df.labels = Some(Arc::new(Labels::new(&labelled.name, values)));
+            }
+        }
+        Axis::Columns => {
+            let aligned;
+            let frames = match frames.iter().any(|f| f.labels.is_some()) {
+                true => {
+                    aligned = align_all(frames)?;
+                    df.labels = aligned.first().and_then(|f| f.labels.clone());
+                    &aligned
+                }
+                false => frames,
+            };
+            let len = frames.first().map(|f| f.shape().0).unwrap_or(0);
+            for frame in frames {
+                if frame.shape().0 != len {
+                    return Err(PandasError::LengthMismatch {
+                        expected: len,
+                        found: frame.shape().0,
+                    });
+                }
+                for header in &frame.headers {
+                    if df.data.contains_key(&header.name) {
+                        return Err(PandasError::DuplicateColumn(header.name.clone()));
+                    }
+                    df.headers.push(header.clone());
+                    df.data
+                        .insert(header.name.clone(), frame.data[&header.name].clone());
+                }
+            }
+        }
+    }
+    df.share_labels();
+    Ok(df)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::{fixtures::read, Cell};
+
+    fn column(df: &DataFrame, name: &str) -> Vec<Option<Cell>> {
+        (0..df.shape().0)
+            .map(|idx| df.column(name).unwrap()[idx].clone())
+            .collect()
+    }
+
+    fn names(df: &DataFrame) -> Vec<String> {
+        df.headers.iter().map(|h| h.name.clone()).collect()
+    }
+
+    fn ints(values: &[Option<i64>]) -> Vec<Option<Cell>> {
+        values.iter().map(|v| v.map(Cell::Int)).collect()
+    }
+
+    fn scores() -> DataFrame {
+        read("SN,Name,Score\n1,John,10\n2,Jane,20\n3,Adam,30\n")
+    }
+
+    fn grades() -> DataFrame {
+        read("SN,Score,Grade\n2,2.0,B\n3,3.0,A\n3,3.5,A\n4,4.0,C\n")
+    }
+
+    #[test]
+    fn test_merge_inner() {
+        let df = scores().merge(&grades(), ["SN"], Join::Inner).unwrap();
+        assert_eq!(
+            names(&df),
+            vec!["SN", "Name", "Score_x", "Score_y", "Grade"]
+        );
+        assert_eq!(column(&df, "SN"), ints(&[Some(2), Some(3), Some(3)]));
+        assert_eq!(df.header("Score_y").unwrap().d_type, DType::Float);
+    }
+
+    #[test]
+    fn test_merge_left_right_outer() {
+        let left = scores().merge(&grades(), ["SN"], Join::Left).unwrap();
+        assert_eq!(
+            column(&left, "SN"),
+            ints(&[Some(1), Some(2), Some(3), Some(3)])
+        );
+        assert_eq!(left.column("Grade").unwrap()[0], None);
+
+        let right = scores().merge(&grades(), ["SN"], Join::Right).unwrap();
+        assert_eq!(
+            column(&right, "SN"),
+            ints(&[Some(2), Some(3), Some(3), Some(4)])
+        );
+        assert_eq!(right.column("Name").unwrap()[3], None);
+
+        let outer = scores()
+            .merge_with(&grades(), ["SN"], Join::Outer, ("_student", "_grade"))
+            .unwrap();
+        assert_eq!(
+            column(&outer, "SN"),
+            ints(&[Some(1), Some(2), Some(3), Some(3), Some(4)])
+        );
+        assert_eq!(
+            column(&outer, "Score_student"),
+            ints(&[Some(10), Some(20), Some(30), Some(30), None])
+        );
+    }
+
+    #[test]
+    fn test_merge_multiple_keys_and_dtypes() {
+        let left = read("a,b,x\n1,u,1\n1,v,2\n,u,3\n");
+        let right = read("a,b,y\n1.0,v,true\n,u,false\n");
+        let df = left.merge(&right, ["a", "b"], Join::Inner).unwrap();
+        assert_eq!(df.shape(), (1, 4));
+        assert_eq!(df.header("a").unwrap().d_type, DType::Float);
+        assert_eq!(column(&df, "x"), ints(&[Some(2)]));
+        assert!(matches!(
+            left.merge(&right, ["c"], Join::Inner),
+            Err(PandasError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_concat_rows() {
+        let df = concat(&[scores(), grades()], Axis::Rows).unwrap();
+        assert_eq!(names(&df), vec!["SN", "Name", "Score", "Grade"]);
+        assert_eq!(df.shape(), (7, 4));
+        assert_eq!(df.header("Score").unwrap().d_type, DType::Float);
+        assert_eq!(df.column("Score").unwrap()[0], Some(Cell::Float(10.0)));
+        assert_eq!(df.column("Name").unwrap()[3], None);
+        assert_eq!(df.column("Grade").unwrap()[0], None);
+    }
+
+    #[test]
+    fn test_concat_columns() {
+        let extra = read("Age\n20\n19\n21\n");
+        let df = concat(&[scores(), extra], Axis::Columns).unwrap();
+        assert_eq!(names(&df), vec!["SN", "Name", "Score", "Age"]);
+        assert!(matches!(
+            concat(&[scores(), grades()], Axis::Columns),
+            Err(PandasError::LengthMismatch { .. })
+        ));
+        assert!(matches!(
+            concat(&[scores(), scores()], Axis::Columns),
+            Err(PandasError::DuplicateColumn(_))
+        ));
+    }
+
+    #[test]
+    fn test_concat_labels() {
+        let labelled = scores().set_index("Name").unwrap();
+        let df = concat(&[labelled.clone(), read("Score\n40\n")], Axis::Rows).unwrap();
+        let labels = df.labels.as_ref().unwrap();
+        assert_eq!(labels.name, "Name");
+        assert_eq!(
+            labels.values.iter().collect::<Vec<_>>(),
+            ["John", "Jane", "Adam", "0"].map(|l| Some(Cell::from(l)))
+        );
+        assert!(df.column("Score").unwrap().labels().is_some());
+
+        // along columns rows are matched by label
+        let ages = read("Name,Age\nAdam,21\nJane,19\nEve,22\n")
+            .set_index("Name")
+            .unwrap();
+        let df = concat(&[labelled, ages], Axis::Columns).unwrap();
+        assert_eq!(df.shape(), (4, 3));
+        assert_eq!(
+            column(&df, "Age"),
+            [
+                None,
+                Some(Cell::Int(19)),
+                Some(Cell::Int(21)),
+                Some(Cell::Int(22))
+            ]
+        );
+        assert_eq!(df.loc("Eve").unwrap().column("SN").unwrap().get(0), None);
+    }
+}
@@ -0,0 +1,326 @@
+use super::{
+    error::{PandasError, Result},
+    Cell, DType, DataFrame, OnError, Series,
+};
+
+/// How `fill_null` replaces missing values
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Fill {
+    /// the same value for every missing row
+    Value(Cell),
+    /// the last value before the missing row
+    Forward,
+    /// the first value after the missing row
+    Backward,
+}
+
+/// Which rows `drop_nulls` removes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum How {
+    /// rows with a missing value in any of the columns
+    #[default]
+    Any,
+    /// rows where every column is missing
+    All,
+}
+
+impl Series {
+    /// `Bool` mask that is `true` for every missing value
+    pub(crate) fn is_null(&self) -> Series {
+        (0..self.len())
+            .map(|row| Some(Cell::Bool(!self.is_valid(row))))
+            .collect()
+    }
+
+    /// `Bool` mask that is `true` for every value that is present
+    pub(crate) fn not_null(&self) -> Series {
+        (0..self.len())
+            .map(|row| Some(Cell::Bool(self.is_valid(row))))
+            .collect()
+    }
+
+    /// Replaces missing values. A `Fill::Value` must have the type of the
+    /// series, except that numbers are converted into the common type of the
+    /// series and the value. Missing values without a value before them
+    /// (forward) or after them (backward) are kept.
+    ///
+    /// ```ignore
+    /// let cgpa = df["CGPA".to_string()].fill_null(Fill::Value(Cell::Float(0.0)))?;
+    /// ```
+    pub(crate) fn fill_null(&self, fill: Fill) -> Result<Series> {
+        let d_type = self.d_type();
+        match fill {
+            Fill::Value(value) => {
+                let is_number = |d_type| matches!(d_type, DType::Int | DType::Float);
+                let d_type = match (d_type, value.d_type()) {
+                    // a series without values has no type to keep
+                    (_, found) if self.null_count() == self.len() => found,
+                    (expected, found) if expected == found => expected,
+                    (expected, found) if is_number(expected) && is_number(found) => {
+                        expected.common(found)
+                    }
+                    (expected, found) => {
+                        return Err(PandasError::DTypeMismatch { expected, found })
+                    }
+                };
+                let series = self.cast(d_type, OnError::Coerce)?;
+                Ok(Series::from_cells(
+                    d_type,
+                    series
+                        .iter()
+                        .map(|cell| cell.or_else(|| Some(value.clone()))),
+                ))
+            }
+            Fill::Forward => {
+                let mut last = None;
+                Ok(Series::from_cells(
+                    d_type,
+                    self.iter().map(|cell| {
+                        if cell.is_some() {
+                            last = cell.clone();
+                        }
+                        cell.or_else(|| last.clone())
+                    }),
+                ))
+            }
+            Fill::Backward => {
+                let mut next = None;
+                let mut cells = (0..self.len())
+                    .rev()
+                    .map(|row| {
+                        let cell = self.get(row);
+                        if cell.is_some() {
+                            next = cell.clone();
+                        }
+                        cell.or_else(|| next.clone())
+                    })
+                    .collect::<Vec<Option<Cell>>>();
+                cells.reverse();
+                Ok(Series::from_cells(d_type, cells))
+            }
+        }
+    }
+
+    /// Fills missing numeric values linearly between the closest values
+    /// before and after them, by position. The result is a `Float` series,
+    /// missing values at the start or the end are kept.
+    pub(crate) fn interpolate(&self) -> Result<Series> {
+        if !matches!(self.d_type(), DType::Int | DType::Float) {
+            return Err(PandasError::DTypeMismatch {
+                expected: DType::Float,
+                found: self.d_type(),
+            });
+        }
+        let values = (0..self.len())
+            .map(|row| self.f64_at(row))
+            .collect::<Vec<Option<f64>>>();
+        let mut filled = values.clone();
+        let mut previous: Option<(usize, f64)> = None;
+        for (row, value) in values.iter().enumerate() {
+            let Some(value) = *value else { continue };
+            if let Some((start, first)) = previous {
+                for (gap, filled) in filled.iter_mut().enumerate().take(row).skip(start + 1) {
+                    let step = (gap - start) as f64 / (row - start) as f64;
+                    *filled = Some(first + (value - first) * step);
+                }
+            }
+            previous = Some((row, value));
+        }
+        Ok(Series::from_cells(
+            DType::Float,
+            filled.into_iter().map(|v| v.map(Cell::Float)),
+        ))
+    }
+}
+
+impl DataFrame {
+    /// replaces every column in `columns` with the result of `f`
+    fn map_columns<F>(&self, columns: &[String], f: F) -> Result<DataFrame>
+    where
+        F: Fn(&Series) -> Result<Series>,
+    {
+        let mut df = self.clone();
+        for name in columns {
+            let series = f(self.column(name)?).map_err(|err| err.in_column(name))?;
+            df = df.with_column(name, series)?;
+        }
+        Ok(df)
+    }
+
+    /// names of the given columns, every column if `columns` is `None`
+    pub(crate) fn column_names<T, S>(&self, columns: Option<T>) -> Result<Vec<String>>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        match columns {
+            Some(columns) => columns
+                .into_iter()
+                .map(|name| self.header(name.as_ref()).map(|h| h.name.clone()))
+                .collect(),
+            None => Ok(self.headers.iter().map(|h| h.name.clone()).collect()),
+        }
+    }
+
+    /// Replaces missing values in every column, see `Series::fill_null`. A
+    /// `Fill::Value` only fills the columns it can be stored in, e.g. text
+    /// fills the `Str` columns and a number the `Int` and `Float` columns.
+    pub(crate) fn fill_null(&self, fill: Fill) -> Result<DataFrame> {
+        let columns = self.column_names(None::<[&str; 0]>)?;
+        self.map_columns(&columns, |series| match series.fill_null(fill.clone()) {
+            Err(PandasError::DTypeMismatch { .. }) => Ok(series.clone()),
+            filled => filled,
+        })
+    }
+
+    /// Replaces missing values in the given columns only, every column must
+    /// be able to store a `Fill::Value`.
+    ///
+    /// ```ignore
+    /// let df = df.fill_null_with(["Age", "CGPA"], Fill::Forward)?;
+    /// ```
+    pub(crate) fn fill_null_with<T, S>(&self, columns: T, fill: Fill) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let columns = self.column_names(Some(columns))?;
+        self.map_columns(&columns, |series| series.fill_null(fill.clone()))
+    }
+
+    /// Removes rows with missing values in any or all of the columns, rows
+    /// keep their index.
+    pub(crate) fn drop_nulls(&self, how: How) -> DataFrame {
+        self.drop_nulls_with(how, None::<[&str; 0]>)
+            .expect("every column exists")
+    }
+
+    /// Removes rows with missing values, looking at the `subset` columns only
+    /// if provided.
+    ///
+    /// ```ignore
+    /// let df = df.drop_nulls_with(How::All, Some(["Age", "CGPA"]))?;
+    /// ```
+    pub(crate) fn drop_nulls_with<T, S>(&self, how: How, subset: Option<T>) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let columns = self
+            .column_names(subset)?
+            .iter()
+            .map(|name| &self.data[name])
+            .collect::<Vec<&Series>>();
+        let indices = (0..self.shape().0)
+            .filter(|&row| match how {
+                How::Any => columns.iter().all(|s| s.is_valid(row)),
+                How::All => columns.iter().any(|s| s.is_valid(row)),
+            })
+            .collect::<Vec<usize>>();
+        Ok(self.take(&indices))
+    }
+
+    /// Interpolates the missing values of every `Int` and `Float` column, see
+    /// `Series::interpolate`. Columns without missing values keep their type.
+    pub(crate) fn interpolate(&self) -> DataFrame {
+        let columns = self
+            .headers
+            .iter()
+            .filter(|h| matches!(h.d_type, DType::Int | DType::Float))
+            .filter(|h| self.data[&h.name].null_count() > 0)
+            .map(|h| h.name.clone())
+            .collect::<Vec<String>>();
+        self.map_columns(&columns, Series::interpolate)
+            .expect("only numeric columns are interpolated")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::fixtures::read;
+
+    fn sparse() -> DataFrame {
+        let source = "Name,Age,CGPA\n\
+                      John,20,3.0\n\
+                      Jane,,\n\
+                      Satoshi,,3.5\n\
+                      ,,\n\
+                      Eve,24,\n";
+        read(source)
+    }
+
+    fn column(df: &DataFrame, name: &str) -> Vec<Option<Cell>> {
+        df.column(name).unwrap().iter().collect()
+    }
+
+    #[test]
+    fn test_masks_and_fill() {
+        let df = sparse();
+        let cgpa = df.column("CGPA").unwrap();
+        assert_eq!(cgpa.is_null().get(1), Some(Cell::Bool(true)));
+        assert_eq!(cgpa.not_null().get(1), Some(Cell::Bool(false)));
+        assert_eq!(df.filter(&cgpa.not_null()).unwrap().shape(), (2, 3));
+
+        let age = df.column("Age").unwrap();
+        let filled = age.fill_null(Fill::Value(Cell::Float(0.5))).unwrap();
+        assert_eq!(filled.d_type(), DType::Float);
+        assert_eq!(filled.get(1), Some(Cell::Float(0.5)));
+        assert!(matches!(
+            age.fill_null(Fill::Value(Cell::Str("?".into()))),
+            Err(PandasError::DTypeMismatch { .. })
+        ));
+
+        let forward = df.fill_null_with(["Age"], Fill::Forward).unwrap();
+        assert_eq!(
+            column(&forward, "Age"),
+            [20, 20, 20, 20, 24].map(|v| Some(Cell::Int(v)))
+        );
+        let backward = cgpa.fill_null(Fill::Backward).unwrap();
+        assert_eq!(backward.get(1), Some(Cell::Float(3.5)));
+        assert_eq!(backward.get(4), None);
+    }
+
+    #[test]
+    fn test_fill_mixed_columns() {
+        let df = sparse();
+        let filled = df.fill_null(Fill::Value(Cell::Int(0))).unwrap();
+        assert_eq!(column(&filled, "Age")[1], Some(Cell::Int(0)));
+        assert_eq!(column(&filled, "CGPA")[1], Some(Cell::Float(0.0)));
+        assert_eq!(column(&filled, "Name")[3], None);
+
+        let filled = df.fill_null(Fill::Value(Cell::Str("?".into()))).unwrap();
+        assert_eq!(column(&filled, "Name")[3], Some(Cell::Str("?".into())));
+        assert_eq!(column(&filled, "Age")[1], None);
+        assert!(matches!(
+            df.fill_null_with(["Name", "Age"], Fill::Value(Cell::Int(0))),
+            Err(PandasError::DTypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_drop_nulls() {
+        let df = sparse();
+        assert_eq!(df.drop_nulls(How::Any).shape(), (1, 3));
+        assert_eq!(df.drop_nulls(How::All).index(), vec![0, 1, 2, 4]);
+        let dropped = df.drop_nulls_with(How::Any, Some(["Age"])).unwrap();
+        assert_eq!(dropped.index(), vec![0, 4]);
+        assert!(matches!(
+            df.drop_nulls_with(How::Any, Some(["Grade"])),
+            Err(PandasError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let df = sparse().interpolate();
+        assert_eq!(df.header("Age").unwrap().d_type, DType::Float);
+        assert_eq!(
+            column(&df, "Age"),
+            [20.0, 21.0, 22.0, 23.0, 24.0].map(|v| Some(Cell::Float(v)))
+        );
+        assert_eq!(column(&df, "CGPA")[1], Some(Cell::Float(3.25)));
+        assert_eq!(column(&df, "CGPA")[4], None);
+        assert!(df.column("Name").unwrap().interpolate().is_err());
+    }
+}
@@ -1,102 +1,532 @@
-use std::{collections::HashMap, fmt::Display, fs, ops::Index};
+mod apply;
+mod category;
+mod columnar;
+mod csv;
+mod datetime;
+mod display;
+mod error;
+mod groupby;
+mod infer;
+mod json;
+mod label;
+mod lazy;
+mod markdown;
+mod merge;
+mod missing;
+mod ops;
+mod parallel;
+mod query;
+mod reshape;
+mod select;
+mod sort;
+mod stats;
+mod storage;
+mod unique;
+mod window;
 
-#[derive(Debug, Clone, Copy)]
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    ops::Index,
+    sync::{Arc, OnceLock},
+};
+
+pub(crate) use columnar::{read_columnar_with, ColumnarOptions};
+pub(crate) use csv::{read_csv, read_csv_chunked, read_csv_with, CsvOptions};
+pub(crate) use datetime::{Freq, TimeDelta};
+pub(crate) use display::DisplayOptions;
+use error::{PandasError, Result};
+pub(crate) use groupby::Agg;
+pub(crate) use json::Orient;
+use label::Labels;
+pub(crate) use lazy::scan_csv;
+pub(crate) use merge::{concat, Axis, Join};
+pub(crate) use missing::{Fill, How};
+pub(crate) use parallel::set_threads;
+pub(crate) use sort::NaPosition;
+use storage::{Bitmap, Column};
+pub(crate) use unique::Keep;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum DType {
     Bool,
     Int,
     Float,
     Str,
+    /// calendar dates without a time
+    Date,
+    /// dates with a time of day, without a time zone
+    DateTime,
+    /// text from a small set of distinct values, stored as codes into a
+    /// dictionary of the values
+    Category,
 }
 
-#[derive(Debug, Clone)]
+impl DType {
+    /// the narrowest type both `self` and `other` can be converted into
+    pub(crate) fn common(self, other: DType) -> DType {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (DType::Str | DType::Category, _) | (_, DType::Str | DType::Category) => DType::Str,
+            (DType::Date | DType::DateTime, DType::Date | DType::DateTime) => DType::DateTime,
+            (DType::Date | DType::DateTime, _) | (_, DType::Date | DType::DateTime) => DType::Str,
+            (DType::Float, _) | (_, DType::Float) => DType::Float,
+            _ => DType::Int,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) enum Cell {
     Bool(bool),
-    Int(isize),
+    Int(i64),
     Float(f64),
     Str(String),
+    /// days since 1970-01-01
+    Date(i64),
+    /// seconds since 1970-01-01 00:00:00
+    DateTime(i64),
+    /// a value of a `Category` series
+    Category(String),
 }
 impl Cell {
-    pub(crate) fn to_string(&self) -> String {
+    pub(crate) fn d_type(&self) -> DType {
+        match self {
+            Cell::Bool(_) => DType::Bool,
+            Cell::Int(_) => DType::Int,
+            Cell::Float(_) => DType::Float,
+            Cell::Str(_) => DType::Str,
+            Cell::Date(_) => DType::Date,
+            Cell::DateTime(_) => DType::DateTime,
+            Cell::Category(_) => DType::Category,
+        }
+    }
+
+    /// numeric value of `Bool`, `Int` and `Float` cells
+    pub(crate) fn as_f64(&self) -> Option<f64> {
         match self {
-            Cell::Bool(v) => v.to_string(),
-            Cell::Int(v) => v.to_string(),
-            Cell::Float(v) => v.to_string(),
-            Cell::Str(v) => v.to_string(),
+            Cell::Bool(v) => Some(*v as i64 as f64),
+            Cell::Int(v) => Some(*v as f64),
+            Cell::Float(v) => Some(*v),
+            Cell::Str(_) | Cell::Date(_) | Cell::DateTime(_) | Cell::Category(_) => None,
         }
     }
+
+    /// converts the cell into `d_type`, `None` if the value can not be parsed
+    fn cast(&self, d_type: DType) -> Option<Cell> {
+        Some(match d_type {
+            DType::Bool => Cell::Bool(match self {
+                Cell::Bool(v) => *v,
+                Cell::Int(v) => *v > 0,
+                Cell::Float(v) => *v > 0f64,
+                Cell::Str(v) | Cell::Category(v) => match v.as_str() {
+                    "0" => false,                 // parse 0 as false
+                    "1" => true,                  // parse 1 as true
+                    v => v.parse::<bool>().ok()?, // parse true or false
+                },
+                Cell::Date(_) | Cell::DateTime(_) => return None,
+            }),
+            DType::Int => Cell::Int(match self {
+                Cell::Bool(v) => *v as i64,
+                Cell::Int(v) => *v,
+                Cell::Float(v) => *v as i64,
+                Cell::Str(v) | Cell::Category(v) => v.parse::<i64>().ok()?,
+                Cell::Date(v) | Cell::DateTime(v) => *v,
+            }),
+            DType::Float => Cell::Float(match self {
+                Cell::Bool(v) => (*v as i64) as f64,
+                Cell::Int(v) => *v as f64,
+                Cell::Float(v) => *v,
+                Cell::Str(v) | Cell::Category(v) => v.parse::<f64>().ok()?,
+                Cell::Date(_) | Cell::DateTime(_) => return None,
+            }),
+            DType::Str => Cell::Str(self.to_string()),
+            DType::Date => {
+                let days = match self {
+                    Cell::Int(v) | Cell::Date(v) => *v,
+                    Cell::DateTime(v) => v.div_euclid(datetime::SECONDS_PER_DAY),
+                    Cell::Str(v) | Cell::Category(v) => datetime::parse_iso(v, DType::Date)?,
+                    Cell::Bool(_) | Cell::Float(_) => return None,
+                };
+                // the midnight of every date must be a valid `DateTime`
+                Cell::Date(Some(days).filter(|d| datetime::DATE_RANGE.contains(d))?)
+            }
+            DType::DateTime => Cell::DateTime(match self {
+                Cell::Int(v) | Cell::DateTime(v) => *v,
+                Cell::Date(v) => v.checked_mul(datetime::SECONDS_PER_DAY)?,
+                Cell::Str(v) | Cell::Category(v) => datetime::parse_iso(v, DType::DateTime)?,
+                Cell::Bool(_) | Cell::Float(_) => return None,
+            }),
+            DType::Category => Cell::Category(self.to_string()),
+        })
+    }
 }
 
 impl Display for Cell {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        match self {
+            Cell::Bool(v) => write!(f, "{v}"),
+            Cell::Int(v) => write!(f, "{v}"),
+            Cell::Float(v) => write!(f, "{v}"),
+            Cell::Str(v) | Cell::Category(v) => write!(f, "{v}"),
+            Cell::Date(v) => write!(f, "{}", datetime::format_date(*v)),
+            Cell::DateTime(v) => write!(f, "{}", datetime::format_datetime(*v)),
+        }
+    }
+}
+
+/// Cells of the same `DType` are ordered by value, strings lexicographically.
+/// Cells of different types can not be compared.
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Cell::Bool(a), Cell::Bool(b)) => a.partial_cmp(b),
+            (Cell::Int(a), Cell::Int(b)) => a.partial_cmp(b),
+            (Cell::Float(a), Cell::Float(b)) => a.partial_cmp(b),
+            (Cell::Str(a), Cell::Str(b)) => a.partial_cmp(b),
+            (Cell::Date(a), Cell::Date(b)) => a.partial_cmp(b),
+            (Cell::DateTime(a), Cell::DateTime(b)) => a.partial_cmp(b),
+            (Cell::Category(a), Cell::Category(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(value: bool) -> Self {
+        Cell::Bool(value)
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(value: i64) -> Self {
+        Cell::Int(value)
+    }
+}
+
+impl From<i32> for Cell {
+    fn from(value: i32) -> Self {
+        Cell::Int(value as i64)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Float(value)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(value: &str) -> Self {
+        Cell::Str(value.to_string())
+    }
+}
+
+/// Consistent with `PartialEq`, `-0.0` and `0.0` have the same hash. `NaN`
+/// values hash the same too, use `HashKey` to also find them equal.
+impl Hash for Cell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        HashKey::from(Some(self)).hash(state);
+    }
+}
+
+/// A hashable representation of a cell used to find equal values, floats are
+/// compared by their bits so that `NaN` values are grouped together.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum HashKey {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(u64),
+    Str(String),
+    Date(i64),
+    DateTime(i64),
+}
+
+impl From<Option<&Cell>> for HashKey {
+    fn from(cell: Option<&Cell>) -> Self {
+        match cell {
+            None => HashKey::Null,
+            Some(Cell::Bool(v)) => HashKey::Bool(*v),
+            Some(Cell::Int(v)) => HashKey::Int(*v),
+            // -0.0 and 0.0 are the same value
+            Some(Cell::Float(v)) if *v == 0.0 => HashKey::Float(0f64.to_bits()),
+            Some(Cell::Float(v)) if v.is_nan() => HashKey::Float(f64::NAN.to_bits()),
+            Some(Cell::Float(v)) => HashKey::Float(v.to_bits()),
+            // categories are equal to text with the same value
+            Some(Cell::Str(v) | Cell::Category(v)) => HashKey::Str(v.clone()),
+            Some(Cell::Date(v)) => HashKey::Date(*v),
+            Some(Cell::DateTime(v)) => HashKey::DateTime(*v),
+        }
     }
 }
 
+/// How `as_type` treats values that can not be converted into the target type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum OnError {
+    /// return a `PandasError::Conversion`
+    #[default]
+    Raise,
+    /// replace the value with `None`
+    Coerce,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Header {
     name: String,
     d_type: DType,
 }
 
+/// A column of values of a single `DType`, stored in a buffer of that type
+/// with a validity bitmap marking the rows that hold a value.
 #[derive(Debug, Clone)]
-pub(crate) struct Series(Vec<Option<Cell>>);
+pub(crate) struct Series {
+    values: Column,
+    validity: Bitmap,
+    /// labels of the rows, shared with the data frame the series belongs to
+    labels: Option<Arc<Labels>>,
+    /// the values as cells, built by the first use of `Index`
+    cells: OnceLock<Vec<Option<Cell>>>,
+}
 
+/// Indexing builds a `Cell` for every value on first use, `Series::get` is
+/// cheaper when only a few values are needed.
 impl Index<usize> for Series {
     type Output = Option<Cell>;
 
     fn index(&self, index: usize) -> &Self::Output {
-        &self.0[index]
+        &self.cells.get_or_init(|| self.iter().collect())[index]
+    }
+}
+
+/// The series gets the common type of all values, `Str` if there are none.
+impl FromIterator<Option<Cell>> for Series {
+    fn from_iter<T: IntoIterator<Item = Option<Cell>>>(iter: T) -> Self {
+        let cells = iter.into_iter().collect::<Vec<Option<Cell>>>();
+        let d_type = cells
+            .iter()
+            .flatten()
+            .map(Cell::d_type)
+            .reduce(DType::common)
+            .unwrap_or(DType::Str);
+        Series::from_cells(d_type, cells)
     }
 }
 
 impl Series {
     pub(crate) fn new(data: Vec<String>) -> Self {
-        Self(
-            data.iter()
-                .map(|v| Some(Cell::Str(v.to_string())))
-                .collect(),
-        )
+        let mut values = Column::with_capacity(DType::Str, data.len());
+        data.iter().for_each(|v| values.push_str(v));
+        Self {
+            validity: Bitmap::filled(data.len(), true),
+            values,
+            labels: None,
+            cells: OnceLock::new(),
+        }
+    }
+
+    /// an empty series of type `d_type`
+    pub(crate) fn with_capacity(d_type: DType, capacity: usize) -> Self {
+        Self {
+            values: Column::with_capacity(d_type, capacity),
+            validity: Bitmap::with_capacity(capacity),
+            labels: None,
+            cells: OnceLock::new(),
+        }
     }
+
+    /// Creates a series of type `d_type`, cells of another type are converted
+    /// and become `None` if they can not be.
+    pub(crate) fn from_cells<T>(d_type: DType, cells: T) -> Self
+    where
+        T: IntoIterator<Item = Option<Cell>>,
+    {
+        let cells = cells.into_iter();
+        let mut series = Series::with_capacity(d_type, cells.size_hint().0);
+        cells.for_each(|cell| {
+            series.push_cell(cell.and_then(|c| match c.d_type() == d_type {
+                true => Some(c),
+                false => c.cast(d_type),
+            }))
+        });
+        series
+    }
+
     pub(crate) fn len(&self) -> usize {
-        self.0.len()
+        self.validity.len()
+    }
+
+    pub(crate) fn d_type(&self) -> DType {
+        self.values.d_type()
+    }
+
+    /// whether the row at `idx` holds a value
+    pub(crate) fn is_valid(&self, idx: usize) -> bool {
+        self.validity.get(idx)
+    }
+
+    /// number of missing values
+    pub(crate) fn null_count(&self) -> usize {
+        self.len() - self.validity.count_ones()
+    }
+
+    /// the value at `idx`, `None` if it is missing
+    pub(crate) fn get(&self, idx: usize) -> Option<Cell> {
+        self.is_valid(idx).then(|| self.values.get(idx))
+    }
+
+    /// text of the value at `idx` of a `Str` or `Category` series, without
+    /// copying it
+    pub(crate) fn str_at(&self, idx: usize) -> Option<&str> {
+        let is_text = matches!(self.d_type(), DType::Str | DType::Category);
+        (is_text && self.is_valid(idx)).then(|| self.values.str_at(idx))
+    }
+
+    /// numeric value at `idx` of a `Bool`, `Int` or `Float` series
+    pub(crate) fn f64_at(&self, idx: usize) -> Option<f64> {
+        match self.is_valid(idx) {
+            true => self.values.f64_at(idx),
+            false => None,
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Option<Cell>> + '_ {
+        (0..self.len()).map(|idx| self.get(idx))
+    }
+
+    /// hashable representation of the value at `idx`
+    pub(crate) fn hash_key(&self, idx: usize) -> HashKey {
+        match (&self.values, self.is_valid(idx)) {
+            (_, false) => HashKey::Null,
+            (Column::Str { .. } | Column::Category { .. }, true) => {
+                HashKey::Str(self.values.str_at(idx).to_string())
+            }
+            (values, true) => HashKey::from(Some(&values.get(idx))),
+        }
+    }
+
+    /// bytes used by the values and the validity bitmap
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.values.memory_usage() + self.validity.memory_usage()
     }
+
+    /// Appends a value. A series without values takes the type of `item`,
+    /// otherwise the series is converted into the common type of both first.
+    /// The series loses its labels.
     pub(crate) fn push(&mut self, item: Option<Cell>) {
-        self.0.push(item)
+        self.labels = None;
+        let current = self.d_type();
+        if let Some(d_type) = item.as_ref().map(Cell::d_type).filter(|&t| t != current) {
+            match self.null_count() == self.len() {
+                true => {
+                    self.values = Column::with_capacity(d_type, self.len() + 1);
+                    (0..self.len()).for_each(|_| self.values.push_default());
+                }
+                false => {
+                    *self = self
+                        .cast(current.common(d_type), OnError::Coerce)
+                        .expect("coerced casts never fail");
+                }
+            }
+        }
+        let d_type = self.d_type();
+        self.push_cell(item.and_then(|c| match c.d_type() == d_type {
+            true => Some(c),
+            false => c.cast(d_type),
+        }));
     }
 
-    pub(crate) fn as_type(&mut self, d_type: DType) {
-        self.0 = self
-            .0
-            .iter()
-            .map(|v| match v {
-                Some(cell) => match d_type {
-                    DType::Bool => Some(Cell::Bool(match cell {
-                        Cell::Bool(v) => v.to_owned(),
-                        Cell::Int(v) => v.to_owned() > 0,
-                        Cell::Float(v) => v.to_owned() > 0f64,
-                        Cell::Str(v) => match v.to_owned().as_str() {
-                            "0" => false,                    // parse 0 as false
-                            "1" => true,                     // parse 1 as true
-                            v => v.parse::<bool>().unwrap(), // parse true or false
-                        },
-                    })),
-                    DType::Int => Some(Cell::Int(match cell {
-                        Cell::Bool(v) => v.to_owned() as isize,
-                        Cell::Int(v) => v.to_owned(),
-                        Cell::Float(v) => v.to_owned() as isize,
-                        Cell::Str(v) => v.to_owned().parse::<isize>().unwrap(),
-                    })),
-                    DType::Float => Some(Cell::Float(match cell {
-                        Cell::Bool(v) => (v.to_owned() as isize) as f64,
-                        Cell::Int(v) => v.to_owned() as f64,
-                        Cell::Float(v) => v.to_owned(),
-                        Cell::Str(v) => v.to_owned().parse::<f64>().unwrap(),
-                    })),
-                    DType::Str => Some(Cell::Str(format!("{}", cell))),
+    /// appends a value that has the type of the series
+    fn push_cell(&mut self, item: Option<Cell>) {
+        match &item {
+            Some(cell) => self.values.push(cell),
+            None => self.values.push_default(),
+        }
+        self.validity.push(item.is_some());
+        self.cells.take();
+    }
+
+    /// Parses `value` into the type of the series and appends it, `None` is
+    /// appended as a missing value. Returns `false` and appends nothing if the
+    /// value can not be parsed.
+    pub(crate) fn push_parsed(&mut self, value: Option<&str>) -> bool {
+        match value {
+            Some(value) if !self.values.push_parsed(value) => return false,
+            Some(_) => {}
+            None => self.values.push_default(),
+        }
+        self.validity.push(value.is_some());
+        self.cells.take();
+        true
+    }
+
+    /// Converts every non-null value into `d_type`, returning an error that
+    /// names the row and the offending value if any of them can not be parsed.
+    pub(crate) fn as_type(&mut self, d_type: DType) -> Result<()> {
+        self.as_type_with(d_type, OnError::Raise)
+    }
+
+    /// Converts every non-null value into `d_type`, values that can not be
+    /// parsed are either reported or turned into `None` depending on `errors`.
+    /// The categories of a `Category` series are the distinct values in
+    /// lexicographic order.
+    pub(crate) fn as_type_with(&mut self, d_type: DType, errors: OnError) -> Result<()> {
+        *self = self.cast(d_type, errors)?;
+        Ok(())
+    }
+
+    fn cast(&self, d_type: DType, errors: OnError) -> Result<Series> {
+        if d_type == self.d_type() {
+            return Ok(self.clone());
+        }
+        let mut values = Column::with_capacity(d_type, self.len());
+        let mut validity = self.validity.clone();
+        for row in 0..self.len() {
+            if !validity.get(row) {
+                values.push_default();
+                continue;
+            }
+            // text is parsed in place instead of going through a `Cell`
+            let converted = match &self.values {
+                Column::Str { .. } | Column::Category { .. } => {
+                    values.push_parsed(self.values.str_at(row))
+                }
+                column => match column.get(row).cast(d_type) {
+                    Some(cell) => {
+                        values.push(&cell);
+                        true
+                    }
+                    None => false,
                 },
-                None => None,
-            })
-            .collect()
+            };
+            match (converted, errors) {
+                (true, _) => {}
+                (false, OnError::Coerce) => {
+                    values.push_default();
+                    validity.set(row, false);
+                }
+                (false, OnError::Raise) => {
+                    return Err(PandasError::Conversion {
+                        column: None,
+                        row,
+                        value: self.values.get(row).to_string(),
+                        d_type,
+                    })
+                }
+            }
+        }
+        let series = Series {
+            values,
+            validity,
+            labels: self.labels.clone(),
+            cells: OnceLock::new(),
+        };
+        Ok(match d_type {
+            DType::Category => series.sort_categories(),
+            _ => series,
+        })
     }
 }
 
@@ -104,29 +534,41 @@ impl Series {
 pub(crate) struct DataFrame {
     pub(crate) headers: Vec<Header>,
     pub(crate) data: HashMap<String, Series>,
+    /// original position of every row, `None` when rows are in their default
+    /// `0..n` order
+    pub(crate) index: Option<Vec<usize>>,
+    /// labels of the rows set with `set_index`, shared with every column
+    pub(crate) labels: Option<Arc<Labels>>,
 }
 
+/// Looks a column up by name, e.g. `df["Age".to_string()]`.
+///
+/// # Panics
+///
+/// Panics if there is no column with that name, `DataFrame::column` returns
+/// a `ColumnNotFound` error instead.
 impl Index<String> for DataFrame {
     type Output = Series;
     fn index(&self, index: String) -> &Self::Output {
-        self.data
-            .get(&index)
-            .unwrap_or_else(|| panic!("Wrong column name provided"))
+        self.column(&index).unwrap_or_else(|err| panic!("{err}"))
     }
 }
 
 impl DataFrame {
+    /// number of rows and columns, a data frame without columns has a row
+    /// per label
     pub(crate) fn shape(&self) -> (usize, usize) {
-        let first_header = self.headers.get(0).unwrap().name.clone();
-
-        match self.get(first_header) {
+        match self.headers.first().and_then(|h| self.get(h.name.clone())) {
             Some(s) => (s.len(), self.headers.len()),
-            None => (0, self.headers.len()),
+            None => (
+                self.labels.as_ref().map_or(0, |labels| labels.values.len()),
+                self.headers.len(),
+            ),
         }
     }
-    pub(crate) fn new<T: Clone>(data: T) -> Self
+    pub(crate) fn new<T>(data: T) -> Self
     where
-        T: IntoIterator<Item = (String, Vec<String>)>,
+        T: Clone + IntoIterator<Item = (String, Vec<String>)>,
     {
         let headers = data
             .clone()
@@ -137,117 +579,241 @@ impl DataFrame {
             })
             .collect();
         Self {
-            headers: headers,
+            headers,
             data: data
                 .into_iter()
                 .map(|(k, v)| (k.to_string(), Series::new(v.to_owned())))
                 .collect(),
+            index: None,
+            labels: None,
         }
     }
     pub(crate) fn push(&mut self, row: Vec<Option<Cell>>) {
         if row.len() != self.headers.len() {
             panic!("Row dimension and header dimension mismatch")
         }
-        self.headers.iter().zip(row).for_each(|(header, item)| {
+        let len = self.shape().0;
+        if let Some(index) = self.index.as_mut() {
+            index.push(len);
+        }
+        if let Some(mut labels) = self.labels.take() {
+            // the columns let go of the labels so that they are not copied
+            self.share_labels();
+            Arc::make_mut(&mut labels).push(None);
+            self.labels = Some(labels);
+        }
+        self.headers.iter_mut().zip(row).for_each(|(header, item)| {
             match self.data.get_mut(&header.name) {
                 Some(s) => {
-                    // println!("Series: {:?}", s);
                     s.push(item);
+                    // pushing may have widened the type of the series
+                    header.d_type = s.d_type();
                 }
                 None => println!("Header: {}, value: {:?}", header.name, item),
             };
         });
+        self.share_labels();
+    }
+
+    /// Converts the given columns, see `Series::as_type`. No column is
+    /// modified if any of the conversions fails. Columns are converted in
+    /// parallel, see `set_threads`.
+    pub(crate) fn as_type<T>(&mut self, titles: T) -> Result<()>
+    where
+        T: IntoIterator<Item = (String, DType)>,
+    {
+        self.as_type_with(titles, OnError::Raise)
+    }
+
+    /// Converts the given columns, see `Series::as_type_with`.
+    pub(crate) fn as_type_with<T>(&mut self, titles: T, errors: OnError) -> Result<()>
+    where
+        T: IntoIterator<Item = (String, DType)>,
+    {
+        self.convert(titles, None, errors)
     }
 
-    pub(crate) fn as_type<T>(&mut self, titles: T)
+    /// Converts the given columns like `as_type_with`, text converted into
+    /// `Date` or `DateTime` values is parsed with the `strftime` like
+    /// `format` instead of as ISO dates, see `Series::parse_dates`.
+    ///
+    /// ```ignore
+    /// let dates = [(String::from("Joined"), DType::Date)];
+    /// df.as_type_with_format(dates, "%d/%m/%Y", OnError::Raise)?;
+    /// ```
+    pub(crate) fn as_type_with_format<T>(
+        &mut self,
+        titles: T,
+        format: &str,
+        errors: OnError,
+    ) -> Result<()>
     where
         T: IntoIterator<Item = (String, DType)>,
     {
-        titles.into_iter().for_each(|(title, d_type)| {
-            match self.headers.iter().position(|h| h.name == title) {
-                Some(idx) => {
-                    self.data.entry(title).and_modify(|f| f.as_type(d_type));
-                    self.headers[idx].d_type = d_type;
+        self.convert(titles, Some(format), errors)
+    }
+
+    /// converts the given columns, no column is modified if any of them fails
+    fn convert<T>(&mut self, titles: T, format: Option<&str>, errors: OnError) -> Result<()>
+    where
+        T: IntoIterator<Item = (String, DType)>,
+    {
+        let titles = titles.into_iter().collect();
+        let converted = parallel::map(parallel::threads(), titles, |(title, d_type)| {
+            let column = self.column(&title)?;
+            let series = match (format, d_type) {
+                (Some(format), DType::Date | DType::DateTime) => {
+                    let mut series = column.clone();
+                    series.parse_dates(d_type, format, errors).map(|_| series)
                 }
-                None => panic!("Trying to access non-existent column"),
+                _ => column.cast(d_type, errors),
             };
+            let series = series.map_err(|err| err.in_column(&title))?;
+            Ok((title, d_type, series))
         })
+        .into_iter()
+        .collect::<Result<Vec<(String, DType, Series)>>>()?;
+        converted.into_iter().for_each(|(title, d_type, series)| {
+            if let Some(header) = self.headers.iter_mut().find(|h| h.name == title) {
+                header.d_type = d_type;
+            }
+            self.data.insert(title, series);
+        });
+        self.share_labels();
+        Ok(())
+    }
+
+    /// returns the column with the given name
+    pub(crate) fn column(&self, title: &str) -> Result<&Series> {
+        self.data
+            .get(title)
+            .ok_or_else(|| PandasError::ColumnNotFound(title.to_string()))
+    }
+
+    /// returns the header of the column with the given name
+    pub(crate) fn header(&self, title: &str) -> Result<&Header> {
+        self.headers
+            .iter()
+            .find(|h| h.name == title)
+            .ok_or_else(|| PandasError::ColumnNotFound(title.to_string()))
     }
 
     fn get(&self, title: String) -> Option<&Series> {
         self.data.get(&title)
     }
 
+    /// bytes used by the values of every column
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.data.values().map(Series::memory_usage).sum()
+    }
+
     pub(crate) fn get_item_at(&self, index: usize) -> Vec<Option<Cell>> {
         self.headers
             .iter()
-            .map(|header| self.data.get(&header.name).unwrap()[index].clone())
+            .map(|header| self.data[&header.name].get(index))
             .collect()
     }
+}
 
-    pub(crate) fn describe(&self) {
-        self.headers.iter().for_each(|item| {
-            println!("{:<30}: {:?}", item.name, item.d_type);
-        });
+/// Data frames shared by the tests of every module.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::{
+        csv::{read_csv_from, CsvOptions},
+        DataFrame,
+    };
+
+    /// Reads `source` as CSV with the default options.
+    pub(crate) fn read(source: &str) -> DataFrame {
+        read_csv_from(source.as_bytes(), &CsvOptions::new()).unwrap()
+    }
+
+    /// Five students of two departments, Satoshi without a `CGPA`.
+    pub(crate) fn students() -> DataFrame {
+        read(
+            "SN,Name,Dept,Age,CGPA,Graduated\n\
+             1,John,CS,20,3.5,true\n\
+             2,Jane,EE,19,3.0,false\n\
+             3,Satoshi,CS,21,,false\n\
+             4,Adam,CS,22,2.5,true\n\
+             5,Eve,EE,23,4.0,true\n",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unparsed() -> DataFrame {
+        DataFrame::new([
+            (
+                String::from("Name"),
+                vec![String::from("John"), String::from("Jane")],
+            ),
+            (
+                String::from("Age"),
+                vec![String::from("20"), String::from("N/A")],
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_as_type_raise() {
+        let mut df = unparsed();
+        let err = df
+            .as_type([
+                (String::from("Name"), DType::Str),
+                (String::from("Age"), DType::Int),
+            ])
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            PandasError::Conversion { column: Some(column), row: 1, value, d_type: DType::Int }
+                if column == "Age" && value == "N/A"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "can not convert \"N/A\" at row 1 of column \"Age\" to Int"
+        );
+        // nothing is converted when one of the columns fails
+        assert!(matches!(df.column("Age").unwrap()[0], Some(Cell::Str(_))));
+    }
+
+    #[test]
+    fn test_as_type_coerce() {
+        let mut df = unparsed();
+        df.as_type_with([(String::from("Age"), DType::Int)], OnError::Coerce)
+            .unwrap();
+        assert!(matches!(df.column("Age").unwrap()[0], Some(Cell::Int(20))));
+        assert!(df.column("Age").unwrap()[1].is_none());
+        assert_eq!(df.headers[1].d_type, DType::Int);
+    }
+
+    #[test]
+    fn test_unknown_column() {
+        let mut df = unparsed();
+        let err = df
+            .as_type([(String::from("CGPA"), DType::Float)])
+            .unwrap_err();
+        assert!(matches!(err, PandasError::ColumnNotFound(name) if name == "CGPA"));
+        assert!(df.column("Name").is_ok());
+        assert!(df.column("name").is_err());
+    }
+
+    #[test]
+    fn test_series_as_type() {
+        let mut series = Series::new(vec![String::from("1"), String::from("true")]);
+        let err = series.as_type(DType::Int).unwrap_err();
+        assert!(matches!(
+            err,
+            PandasError::Conversion {
+                column: None,
+                row: 1,
+                ..
+            }
+        ));
+        series.as_type(DType::Bool).unwrap();
+        assert!(matches!(series[1], Some(Cell::Bool(true))));
     }
-    pub(crate) fn head(&self, mut n: usize) {
-        if n > self.shape().0 {
-            n = self.shape().0
-        }
-        let titles = self
-            .headers
-            .iter()
-            .map(|h| format!("{:^20}", h.name))
-            .collect::<Vec<String>>()
-            .join(" | ");
-        println!("{}", "-".repeat(titles.len()));
-        println!("{titles}");
-        println!("{}", "-".repeat(titles.len()));
-        for idx in 0..n {
-            println!(
-                "{}",
-                self.get_item_at(idx)
-                    .iter()
-                    .map(|_cell| match _cell {
-                        Some(cell) => format!("{:<20}", cell.to_string()),
-                        None => String::from(" ".repeat(20)),
-                    })
-                    .collect::<Vec<String>>()
-                    .join(" | ")
-            );
-        }
-        println!("{}", "-".repeat(titles.len()));
-    }
-}
-
-pub(crate) fn read_csv(path: &str, headers: bool) -> DataFrame {
-    let mut lines: Vec<String> = fs::read_to_string(path)
-        .unwrap()
-        .lines()
-        .map(|v| v.to_string())
-        .collect();
-
-    let mut df = DataFrame::new(
-        lines
-            .get(0)
-            .unwrap()
-            .split(",")
-            .enumerate()
-            .map(|(idx, name)| match headers {
-                true => (name.trim().to_string(), vec![]),
-                false => (idx.to_string(), vec![]),
-            })
-            .collect::<Vec<(String, Vec<String>)>>(),
-    );
-    if headers {
-        lines.remove(0);
-    }
-    lines.iter().for_each(|line| {
-        let row = line
-            .split(",")
-            .map(|v| Some(Cell::Str(v.trim().to_string())))
-            .collect::<Vec<Option<Cell>>>();
-        df.push(row)
-    });
-    df
 }
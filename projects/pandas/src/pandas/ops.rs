@@ -0,0 +1,550 @@
+use std::{
+    cmp::Ordering,
+    ops::{Add, Div, Mul, Neg, Sub},
+    sync::Arc,
+};
+
+use super::{
+    datetime::SECONDS_PER_DAY,
+    error::{PandasError, Result},
+    storage::Column,
+    Cell, DType, Labels, Series,
+};
+
+/// Arithmetic operations between a series and a scalar or another series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Arith {
+    Add,
+    Sub,
+    Mul,
+    /// true division, the result is always a `Float`
+    Div,
+}
+
+impl Arith {
+    /// `None` if the result does not fit in an `i64`
+    fn int(&self, a: i64, b: i64) -> Option<i64> {
+        match self {
+            Arith::Add => a.checked_add(b),
+            Arith::Sub => a.checked_sub(b),
+            Arith::Mul => a.checked_mul(b),
+            Arith::Div => unreachable!("integer division produces floats"),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Arith::Add => "addition",
+            Arith::Sub => "subtraction",
+            Arith::Mul => "multiplication",
+            Arith::Div => "division",
+        }
+    }
+
+    fn float(&self, a: f64, b: f64) -> f64 {
+        match self {
+            Arith::Add => a + b,
+            Arith::Sub => a - b,
+            Arith::Mul => a * b,
+            Arith::Div => a / b,
+        }
+    }
+}
+
+/// Comparisons between a series and a scalar or another series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Compare {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Compare {
+    /// whether `ordering` satisfies the comparison, `None` for unordered
+    /// values such as `NaN` which are only unequal
+    fn matches(&self, ordering: Option<Ordering>) -> bool {
+        match (self, ordering) {
+            (Compare::Ne, None) => true,
+            (_, None) => false,
+            (Compare::Eq, Some(o)) => o.is_eq(),
+            (Compare::Ne, Some(o)) => o.is_ne(),
+            (Compare::Lt, Some(o)) => o.is_lt(),
+            (Compare::Le, Some(o)) => o.is_le(),
+            (Compare::Gt, Some(o)) => o.is_gt(),
+            (Compare::Ge, Some(o)) => o.is_ge(),
+        }
+    }
+}
+
+/// The right hand side of an operation: a scalar applied to every row or a
+/// series with one value per row.
+#[derive(Debug, Clone)]
+pub(crate) enum Operand<'a> {
+    Scalar(Cell),
+    Series(&'a Series),
+}
+
+impl Operand<'_> {
+    fn d_type(&self) -> DType {
+        match self {
+            Operand::Scalar(cell) => cell.d_type(),
+            Operand::Series(series) => series.d_type(),
+        }
+    }
+
+    fn i64_at(&self, row: usize) -> Option<i64> {
+        match self {
+            Operand::Scalar(Cell::Bool(v)) => Some(*v as i64),
+            Operand::Scalar(Cell::Int(v)) => Some(*v),
+            Operand::Scalar(_) => None,
+            Operand::Series(series) => series.i64_at(row),
+        }
+    }
+
+    fn f64_at(&self, row: usize) -> Option<f64> {
+        match self {
+            Operand::Scalar(cell) => cell.as_f64(),
+            Operand::Series(series) => series.f64_at(row),
+        }
+    }
+
+    fn timestamp_at(&self, row: usize) -> Option<i64> {
+        match self {
+            Operand::Scalar(Cell::Date(v)) => v.checked_mul(SECONDS_PER_DAY),
+            Operand::Scalar(Cell::DateTime(v)) => Some(*v),
+            Operand::Scalar(_) => None,
+            Operand::Series(series) => series.timestamp_at(row),
+        }
+    }
+
+    fn str_at(&self, row: usize) -> Option<&str> {
+        match self {
+            Operand::Scalar(Cell::Str(v) | Cell::Category(v)) => Some(v),
+            Operand::Scalar(_) => None,
+            Operand::Series(series) => series.str_at(row),
+        }
+    }
+
+    fn labels(&self) -> Option<&Arc<Labels>> {
+        match self {
+            Operand::Scalar(_) => None,
+            Operand::Series(series) => series.labels.as_ref(),
+        }
+    }
+}
+
+impl<'a> From<&'a Series> for Operand<'a> {
+    fn from(series: &'a Series) -> Self {
+        Operand::Series(series)
+    }
+}
+
+impl From<Cell> for Operand<'_> {
+    fn from(cell: Cell) -> Self {
+        Operand::Scalar(cell)
+    }
+}
+
+impl From<bool> for Operand<'_> {
+    fn from(value: bool) -> Self {
+        Operand::Scalar(Cell::Bool(value))
+    }
+}
+
+impl From<i32> for Operand<'_> {
+    fn from(value: i32) -> Self {
+        Operand::Scalar(Cell::Int(value as i64))
+    }
+}
+
+impl From<i64> for Operand<'_> {
+    fn from(value: i64) -> Self {
+        Operand::Scalar(Cell::Int(value))
+    }
+}
+
+impl From<f64> for Operand<'_> {
+    fn from(value: f64) -> Self {
+        Operand::Scalar(Cell::Float(value))
+    }
+}
+
+impl From<&str> for Operand<'_> {
+    fn from(value: &str) -> Self {
+        Operand::Scalar(Cell::Str(value.to_string()))
+    }
+}
+
+impl Series {
+    /// integer value at `idx` of a `Bool` or `Int` series
+    pub(crate) fn i64_at(&self, idx: usize) -> Option<i64> {
+        match (&self.values, self.is_valid(idx)) {
+            (Column::Bool(values), true) => Some(values.get(idx) as i64),
+            (Column::Int(values), true) => Some(values[idx]),
+            _ => None,
+        }
+    }
+
+    /// the labels of the result of an operation with `rhs`
+    fn result_labels(&self, rhs: &Operand) -> Option<Arc<Labels>> {
+        self.labels.as_ref().or(rhs.labels()).cloned()
+    }
+
+    /// fails unless `rhs` is a scalar or a series as long as `self`
+    fn check_length(&self, rhs: &Operand) -> Result<()> {
+        match rhs {
+            Operand::Series(series) if series.len() != self.len() => {
+                Err(PandasError::LengthMismatch {
+                    expected: self.len(),
+                    found: series.len(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies `op` to every row, with a scalar or the value at the same
+    /// position of another series as the right hand side. Missing values on
+    /// either side give `None`.
+    ///
+    /// Series with different labels, such as columns of two data frames with
+    /// labels, are first aligned on the labels of both, see `Series::align`,
+    /// so that values with the same label are paired up. The result keeps
+    /// the labels.
+    ///
+    /// `Bool` and `Int` values give `Int` results, except for division which
+    /// always gives `Float`, and any `Float` operand gives `Float` results.
+    /// `Int` results that do not fit in an `i64` fail with
+    /// `PandasError::Overflow`.
+    /// `Str` values can only be added to other strings, which concatenates
+    /// them. Dates are shifted with a `TimeDelta` instead.
+    ///
+    /// ```ignore
+    /// let percent = df["CGPA".to_string()].arith(Arith::Mul, 25)?;
+    /// ```
+    pub(crate) fn arith<'a>(&self, op: Arith, rhs: impl Into<Operand<'a>>) -> Result<Series> {
+        let rhs = rhs.into();
+        if let Operand::Series(other) = rhs {
+            if let Some((lhs, rhs)) = self.align(other)? {
+                return lhs.arith(op, &rhs);
+            }
+        }
+        self.check_length(&rhs)?;
+        let is_int = |d_type| matches!(d_type, DType::Bool | DType::Int);
+        let d_type = match (self.d_type(), rhs.d_type()) {
+            (DType::Str, DType::Str) if op == Arith::Add => DType::Str,
+            // the left hand side is reported first when both are invalid
+            (found @ (DType::Str | DType::Category | DType::Date | DType::DateTime), _)
+            | (_, found @ (DType::Str | DType::Category | DType::Date | DType::DateTime)) => {
+                return Err(PandasError::DTypeMismatch {
+                    expected: DType::Float,
+                    found,
+                })
+            }
+            (a, b) if is_int(a) && is_int(b) && op != Arith::Div => DType::Int,
+            _ => DType::Float,
+        };
+        let mut out = Series::with_capacity(d_type, self.len());
+        for row in 0..self.len() {
+            out.push_cell(match d_type {
+                DType::Int => match (self.i64_at(row), rhs.i64_at(row)) {
+                    (Some(a), Some(b)) => {
+                        let value = op.int(a, b).ok_or_else(|| {
+                            PandasError::Overflow(format!("{} at row {row}", op.name()))
+                        })?;
+                        Some(Cell::Int(value))
+                    }
+                    _ => None,
+                },
+                DType::Float => match (self.f64_at(row), rhs.f64_at(row)) {
+                    (Some(a), Some(b)) => Some(Cell::Float(op.float(a, b))),
+                    _ => None,
+                },
+                _ => match (self.str_at(row), rhs.str_at(row)) {
+                    (Some(a), Some(b)) => Some(Cell::Str(format!("{a}{b}"))),
+                    _ => None,
+                },
+            });
+        }
+        out.labels = self.result_labels(&rhs);
+        Ok(out)
+    }
+
+    /// Negates every numeric value, booleans become `Int`.
+    pub(crate) fn negate(&self) -> Result<Series> {
+        match self.d_type() {
+            DType::Float => self.arith(Arith::Mul, -1.0),
+            DType::Bool | DType::Int => self.arith(Arith::Mul, -1),
+            found => Err(PandasError::DTypeMismatch {
+                expected: DType::Float,
+                found,
+            }),
+        }
+    }
+
+    /// Compares every row with a scalar or the value at the same position of
+    /// another series and returns a `Bool` mask that can be passed to
+    /// `DataFrame::filter`. Missing values on either side give `None`.
+    ///
+    /// Numbers are compared numerically, where `NaN` is only unequal to other
+    /// values, strings lexicographically and dates chronologically. Comparing
+    /// values of different kinds, like a string with a number, is an error.
+    /// Series with different labels are aligned first, as in `arith`.
+    ///
+    /// ```ignore
+    /// let adults = df.filter(&df["Age".to_string()].compare(Compare::Ge, 18)?)?;
+    /// ```
+    pub(crate) fn compare<'a>(&self, op: Compare, rhs: impl Into<Operand<'a>>) -> Result<Series> {
+        let rhs = rhs.into();
+        if let Operand::Series(other) = rhs {
+            if let Some((lhs, rhs)) = self.align(other)? {
+                return lhs.compare(op, &rhs);
+            }
+        }
+        self.check_length(&rhs)?;
+        let (lhs_type, rhs_type) = (self.d_type(), rhs.d_type());
+        let is_date = |d_type| matches!(d_type, DType::Date | DType::DateTime);
+        let is_text = |d_type| matches!(d_type, DType::Str | DType::Category);
+        if is_text(lhs_type) != is_text(rhs_type) || is_date(lhs_type) != is_date(rhs_type) {
+            return Err(PandasError::DTypeMismatch {
+                expected: lhs_type,
+                found: rhs_type,
+            });
+        }
+        let is_int = |d_type| matches!(d_type, DType::Bool | DType::Int);
+        let mut out = Series::with_capacity(DType::Bool, self.len());
+        for row in 0..self.len() {
+            let ordering = match (lhs_type, rhs_type) {
+                (a, _) if is_text(a) => match (self.str_at(row), rhs.str_at(row)) {
+                    (Some(a), Some(b)) => Some(a.partial_cmp(b)),
+                    _ => None,
+                },
+                // dates are compared with date times at midnight
+                (a, _) if is_date(a) => match (self.timestamp_at(row), rhs.timestamp_at(row)) {
+                    (Some(a), Some(b)) => Some(a.partial_cmp(&b)),
+                    _ => None,
+                },
+                // integers are compared exactly instead of as floats
+                (a, b) if is_int(a) && is_int(b) => match (self.i64_at(row), rhs.i64_at(row)) {
+                    (Some(a), Some(b)) => Some(a.partial_cmp(&b)),
+                    _ => None,
+                },
+                _ => match (self.f64_at(row), rhs.f64_at(row)) {
+                    (Some(a), Some(b)) => Some(a.partial_cmp(&b)),
+                    _ => None,
+                },
+            };
+            out.push_cell(ordering.map(|o| Cell::Bool(op.matches(o))));
+        }
+        out.labels = self.result_labels(&rhs);
+        Ok(out)
+    }
+
+    pub(crate) fn eq<'a>(&self, rhs: impl Into<Operand<'a>>) -> Result<Series> {
+        self.compare(Compare::Eq, rhs)
+    }
+
+    pub(crate) fn ne<'a>(&self, rhs: impl Into<Operand<'a>>) -> Result<Series> {
+        self.compare(Compare::Ne, rhs)
+    }
+
+    pub(crate) fn lt<'a>(&self, rhs: impl Into<Operand<'a>>) -> Result<Series> {
+        self.compare(Compare::Lt, rhs)
+    }
+
+    pub(crate) fn le<'a>(&self, rhs: impl Into<Operand<'a>>) -> Result<Series> {
+        self.compare(Compare::Le, rhs)
+    }
+
+    pub(crate) fn gt<'a>(&self, rhs: impl Into<Operand<'a>>) -> Result<Series> {
+        self.compare(Compare::Gt, rhs)
+    }
+
+    pub(crate) fn ge<'a>(&self, rhs: impl Into<Operand<'a>>) -> Result<Series> {
+        self.compare(Compare::Ge, rhs)
+    }
+}
+
+/// Implements an arithmetic operator for series and scalars, see
+/// `Series::arith`.
+macro_rules! impl_arith {
+    ($trait:ident, $method:ident, $op:expr) => {
+        /// Applies the operator to every row, see `Series::arith`.
+        ///
+        /// # Panics
+        ///
+        /// Panics where `Series::arith` returns an error: on operands of
+        /// different lengths, operands that are not numbers or strings,
+        /// labels that appear more than once, or `Int` results that
+        /// overflow. Use `arith` to handle the error instead.
+        impl<'a, T: Into<Operand<'a>>> $trait<T> for &Series {
+            type Output = Series;
+
+            fn $method(self, rhs: T) -> Series {
+                self.arith($op, rhs).unwrap_or_else(|err| panic!("{err}"))
+            }
+        }
+
+        /// Same as the operator on `&Series`, including its panics.
+        impl<'a, T: Into<Operand<'a>>> $trait<T> for Series {
+            type Output = Series;
+
+            fn $method(self, rhs: T) -> Series {
+                (&self).$method(rhs)
+            }
+        }
+    };
+}
+
+impl_arith!(Add, add, Arith::Add);
+impl_arith!(Sub, sub, Arith::Sub);
+impl_arith!(Mul, mul, Arith::Mul);
+impl_arith!(Div, div, Arith::Div);
+
+/// Negates every numeric value, see `Series::negate`.
+///
+/// # Panics
+///
+/// Panics if the series is not numeric or holds `i64::MIN`, use `negate` to
+/// handle the error instead.
+impl Neg for &Series {
+    type Output = Series;
+
+    fn neg(self) -> Series {
+        self.negate().unwrap_or_else(|err| panic!("{err}"))
+    }
+}
+
+impl Neg for Series {
+    type Output = Series;
+
+    fn neg(self) -> Series {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(values: &[Option<Cell>]) -> Series {
+        Series::from_cells(values[0].as_ref().unwrap().d_type(), values.to_vec())
+    }
+
+    fn ints(values: &[Option<i64>]) -> Series {
+        series(&values.iter().map(|v| v.map(Cell::Int)).collect::<Vec<_>>())
+    }
+
+    fn floats(values: &[Option<f64>]) -> Series {
+        series(
+            &values
+                .iter()
+                .map(|v| v.map(Cell::Float))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn cells(series: &Series) -> Vec<Option<Cell>> {
+        series.iter().collect()
+    }
+
+    #[test]
+    fn test_arith_with_scalars() {
+        let age = ints(&[Some(20), None, Some(22)]);
+        let older = &age + 1;
+        assert_eq!(older.d_type(), DType::Int);
+        assert_eq!(cells(&older), cells(&ints(&[Some(21), None, Some(23)])));
+
+        let halves = &age / 2;
+        assert_eq!(
+            cells(&halves),
+            cells(&floats(&[Some(10.0), None, Some(11.0)]))
+        );
+        assert_eq!((&age * 0.5).d_type(), DType::Float);
+        assert_eq!(cells(&-age), cells(&ints(&[Some(-20), None, Some(-22)])));
+
+        let names = series(&[Some(Cell::Str("Jane".into())), None]);
+        assert_eq!(
+            cells(&(names + " Doe")),
+            vec![Some(Cell::Str("Jane Doe".into())), None]
+        );
+    }
+
+    #[test]
+    fn test_arith_between_series() {
+        let a = ints(&[Some(1), Some(2), None]);
+        let b = floats(&[Some(0.5), None, Some(1.0)]);
+        assert_eq!(cells(&(&a + &b)), cells(&floats(&[Some(1.5), None, None])));
+        assert_eq!(cells(&(&a * &a)), cells(&ints(&[Some(1), Some(4), None])));
+        let flags = series(&[Some(Cell::Bool(true)), Some(Cell::Bool(false)), None]);
+        assert_eq!((&a - &flags).d_type(), DType::Int);
+
+        assert!(matches!(
+            a.arith(Arith::Add, &ints(&[Some(1)])),
+            Err(PandasError::LengthMismatch {
+                expected: 3,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            a.arith(Arith::Mul, "x"),
+            Err(PandasError::DTypeMismatch {
+                found: DType::Str,
+                ..
+            })
+        ));
+        let large = ints(&[Some(1), Some(i64::MAX)]);
+        let err = large.arith(Arith::Mul, 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "integer overflow in the multiplication at row 1"
+        );
+        assert!(matches!(
+            ints(&[Some(i64::MIN)]).negate(),
+            Err(PandasError::Overflow(_))
+        ));
+        assert_eq!(
+            cells(&(&large - 1)),
+            cells(&ints(&[Some(0), Some(i64::MAX - 1)]))
+        );
+        let dates = series(&[Some(Cell::Date(0)), None, Some(Cell::Date(1))]);
+        assert!(matches!(
+            dates.arith(Arith::Add, &a),
+            Err(PandasError::DTypeMismatch {
+                found: DType::Date,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_compare() {
+        let cgpa = floats(&[Some(3.5), Some(f64::NAN), None, Some(2.0)]);
+        let mask = cgpa.gt(3).unwrap();
+        assert_eq!(mask.d_type(), DType::Bool);
+        assert_eq!(
+            cells(&mask),
+            vec![
+                Some(Cell::Bool(true)),
+                Some(Cell::Bool(false)),
+                None,
+                Some(Cell::Bool(false))
+            ]
+        );
+        assert_eq!(cgpa.ne(f64::NAN).unwrap().get(1), Some(Cell::Bool(true)));
+        assert_eq!(cgpa.le(&cgpa).unwrap().get(0), Some(Cell::Bool(true)));
+
+        let names = series(&[
+            Some(Cell::Str("Adam".into())),
+            Some(Cell::Str("Jane".into())),
+        ]);
+        assert_eq!(
+            cells(&names.lt("Eve").unwrap()),
+            vec![Some(Cell::Bool(true)), Some(Cell::Bool(false))]
+        );
+        assert!(names.eq(1).is_err());
+    }
+}
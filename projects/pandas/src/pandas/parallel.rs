@@ -0,0 +1,90 @@
+use std::{
+    num::NonZeroUsize,
+    panic,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+/// number of threads set with `set_threads`, `0` for one per core
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets the number of threads that parse csv sources, convert columns and
+/// compute aggregations. `1` runs everything on the calling thread and `0`
+/// restores the default of one thread per core. Results do not depend on the
+/// number of threads.
+///
+/// ```ignore
+/// pd::set_threads(4);
+/// ```
+pub(crate) fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+/// the number of threads set with `set_threads`
+pub(crate) fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        threads => threads,
+    }
+}
+
+/// Applies `f` to every item on up to `threads` threads and returns the
+/// results in the order of the items. Every thread takes a contiguous run of
+/// items, a panic in `f` is resumed on the calling thread.
+pub(crate) fn map<T, R, F>(threads: usize, items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let threads = threads.clamp(1, items.len().max(1));
+    if threads == 1 {
+        return items.into_iter().map(f).collect();
+    }
+    let size = items.len().div_ceil(threads);
+    let mut items = items.into_iter();
+    let runs = (0..threads)
+        .map(|_| items.by_ref().take(size).collect())
+        .collect::<Vec<Vec<T>>>();
+    let f = &f;
+    thread::scope(|scope| {
+        let handles = runs
+            .into_iter()
+            .map(|run| scope.spawn(move || run.into_iter().map(f).collect::<Vec<R>>()))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_keeps_order() {
+        let items = (0..10).collect::<Vec<usize>>();
+        let serial = map(1, items.clone(), |v| v * v);
+        assert_eq!(serial, items.iter().map(|v| v * v).collect::<Vec<_>>());
+        for threads in [2, 3, 10, 64] {
+            assert_eq!(map(threads, items.clone(), |v| v * v), serial);
+        }
+        assert!(map(4, Vec::<usize>::new(), |v| v).is_empty());
+        assert!(threads() >= 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "odd")]
+    fn test_map_resumes_panics() {
+        map(4, vec![2, 4, 5, 6], |v: usize| {
+            assert!(v.is_multiple_of(2), "odd");
+            v
+        });
+    }
+}
@@ -0,0 +1,972 @@
+use std::{borrow::Cow, fmt::Display};
+
+use super::{
+    error::{PandasError, Result},
+    ops::{Arith, Compare, Operand},
+    Cell, DType, DataFrame, Series,
+};
+
+/// words with a meaning in queries, columns with these names must be quoted
+/// with backticks
+const KEYWORDS: [&str; 10] = [
+    "and",
+    "or",
+    "not",
+    "in",
+    "is",
+    "null",
+    "true",
+    "false",
+    "contains",
+    "startswith",
+];
+
+/// operators made of symbols, longer ones first so that `<=` is not read as `<`
+const SYMBOLS: [&str; 15] = [
+    "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "(", ")", "[", "]", ",",
+];
+
+const COMPARISONS: [(&str, Compare); 6] = [
+    ("==", Compare::Eq),
+    ("!=", Compare::Ne),
+    ("<=", Compare::Le),
+    (">=", Compare::Ge),
+    ("<", Compare::Lt),
+    (">", Compare::Gt),
+];
+
+/// Deepest nesting of parentheses, `not` and unary `-` in a query, and
+/// tallest tree of operators, deeper queries would overflow the stack while
+/// they are parsed or evaluated.
+const MAX_DEPTH: usize = 64;
+
+fn error<T>(position: usize, message: impl Into<String>) -> Result<T> {
+    Err(PandasError::Query {
+        position,
+        message: message.into(),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// a column name or a keyword
+    Ident(String),
+    /// a column name in backticks, which may contain spaces
+    Quoted(String),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Symbol(&'static str),
+    End,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(word) => write!(f, "`{word}`"),
+            Token::Quoted(name) => write!(f, "column `{name}`"),
+            Token::Int(v) => write!(f, "{v}"),
+            Token::Float(v) => write!(f, "{v}"),
+            Token::Str(v) => write!(f, "{v:?}"),
+            Token::Symbol(symbol) => write!(f, "`{symbol}`"),
+            Token::End => write!(f, "the end of the query"),
+        }
+    }
+}
+
+/// Splits a query into tokens, each with the byte offset at which it starts.
+fn tokenize(query: &str) -> Result<Vec<(Token, usize)>> {
+    let mut tokens = vec![];
+    let mut chars = query.char_indices().peekable();
+    while let Some(&(start, ch)) = chars.peek() {
+        let mut take_while = |f: fn(char) -> bool| {
+            let mut end = start;
+            while let Some(&(pos, ch)) = chars.peek().filter(|(_, ch)| f(*ch)) {
+                end = pos + ch.len_utf8();
+                chars.next();
+            }
+            &query[start..end]
+        };
+        let token = match ch {
+            ch if ch.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            ch if ch.is_alphabetic() || ch == '_' => {
+                Token::Ident(take_while(|ch| ch.is_alphanumeric() || ch == '_').to_string())
+            }
+            ch if ch.is_ascii_digit() || ch == '.' => {
+                let text = take_while(|ch| ch.is_ascii_digit() || ch == '.');
+                let number = match text.contains('.') {
+                    true => text.parse().ok().map(Token::Float),
+                    false => text.parse().ok().map(Token::Int),
+                };
+                match number {
+                    Some(number) => number,
+                    None => return error(start, format!("invalid number {text:?}")),
+                }
+            }
+            '\'' | '"' | '`' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, end)) if end == ch => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, escaped)) => text.push(escaped),
+                            None => return error(start, "unterminated string"),
+                        },
+                        Some((_, ch)) => text.push(ch),
+                        None => return error(start, "unterminated string"),
+                    }
+                }
+                match ch {
+                    '`' => Token::Quoted(text),
+                    _ => Token::Str(text),
+                }
+            }
+            ch => match SYMBOLS.iter().find(|s| query[start..].starts_with(**s)) {
+                Some(symbol) => {
+                    symbol.chars().for_each(|_| {
+                        chars.next();
+                    });
+                    Token::Symbol(symbol)
+                }
+                None => return error(start, format!("unexpected character {ch:?}")),
+            },
+        };
+        tokens.push((token, start));
+    }
+    tokens.push((Token::End, query.len()));
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Column(String),
+    Literal(Cell),
+    Not(Box<Node>),
+    Negate(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Arith(Arith, Box<Node>, Box<Node>),
+    Compare(Compare, Box<Node>, Box<Node>),
+    IsIn(Box<Node>, Vec<Cell>),
+    IsNull(Box<Node>),
+    Contains(Box<Node>, String),
+    StartsWith(Box<Node>, String),
+}
+
+/// An expression with the position of its operator, or of its first token
+/// if it has none, for error messages
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    expr: Expr,
+    position: usize,
+    /// number of operators on the longest path down from this node
+    height: usize,
+}
+
+impl Node {
+    fn new(expr: Expr, position: usize) -> Box<Node> {
+        let height = match &expr {
+            Expr::Column(_) | Expr::Literal(_) => 0,
+            Expr::Not(node)
+            | Expr::Negate(node)
+            | Expr::IsIn(node, _)
+            | Expr::IsNull(node)
+            | Expr::Contains(node, _)
+            | Expr::StartsWith(node, _) => node.height + 1,
+            Expr::And(a, b) | Expr::Or(a, b) | Expr::Arith(_, a, b) | Expr::Compare(_, a, b) => {
+                a.height.max(b.height) + 1
+            }
+        };
+        Box::new(Node {
+            expr,
+            position,
+            height,
+        })
+    }
+
+    /// moves the position of the node and of every node below it by `offset`
+    fn shift(&mut self, offset: usize) {
+        let mut nodes = vec![self];
+        while let Some(node) = nodes.pop() {
+            node.position += offset;
+            match &mut node.expr {
+                Expr::Column(_) | Expr::Literal(_) => {}
+                Expr::Not(node)
+                | Expr::Negate(node)
+                | Expr::IsIn(node, _)
+                | Expr::IsNull(node)
+                | Expr::Contains(node, _)
+                | Expr::StartsWith(node, _) => nodes.push(node),
+                Expr::And(a, b)
+                | Expr::Or(a, b)
+                | Expr::Arith(_, a, b)
+                | Expr::Compare(_, a, b) => nodes.extend([a.as_mut(), b.as_mut()]),
+            }
+        }
+    }
+}
+
+/// A recursive descent parser, from the lowest precedence to the highest:
+/// `or`, `and`, `not`, predicates, `+` and `-`, `*` and `/`, unary `-`.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+    /// number of parentheses, `not` and unary `-` around the next token
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.next].0
+    }
+
+    fn position(&self) -> usize {
+        self.tokens[self.next].1
+    }
+
+    fn advance(&mut self) -> (Token, usize) {
+        let token = self.tokens[self.next].clone();
+        // the end token is never consumed
+        self.next = (self.next + 1).min(self.tokens.len() - 1);
+        token
+    }
+
+    /// consumes the keywords if the next tokens are `words`
+    fn keywords(&mut self, words: &[&str]) -> bool {
+        let found = words.iter().enumerate().all(|(i, word)| {
+            matches!(
+                self.tokens.get(self.next + i),
+                Some((Token::Ident(ident), _)) if ident.eq_ignore_ascii_case(word)
+            )
+        });
+        if found {
+            self.next += words.len();
+        }
+        found
+    }
+
+    /// consumes the symbol if it is the next token
+    fn symbol(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Token::Symbol(s) if *s == symbol);
+        if found {
+            self.advance();
+        }
+        found
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<()> {
+        match self.symbol(symbol) {
+            true => Ok(()),
+            false => error(
+                self.position(),
+                format!("expected `{symbol}`, found {}", self.peek()),
+            ),
+        }
+    }
+
+    /// parses a nested expression with `f`, failing past `MAX_DEPTH` levels
+    fn nested<F>(&mut self, position: usize, f: F) -> Result<Box<Node>>
+    where
+        F: FnOnce(&mut Self) -> Result<Box<Node>>,
+    {
+        if self.depth == MAX_DEPTH {
+            let message = format!("the query is nested more than {MAX_DEPTH} levels deep");
+            return error(position, message);
+        }
+        self.depth += 1;
+        let node = f(self);
+        self.depth -= 1;
+        node
+    }
+
+    /// an operator in a chain such as `a + b + c`, which builds a tree one
+    /// level taller for every operator, failing past `MAX_DEPTH` levels
+    fn chained(expr: Expr, position: usize) -> Result<Box<Node>> {
+        let node = Node::new(expr, position);
+        if node.height > MAX_DEPTH {
+            let message = format!("the query is nested more than {MAX_DEPTH} levels deep");
+            return error(position, message);
+        }
+        Ok(node)
+    }
+
+    fn parse(mut self) -> Result<Node> {
+        let node = self.or()?;
+        match self.peek() {
+            Token::End => Ok(*node),
+            token => error(self.position(), format!("unexpected {token}")),
+        }
+    }
+
+    fn or(&mut self) -> Result<Box<Node>> {
+        let mut node = self.and()?;
+        loop {
+            let position = self.position();
+            if !self.keywords(&["or"]) {
+                return Ok(node);
+            }
+            node = Self::chained(Expr::Or(node, self.and()?), position)?;
+        }
+    }
+
+    fn and(&mut self) -> Result<Box<Node>> {
+        let mut node = self.not()?;
+        loop {
+            let position = self.position();
+            if !self.keywords(&["and"]) {
+                return Ok(node);
+            }
+            node = Self::chained(Expr::And(node, self.not()?), position)?;
+        }
+    }
+
+    fn not(&mut self) -> Result<Box<Node>> {
+        let position = self.position();
+        match self.keywords(&["not"]) {
+            true => Ok(Node::new(
+                Expr::Not(self.nested(position, Self::not)?),
+                position,
+            )),
+            false => self.predicate(),
+        }
+    }
+
+    /// a value, optionally followed by a comparison or a test
+    fn predicate(&mut self) -> Result<Box<Node>> {
+        let value = self.sum()?;
+        let position = self.position();
+        if let Some((_, op)) = COMPARISONS
+            .iter()
+            .find(|(s, _)| self.peek() == &Token::Symbol(s))
+        {
+            self.advance();
+            return Ok(Node::new(Expr::Compare(*op, value, self.sum()?), position));
+        }
+        let expr = if self.keywords(&["in"]) {
+            Expr::IsIn(value, self.list()?)
+        } else if self.keywords(&["not", "in"]) {
+            Expr::Not(Node::new(Expr::IsIn(value, self.list()?), position))
+        } else if self.keywords(&["is", "null"]) {
+            Expr::IsNull(value)
+        } else if self.keywords(&["is", "not", "null"]) {
+            Expr::Not(Node::new(Expr::IsNull(value), position))
+        } else if self.keywords(&["contains"]) {
+            Expr::Contains(value, self.string()?)
+        } else if self.keywords(&["startswith"]) {
+            Expr::StartsWith(value, self.string()?)
+        } else {
+            return Ok(value);
+        };
+        Ok(Node::new(expr, position))
+    }
+
+    fn sum(&mut self) -> Result<Box<Node>> {
+        let mut node = self.product()?;
+        loop {
+            let position = self.position();
+            let op = match () {
+                _ if self.symbol("+") => Arith::Add,
+                _ if self.symbol("-") => Arith::Sub,
+                _ => return Ok(node),
+            };
+            node = Self::chained(Expr::Arith(op, node, self.product()?), position)?;
+        }
+    }
+
+    fn product(&mut self) -> Result<Box<Node>> {
+        let mut node = self.unary()?;
+        loop {
+            let position = self.position();
+            let op = match () {
+                _ if self.symbol("*") => Arith::Mul,
+                _ if self.symbol("/") => Arith::Div,
+                _ => return Ok(node),
+            };
+            node = Self::chained(Expr::Arith(op, node, self.unary()?), position)?;
+        }
+    }
+
+    fn unary(&mut self) -> Result<Box<Node>> {
+        let position = self.position();
+        match self.symbol("-") {
+            true => Ok(Node::new(
+                Expr::Negate(self.nested(position, Self::unary)?),
+                position,
+            )),
+            false => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Box<Node>> {
+        let (token, position) = self.advance();
+        let expr = match token {
+            Token::Symbol("(") => {
+                let node = self.nested(position, Self::or)?;
+                self.expect(")")?;
+                return Ok(node);
+            }
+            Token::Quoted(name) => Expr::Column(name),
+            Token::Ident(word) if KEYWORDS.contains(&word.to_lowercase().as_str()) => {
+                match word.to_lowercase().as_str() {
+                    "true" => Expr::Literal(Cell::Bool(true)),
+                    "false" => Expr::Literal(Cell::Bool(false)),
+                    "null" => return error(position, "missing values are tested with `is null`"),
+                    _ => return error(position, format!("expected a value, found `{word}`")),
+                }
+            }
+            Token::Ident(name) => Expr::Column(name),
+            token => match literal(token.clone()) {
+                Some(cell) => Expr::Literal(cell),
+                None => return error(position, format!("expected a value, found {token}")),
+            },
+        };
+        Ok(Node::new(expr, position))
+    }
+
+    /// a list of literals in parentheses or brackets, for `in`
+    fn list(&mut self) -> Result<Vec<Cell>> {
+        let close = match () {
+            _ if self.symbol("(") => ")",
+            _ if self.symbol("[") => "]",
+            _ => {
+                let message = format!("expected a list of values, found {}", self.peek());
+                return error(self.position(), message);
+            }
+        };
+        let mut values = vec![];
+        while !self.symbol(close) {
+            if !values.is_empty() {
+                self.expect(",")?;
+            }
+            let position = self.position();
+            let negative = self.symbol("-");
+            let value = match (self.advance().0, negative) {
+                (Token::Ident(word), false) if word.eq_ignore_ascii_case("true") => {
+                    Some(Cell::Bool(true))
+                }
+                (Token::Ident(word), false) if word.eq_ignore_ascii_case("false") => {
+                    Some(Cell::Bool(false))
+                }
+                (Token::Int(v), true) => Some(Cell::Int(-v)),
+                (Token::Float(v), true) => Some(Cell::Float(-v)),
+                (token, false) => literal(token),
+                _ => None,
+            };
+            match value {
+                Some(value) => values.push(value),
+                None => return error(position, "expected a number, a string or a boolean"),
+            }
+        }
+        Ok(values)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        match self.advance() {
+            (Token::Str(text), _) => Ok(text),
+            (token, position) => error(position, format!("expected a string, found {token}")),
+        }
+    }
+}
+
+/// the value of a number or string token
+fn literal(token: Token) -> Option<Cell> {
+    match token {
+        Token::Int(v) => Some(Cell::Int(v)),
+        Token::Float(v) => Some(Cell::Float(v)),
+        Token::Str(v) => Some(Cell::Str(v)),
+        _ => None,
+    }
+}
+
+/// Values of the same kind can be compared with each other: text, dates or
+/// numbers, which include booleans.
+fn kind(d_type: DType) -> u8 {
+    match d_type {
+        DType::Str | DType::Category => 0,
+        DType::Date | DType::DateTime => 1,
+        DType::Bool | DType::Int | DType::Float => 2,
+    }
+}
+
+fn is_number(d_type: DType) -> bool {
+    kind(d_type) == 2
+}
+
+/// the result of an expression: a value of every row, or a single literal
+enum Value<'a> {
+    Series(Cow<'a, Series>),
+    Scalar(Cell),
+}
+
+impl Value<'_> {
+    /// a series with a value per row, repeating a scalar `len` times
+    fn into_series(self, len: usize) -> Series {
+        match self {
+            Value::Series(series) => series.into_owned(),
+            Value::Scalar(cell) => {
+                Series::from_cells(cell.d_type(), (0..len).map(|_| Some(cell.clone())))
+            }
+        }
+    }
+}
+
+/// `and` of two booleans where `None` is unknown
+fn and(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+/// `or` of two booleans where `None` is unknown
+fn or(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+/// the right hand side of an operation on a series
+fn operand<'v>(value: &'v Value) -> Operand<'v> {
+    match value {
+        Value::Series(series) => series.as_ref().into(),
+        Value::Scalar(cell) => cell.clone().into(),
+    }
+}
+
+/// Combines two `Bool` series with three-valued logic, a missing value is
+/// unknown unless the other value decides the result.
+fn logical(a: &Series, b: &Series, f: fn(Option<bool>, Option<bool>) -> Option<bool>) -> Series {
+    let flag = |series: &Series, row| series.i64_at(row).map(|v| v != 0);
+    (0..a.len())
+        .map(|row| f(flag(a, row), flag(b, row)).map(Cell::Bool))
+        .collect()
+}
+
+impl Node {
+    /// Checks that every column exists and every operator applies to the
+    /// types of its operands, returning the type of the result. Strings
+    /// compared with dates are parsed as dates.
+    fn check(&mut self, df: &DataFrame) -> Result<DType> {
+        let position = self.position;
+        let expect_bool = |node: &mut Node| match node.check(df)? {
+            DType::Bool => Ok(DType::Bool),
+            found => error(
+                node.position,
+                format!("expected a Bool value, found {found:?}"),
+            ),
+        };
+        match &mut self.expr {
+            Expr::Column(name) => match df.header(name) {
+                Ok(header) => Ok(header.d_type),
+                Err(_) => error(position, format!("column {name:?} does not exist")),
+            },
+            Expr::Literal(cell) => Ok(cell.d_type()),
+            Expr::Not(node) => expect_bool(node),
+            Expr::And(a, b) | Expr::Or(a, b) => {
+                expect_bool(a)?;
+                expect_bool(b)
+            }
+            Expr::Negate(node) => match node.check(df)? {
+                DType::Bool | DType::Int => Ok(DType::Int),
+                DType::Float => Ok(DType::Float),
+                found => error(position, format!("can not negate {found:?} values")),
+            },
+            Expr::Arith(op, a, b) => match (*op, a.check(df)?, b.check(df)?) {
+                (Arith::Add, DType::Str, DType::Str) => Ok(DType::Str),
+                (Arith::Div, a, b) if is_number(a) && is_number(b) => Ok(DType::Float),
+                (_, a, b) if is_number(a) && is_number(b) => Ok(a.common(b).common(DType::Int)),
+                (_, a, b) => error(
+                    position,
+                    format!("can not apply {op:?} to {a:?} and {b:?} values"),
+                ),
+            },
+            Expr::Compare(_, a, b) => {
+                let (a_type, b_type) = (a.check(df)?, b.check(df)?);
+                let b_type = resolve_date(b, a_type)?.unwrap_or(b_type);
+                let a_type = resolve_date(a, b_type)?.unwrap_or(a_type);
+                match kind(a_type) == kind(b_type) {
+                    true => Ok(DType::Bool),
+                    false => error(
+                        position,
+                        format!("can not compare {a_type:?} with {b_type:?} values"),
+                    ),
+                }
+            }
+            Expr::IsIn(node, values) => {
+                let d_type = node.check(df)?;
+                for value in values.iter_mut() {
+                    if kind(d_type) == 1 {
+                        *value = parse_date(value, d_type, position)?;
+                    }
+                    if kind(value.d_type()) != kind(d_type) {
+                        let found = value.d_type();
+                        let message = format!("can not compare {d_type:?} with {found:?} values");
+                        return error(position, message);
+                    }
+                }
+                Ok(DType::Bool)
+            }
+            Expr::IsNull(node) => node.check(df).map(|_| DType::Bool),
+            Expr::Contains(node, _) | Expr::StartsWith(node, _) => match node.check(df)? {
+                d_type if kind(d_type) == 0 => Ok(DType::Bool),
+                found => error(position, format!("expected a Str value, found {found:?}")),
+            },
+        }
+    }
+
+    /// Evaluates a checked expression, every operation is applied to whole
+    /// series.
+    fn eval<'a>(&self, df: &'a DataFrame) -> Result<Value<'a>> {
+        let len = df.shape().0;
+        let series = |node: &Node| node.eval(df).map(|value| value.into_series(len));
+        Ok(Value::Series(Cow::Owned(match &self.expr {
+            Expr::Column(name) => return Ok(Value::Series(Cow::Borrowed(df.column(name)?))),
+            Expr::Literal(cell) => return Ok(Value::Scalar(cell.clone())),
+            Expr::Not(node) => {
+                let values = series(node)?;
+                logical(&values, &values, |a, _| a.map(|a| !a))
+            }
+            Expr::And(a, b) => logical(&series(a)?, &series(b)?, and),
+            Expr::Or(a, b) => logical(&series(a)?, &series(b)?, or),
+            Expr::Negate(node) => series(node)?.negate()?,
+            Expr::Arith(op, a, b) => series(a)?.arith(*op, operand(&b.eval(df)?))?,
+            Expr::Compare(op, a, b) => series(a)?.compare(*op, operand(&b.eval(df)?))?,
+            Expr::IsIn(node, values) => {
+                let series = series(node)?;
+                // missing values are unknown, even for an empty list
+                let mut mask = (0..len)
+                    .map(|row| series.is_valid(row).then_some(Cell::Bool(false)))
+                    .collect::<Series>();
+                for value in values {
+                    let equal = series.compare(Compare::Eq, value.clone())?;
+                    mask = logical(&mask, &equal, or);
+                }
+                mask
+            }
+            Expr::IsNull(node) => series(node)?.is_null(),
+            Expr::Contains(node, pattern) => series(node)?.contains(pattern)?,
+            Expr::StartsWith(node, pattern) => series(node)?.starts_with(pattern)?,
+        })))
+    }
+}
+
+/// parses a string `cell` as a value of the date type `d_type`
+fn parse_date(cell: &Cell, d_type: DType, position: usize) -> Result<Cell> {
+    match cell {
+        Cell::Str(text) => match cell.cast(d_type) {
+            Some(date) => Ok(date),
+            None => error(position, format!("can not read {text:?} as a {d_type:?}")),
+        },
+        cell => Ok(cell.clone()),
+    }
+}
+
+/// turns a string literal compared with a value of a date type into a date,
+/// returning its new type
+fn resolve_date(node: &mut Node, other: DType) -> Result<Option<DType>> {
+    match &mut node.expr {
+        Expr::Literal(cell @ Cell::Str(_)) if kind(other) == 1 => {
+            *cell = parse_date(cell, other, node.position)?;
+            Ok(Some(other))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// A parsed expression, see `DataFrame::query` for the syntax. Columns and
+/// types are checked against each data frame the expression is applied to.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Query {
+    text: String,
+    node: Node,
+}
+
+impl Query {
+    pub(crate) fn parse(text: &str) -> Result<Query> {
+        let tokens = tokenize(text)?;
+        Ok(Query {
+            text: text.to_string(),
+            node: Parser {
+                tokens,
+                next: 0,
+                depth: 0,
+            }
+            .parse()?,
+        })
+    }
+
+    /// names of the columns the expression uses, in order of first use
+    pub(crate) fn columns(&self) -> Vec<String> {
+        let mut columns = vec![];
+        let mut nodes = vec![&self.node];
+        while let Some(node) = nodes.pop() {
+            match &node.expr {
+                Expr::Column(name) if !columns.contains(name) => columns.push(name.clone()),
+                Expr::Column(_) | Expr::Literal(_) => {}
+                Expr::Not(node)
+                | Expr::Negate(node)
+                | Expr::IsIn(node, _)
+                | Expr::IsNull(node)
+                | Expr::Contains(node, _)
+                | Expr::StartsWith(node, _) => nodes.push(node),
+                // the left hand side is used first
+                Expr::And(a, b)
+                | Expr::Or(a, b)
+                | Expr::Arith(_, a, b)
+                | Expr::Compare(_, a, b) => nodes.extend([b.as_ref(), a.as_ref()]),
+            }
+        }
+        columns
+    }
+
+    /// The expression `(self) and (other)`, built from the parsed
+    /// expressions so that it is never nested too deep to be parsed again.
+    pub(crate) fn and(&self, other: &Query) -> Query {
+        let text = format!("({}) and ({})", self.text, other.text);
+        let (mut lhs, mut rhs) = (self.node.clone(), other.node.clone());
+        lhs.shift(1);
+        rhs.shift(self.text.len() + 8);
+        let node = Node::new(Expr::And(Box::new(lhs), Box::new(rhs)), self.text.len() + 3);
+        Query { text, node: *node }
+    }
+
+    /// type checks the expression against `df` and evaluates it
+    fn eval(&self, df: &DataFrame) -> Result<(Series, DType)> {
+        let mut node = self.node.clone();
+        let d_type = node.check(df)?;
+        Ok((node.eval(df)?.into_series(df.shape().0), d_type))
+    }
+
+    /// the rows of `df` for which the boolean expression is `true`
+    pub(crate) fn filter(&self, df: &DataFrame) -> Result<DataFrame> {
+        let (mask, d_type) = self.eval(df)?;
+        if d_type != DType::Bool {
+            let message = format!("expected a Bool expression, found {d_type:?}");
+            return error(self.node.position, message);
+        }
+        df.filter(&mask)
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Series {
+    /// `Bool` mask that is `true` for text values that contain `pattern`
+    pub(crate) fn contains(&self, pattern: &str) -> Result<Series> {
+        self.match_text(|value| value.contains(pattern))
+    }
+
+    /// `Bool` mask that is `true` for text values that start with `prefix`
+    pub(crate) fn starts_with(&self, prefix: &str) -> Result<Series> {
+        self.match_text(|value| value.starts_with(prefix))
+    }
+
+    fn match_text<F>(&self, f: F) -> Result<Series>
+    where
+        F: Fn(&str) -> bool,
+    {
+        if kind(self.d_type()) != 0 {
+            return Err(PandasError::DTypeMismatch {
+                expected: DType::Str,
+                found: self.d_type(),
+            });
+        }
+        Ok((0..self.len())
+            .map(|row| self.str_at(row).map(|value| Cell::Bool(f(value))))
+            .collect())
+    }
+}
+
+impl DataFrame {
+    /// Evaluates an expression over every row, see `query` for the syntax.
+    ///
+    /// ```ignore
+    /// let percentage = df.eval("CGPA / 4 * 100")?;
+    /// ```
+    pub(crate) fn eval(&self, expr: &str) -> Result<Series> {
+        Query::parse(expr)?.eval(self).map(|(series, _)| series)
+    }
+
+    /// Returns the rows for which the boolean expression `query` is `true`,
+    /// rows where it is `None` are left out.
+    ///
+    /// Columns are referred to by name, or in backticks if the name has
+    /// spaces or is a keyword. Values can be combined with `+ - * /`,
+    /// compared with `== != < <= > >=`, and tested with `in (...)`,
+    /// `not in (...)`, `is null`, `is not null`, `contains 'text'` and
+    /// `startswith 'text'`. Tests are combined with `and`, `or` and `not`.
+    /// Strings are quoted with `'` or `"` and are read as dates when compared
+    /// with a date column.
+    ///
+    /// ```ignore
+    /// let graduates = df.query("Age > 19 and Graduated == true")?;
+    /// let does = df.query("`Last Name` in ('Doe', 'Smith') or CGPA >= 3.8")?;
+    /// ```
+    pub(crate) fn query(&self, query: &str) -> Result<DataFrame> {
+        Query::parse(query)?.filter(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::fixtures::read;
+
+    fn dated() -> DataFrame {
+        let source = "SN,First Name,Age,CGPA,Graduated,Joined\n\
+                      1,John,20,3.5,true,2021-09-01\n\
+                      2,Jane,19,3.8,false,2022-09-01\n\
+                      3,Satoshi,,3.25,true,2020-09-01\n\
+                      4,Adam,22,,false,\n";
+        read(source)
+    }
+
+    fn rows(query: &str) -> Vec<usize> {
+        dated()
+            .query(query)
+            .unwrap_or_else(|err| panic!("{err}"))
+            .index()
+    }
+
+    fn position(query: &str) -> (usize, String) {
+        match dated().query(query) {
+            Err(PandasError::Query { position, message }) => (position, message),
+            result => panic!("expected a query error, found {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_query() {
+        assert_eq!(rows("Age > 19 and Graduated == true"), [0]);
+        assert_eq!(rows("Age >= 20 or CGPA > 3.6"), [0, 1, 3]);
+        assert_eq!(rows("not (Age < 21)"), [3]);
+        assert_eq!(rows("Age * 2 - SN == 39"), [0]);
+        assert_eq!(rows("CGPA / 4 * 100 >= 87.5"), [0, 1]);
+        assert_eq!(rows("-Age < -20"), [3]);
+        assert_eq!(rows("SN in (1, 3) or `First Name` in ['Adam']"), [0, 2, 3]);
+        assert_eq!(rows("SN not in (1, 3)"), [1, 3]);
+        assert_eq!(rows("Age is null or CGPA is null"), [2, 3]);
+        assert_eq!(rows("Age is not null AND CGPA IS NOT NULL"), [0, 1]);
+        assert_eq!(rows("`First Name` contains 'a'"), [1, 2, 3]);
+        assert_eq!(rows("`First Name` startswith \"J\""), [0, 1]);
+        assert_eq!(rows("Joined < '2021-01-01'"), [2]);
+        assert_eq!(rows("Joined in ('2022-09-01')"), [1]);
+        assert_eq!(rows("Graduated"), [0, 2]);
+        // unknown values are only decided by the other side of `or`
+        assert_eq!(rows("Age > 19 or Graduated"), [0, 2, 3]);
+    }
+
+    #[test]
+    fn test_eval() {
+        let df = dated();
+        let age = df.eval("Age + 1").unwrap();
+        assert_eq!(age.d_type(), DType::Int);
+        assert_eq!(age.get(0), Some(Cell::Int(21)));
+        assert_eq!(age.get(2), None);
+        let sum = df.eval("1 + 2").unwrap();
+        assert_eq!(sum.len(), 4);
+        assert_eq!(sum.get(3), Some(Cell::Int(3)));
+    }
+
+    #[test]
+    fn test_columns_and_fusion() {
+        let query = Query::parse("`First Name` contains 'J' and Age + SN > CGPA or Age is null");
+        let query = query.unwrap();
+        assert_eq!(query.columns(), ["First Name", "Age", "SN", "CGPA"]);
+        let fused = Query::parse("Age > 19").unwrap().and(&query);
+        assert_eq!(
+            fused.to_string(),
+            "(Age > 19) and (`First Name` contains 'J' and Age + SN > CGPA or Age is null)"
+        );
+        assert_eq!(fused.filter(&dated()).unwrap().index(), [0]);
+        assert_eq!(Query::parse(&fused.to_string()).unwrap(), fused);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            position("Age > "),
+            (
+                6,
+                String::from("expected a value, found the end of the query")
+            )
+        );
+        assert_eq!(position("Age >> 3").0, 5);
+        assert_eq!(
+            position("Age > 19 Graduated"),
+            (9, String::from("unexpected `Graduated`"))
+        );
+        assert_eq!(
+            position("(Age > 19"),
+            (9, String::from("expected `)`, found the end of the query"))
+        );
+        assert_eq!(
+            position("`First Name` == 'John"),
+            (16, String::from("unterminated string"))
+        );
+        assert_eq!(position("Age ? 3").0, 4);
+        assert_eq!(position("Age == null").0, 7);
+        assert_eq!(position("SN in 1").0, 6);
+
+        let nested = format!("{}Age{}", "(".repeat(64), ")".repeat(64));
+        assert_eq!(rows(&format!("{nested} > 21")), [3]);
+        let nested = format!("{}Age{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert_eq!(
+            position(&nested),
+            (
+                64,
+                String::from("the query is nested more than 64 levels deep")
+            )
+        );
+        assert_eq!(position(&"not ".repeat(100_000)).0, 256);
+        assert_eq!(position(&format!("{}Age", "-".repeat(100_000))).0, 64);
+
+        let chain = format!("{}Age", "Age + ".repeat(64));
+        assert_eq!(rows(&format!("{chain} > 0")), rows("Age > 0"));
+        let chain = format!("{}Age", "Age + ".repeat(200_000));
+        assert_eq!(
+            position(&chain),
+            (
+                388,
+                String::from("the query is nested more than 64 levels deep")
+            )
+        );
+        let chain = format!("Graduated{}", " or Graduated".repeat(200_000));
+        assert_eq!(position(&chain).0, 842);
+    }
+
+    #[test]
+    fn test_type_errors() {
+        assert_eq!(
+            position("Grade > 3"),
+            (0, String::from("column \"Grade\" does not exist"))
+        );
+        assert_eq!(
+            position("Age > 'twenty'"),
+            (4, String::from("can not compare Int with Str values"))
+        );
+        assert_eq!(position("`First Name` * 2 == 4").0, 13);
+        assert_eq!(position("Age contains 'x'").0, 4);
+        assert_eq!(position("Age and Graduated").0, 0);
+        assert_eq!(position("Joined > 'yesterday'").0, 9);
+        assert_eq!(
+            position("Age + 1"),
+            (4, String::from("expected a Bool expression, found Int"))
+        );
+        assert!(matches!(
+            dated().column("SN").unwrap().contains("1"),
+            Err(PandasError::DTypeMismatch { .. })
+        ));
+    }
+}
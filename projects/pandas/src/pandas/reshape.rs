@@ -0,0 +1,446 @@
+use std::{collections::HashMap, sync::Arc};
+
+use super::{
+    error::{PandasError, Result},
+    Agg, Cell, DType, DataFrame, HashKey, Header, Series,
+};
+
+/// The position of the distinct value of every row in order of first
+/// appearance, `None` for missing values, and the first row of every distinct
+/// value.
+fn first_appearance(series: &Series) -> (Vec<Option<usize>>, Vec<usize>) {
+    let mut positions: HashMap<HashKey, usize> = HashMap::new();
+    let mut firsts = vec![];
+    let rows = (0..series.len())
+        .map(|row| {
+            series.is_valid(row).then(|| {
+                *positions.entry(series.hash_key(row)).or_insert_with(|| {
+                    firsts.push(row);
+                    firsts.len() - 1
+                })
+            })
+        })
+        .collect();
+    (rows, firsts)
+}
+
+impl DataFrame {
+    /// Spreads long data into wide form: one row per distinct combination of
+    /// the `index` columns and one column per distinct value of `columns`,
+    /// named after the value, holding `aggfunc` of the `values` of the rows
+    /// in that cell. Cells without rows are `None`, rows with a missing
+    /// `columns` value are left out. Rows and columns are ordered by first
+    /// appearance.
+    ///
+    /// ```ignore
+    /// let wide = df.pivot_table(["Dept"], "Year", "Salary", Agg::Mean)?;
+    /// ```
+    pub(crate) fn pivot_table<T, S>(
+        &self,
+        index: T,
+        columns: &str,
+        values: &str,
+        aggfunc: Agg,
+    ) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let index = index
+            .into_iter()
+            .map(|name| name.as_ref().to_string())
+            .collect::<Vec<String>>();
+        let pivot = self.column(columns)?;
+        let (value_series, value_type) = (self.column(values)?, self.header(values)?.d_type);
+        let output_type = aggfunc.output_type(value_type)?;
+
+        let (pivot_rows, firsts) = first_appearance(pivot);
+        let names = firsts
+            .iter()
+            .filter_map(|&row| pivot.get(row).map(|cell| cell.to_string()))
+            .collect::<Vec<String>>();
+
+        let groups = self.group_by(&index).groups()?;
+        // the rows of every cell of the table, by group and pivot value
+        let mut cells: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (group, rows) in groups.iter().enumerate() {
+            for &row in rows {
+                if let Some(position) = pivot_rows[row] {
+                    cells.entry((group, position)).or_default().push(row);
+                }
+            }
+        }
+        let mut df = DataFrame::new(Vec::<(String, Vec<String>)>::new());
+        for key in &index {
+            let series = &self.data[key];
+            df.headers.push(self.header(key)?.clone());
+            df.data.insert(
+                key.clone(),
+                Series::from_cells(
+                    series.d_type(),
+                    groups.iter().map(|rows| series.get(rows[0])),
+                ),
+            );
+        }
+        for (position, name) in names.iter().enumerate() {
+            if df.data.contains_key(name) {
+                return Err(PandasError::DuplicateColumn(name.clone()));
+            }
+            let aggregated = (0..groups.len())
+                .map(|group| {
+                    let Some(rows) = cells.get(&(group, position)) else {
+                        return Ok(None);
+                    };
+                    let values = rows
+                        .iter()
+                        .filter_map(|&row| value_series.get(row))
+                        .collect::<Vec<Cell>>();
+                    aggfunc.apply(value_type, &values.iter().collect::<Vec<&Cell>>())
+                })
+                .collect::<Result<Vec<Option<Cell>>>>()
+                .map_err(|err| err.in_column(name))?;
+            df.data
+                .insert(name.clone(), Series::from_cells(output_type, aggregated));
+            df.headers.push(Header {
+                name: name.clone(),
+                d_type: output_type,
+            });
+        }
+        Ok(df)
+    }
+
+    /// Gathers wide data into long form, see `melt_with`. The names of the
+    /// gathered columns go into a `variable` column and their values into a
+    /// `value` column.
+    ///
+    /// ```ignore
+    /// let long = df.melt(["Name"], ["Math", "Physics"])?;
+    /// ```
+    pub(crate) fn melt<T, S, U, V>(&self, id_vars: T, value_vars: U) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        U: IntoIterator<Item = V>,
+        V: AsRef<str>,
+    {
+        self.melt_with(id_vars, value_vars, "variable", "value")
+    }
+
+    /// Gathers the `value_vars` columns, every column that is not in
+    /// `id_vars` if empty, into two columns: `var_name` with the name of the
+    /// column and `value_name` with its value. Every row of the result repeats
+    /// the `id_vars` of its original row, rows are ordered by column first.
+    /// Values are converted into the common type of the gathered columns.
+    pub(crate) fn melt_with<T, S, U, V>(
+        &self,
+        id_vars: T,
+        value_vars: U,
+        var_name: &str,
+        value_name: &str,
+    ) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        U: IntoIterator<Item = V>,
+        V: AsRef<str>,
+    {
+        let id_vars = id_vars
+            .into_iter()
+            .map(|name| self.header(name.as_ref()).cloned())
+            .collect::<Result<Vec<Header>>>()?;
+        let mut value_vars = value_vars
+            .into_iter()
+            .map(|name| self.header(name.as_ref()).cloned())
+            .collect::<Result<Vec<Header>>>()?;
+        if value_vars.is_empty() {
+            value_vars = self
+                .headers
+                .iter()
+                .filter(|h| !id_vars.iter().any(|id| id.name == h.name))
+                .cloned()
+                .collect();
+        }
+        if var_name == value_name {
+            return Err(PandasError::DuplicateColumn(value_name.to_string()));
+        }
+        if let Some(h) = id_vars
+            .iter()
+            .find(|h| h.name == var_name || h.name == value_name)
+        {
+            return Err(PandasError::DuplicateColumn(h.name.clone()));
+        }
+
+        let len = self.shape().0;
+        let mut df = DataFrame::new(Vec::<(String, Vec<String>)>::new());
+        for header in &id_vars {
+            let series = &self.data[&header.name];
+            let cells = value_vars.iter().flat_map(|_| series.iter());
+            df.data.insert(
+                header.name.clone(),
+                Series::from_cells(header.d_type, cells),
+            );
+            df.headers.push(header.clone());
+        }
+        let variables = value_vars
+            .iter()
+            .flat_map(|h| (0..len).map(|_| Some(Cell::Str(h.name.clone()))));
+        df.data.insert(
+            var_name.to_string(),
+            Series::from_cells(DType::Str, variables),
+        );
+        df.headers.push(Header {
+            name: var_name.to_string(),
+            d_type: DType::Str,
+        });
+        let d_type = value_vars
+            .iter()
+            .map(|h| h.d_type)
+            .reduce(DType::common)
+            .unwrap_or(DType::Str);
+        let values = value_vars.iter().flat_map(|h| self.data[&h.name].iter());
+        df.data
+            .insert(value_name.to_string(), Series::from_cells(d_type, values));
+        df.headers.push(Header {
+            name: value_name.to_string(),
+            d_type,
+        });
+        Ok(df)
+    }
+
+    /// Moves the columns into the rows, see `stack_with`. The names of the
+    /// columns go into a `variable` column and their values into a `value`
+    /// column.
+    ///
+    /// ```ignore
+    /// let long = df.set_index("Name")?.stack()?;
+    /// ```
+    pub(crate) fn stack(&self) -> Result<DataFrame> {
+        self.stack_with("variable", "value")
+    }
+
+    /// Moves the columns into the rows: one row per row and column with a
+    /// value, ordered by row first, with the name of the column in
+    /// `var_name` and the value in `value_name`. Rows keep their labels, or
+    /// get their index as labels, see `set_index`. Missing values are left
+    /// out and values are converted into the common type of the columns.
+    /// `unstack_with` turns the result back into columns.
+    pub(crate) fn stack_with(&self, var_name: &str, value_name: &str) -> Result<DataFrame> {
+        if var_name == value_name {
+            return Err(PandasError::DuplicateColumn(value_name.to_string()));
+        }
+        let d_type = self
+            .headers
+            .iter()
+            .map(|h| h.d_type)
+            .reduce(DType::common)
+            .unwrap_or(DType::Str);
+        let columns = self
+            .headers
+            .iter()
+            .map(|h| (h.name.as_str(), &self.data[&h.name]))
+            .collect::<Vec<(&str, &Series)>>();
+        let cells = (0..self.shape().0)
+            .flat_map(|row| {
+                columns
+                    .iter()
+                    .filter(move |(_, series)| series.is_valid(row))
+                    .map(move |&(name, series)| (row, name, series))
+            })
+            .collect::<Vec<(usize, &str, &Series)>>();
+
+        let mut df = DataFrame::new(Vec::<(String, Vec<String>)>::new());
+        let variables = cells
+            .iter()
+            .map(|(_, name, _)| Some(Cell::Str(name.to_string())));
+        let values = cells.iter().map(|(row, _, series)| series.get(*row));
+        for (name, series) in [
+            (var_name, Series::from_cells(DType::Str, variables)),
+            (value_name, Series::from_cells(d_type, values)),
+        ] {
+            df.headers.push(Header {
+                name: name.to_string(),
+                d_type: series.d_type(),
+            });
+            df.data.insert(name.to_string(), series);
+        }
+        let rows = cells.iter().map(|(row, _, _)| *row).collect::<Vec<usize>>();
+        df.labels = Some(Arc::new(self.row_labels().take(&rows)));
+        df.share_labels();
+        Ok(df)
+    }
+
+    /// Spreads the rows into columns, the inverse of `stack`, see
+    /// `unstack_with`.
+    ///
+    /// ```ignore
+    /// let wide = long.unstack()?;
+    /// ```
+    pub(crate) fn unstack(&self) -> Result<DataFrame> {
+        self.unstack_with("variable", "value")
+    }
+
+    /// Spreads the rows into columns: one row per distinct label and one
+    /// column per distinct value of `columns`, named after the value, holding
+    /// the `values` of the row with that label and value. Cells without a row
+    /// are `None`, rows with a missing label or `columns` value are left out.
+    /// Rows and columns are ordered by first appearance. A label that appears
+    /// twice with the same `columns` value is a `DuplicateLabel` error, use
+    /// `pivot_table` to aggregate such rows instead.
+    pub(crate) fn unstack_with(&self, columns: &str, values: &str) -> Result<DataFrame> {
+        let pivot = self.column(columns)?;
+        let (value_series, value_type) = (self.column(values)?, self.header(values)?.d_type);
+        let labels = self.row_labels();
+        let (label_rows, label_firsts) = first_appearance(&labels.values);
+        let (pivot_rows, pivot_firsts) = first_appearance(pivot);
+
+        // the row of every cell of the table, by label and pivot value
+        let mut cells: HashMap<(usize, usize), usize> = HashMap::new();
+        for row in 0..self.shape().0 {
+            if let (Some(label), Some(position)) = (label_rows[row], pivot_rows[row]) {
+                if cells.insert((label, position), row).is_some() {
+                    let label = labels.values.get(row).expect("the label is not missing");
+                    return Err(PandasError::DuplicateLabel(label.to_string()));
+                }
+            }
+        }
+        let mut df = DataFrame::new(Vec::<(String, Vec<String>)>::new());
+        for (position, &first) in pivot_firsts.iter().enumerate() {
+            let name = pivot.get(first).map_or(String::new(), |c| c.to_string());
+            if df.data.contains_key(&name) {
+                return Err(PandasError::DuplicateColumn(name));
+            }
+            let cells = (0..label_firsts.len()).map(|label| {
+                cells
+                    .get(&(label, position))
+                    .and_then(|&row| value_series.get(row))
+            });
+            df.data
+                .insert(name.clone(), Series::from_cells(value_type, cells));
+            df.headers.push(Header {
+                name,
+                d_type: value_type,
+            });
+        }
+        df.labels = Some(Arc::new(labels.take(&label_firsts)));
+        df.share_labels();
+        Ok(df)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::fixtures::read;
+
+    fn scores() -> DataFrame {
+        let source = "Name,Subject,Term,Score\n\
+                      John,Math,1,80\n\
+                      John,Physics,1,70\n\
+                      Jane,Math,1,90\n\
+                      John,Math,2,60\n\
+                      Jane,Art,2,\n";
+        read(source)
+    }
+
+    fn column(df: &DataFrame, name: &str) -> Vec<Option<Cell>> {
+        df.column(name).unwrap().iter().collect()
+    }
+
+    #[test]
+    fn test_pivot_table() {
+        let df = scores()
+            .pivot_table(["Name"], "Subject", "Score", Agg::Mean)
+            .unwrap();
+        let names: Vec<String> = df.headers.iter().map(|h| h.name.clone()).collect();
+        assert_eq!(names, vec!["Name", "Math", "Physics", "Art"]);
+        assert_eq!(
+            column(&df, "Math"),
+            vec![Some(Cell::Float(70.0)), Some(Cell::Float(90.0))]
+        );
+        assert_eq!(column(&df, "Physics"), vec![Some(Cell::Float(70.0)), None]);
+        // the only value of the cell is missing
+        assert_eq!(column(&df, "Art"), vec![None, None]);
+
+        let df = scores()
+            .pivot_table(["Name", "Term"], "Subject", "Score", Agg::Count)
+            .unwrap();
+        assert_eq!(df.shape(), (4, 5));
+        assert_eq!(column(&df, "Art")[3], Some(Cell::Int(0)));
+        assert_eq!(column(&df, "Art")[0], None);
+
+        assert!(matches!(
+            scores().pivot_table(["Name"], "Score", "Subject", Agg::Sum),
+            Err(PandasError::DTypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_melt() {
+        let wide = scores()
+            .pivot_table(["Name"], "Subject", "Score", Agg::Max)
+            .unwrap();
+        let long = wide.melt(["Name"], Vec::<&str>::new()).unwrap();
+        assert_eq!(long.shape(), (6, 3));
+        assert_eq!(
+            column(&long, "variable")[2],
+            Some(Cell::Str("Physics".into()))
+        );
+        assert_eq!(column(&long, "value")[1], Some(Cell::Int(90)));
+
+        // melting and pivoting again gives back the wide data frame
+        let back = long
+            .pivot_table(["Name"], "variable", "value", Agg::First)
+            .unwrap();
+        for name in ["Math", "Physics", "Art"] {
+            assert_eq!(column(&back, name), column(&wide, name));
+        }
+
+        let long = wide
+            .melt_with(["Name"], ["Art", "Math"], "Subject", "Score")
+            .unwrap();
+        assert_eq!(column(&long, "Subject")[0], Some(Cell::Str("Art".into())));
+        assert!(matches!(
+            wide.melt_with(["Name"], ["Math"], "Name", "value"),
+            Err(PandasError::DuplicateColumn(_))
+        ));
+    }
+
+    #[test]
+    fn test_stack_and_unstack() {
+        let wide = scores()
+            .pivot_table(["Name"], "Subject", "Score", Agg::Max)
+            .unwrap()
+            .set_index("Name")
+            .unwrap();
+        let long = wide.stack().unwrap();
+        // John has no Art score, Jane no Physics score and her Art score is
+        // missing
+        assert_eq!(long.shape(), (3, 2));
+        assert_eq!(
+            long.labels().iter().collect::<Vec<_>>(),
+            ["John", "John", "Jane"].map(|l| Some(Cell::from(l)))
+        );
+        assert_eq!(
+            column(&long, "variable"),
+            ["Math", "Physics", "Math"].map(|v| Some(Cell::from(v)))
+        );
+        assert_eq!(column(&long, "value")[1], Some(Cell::Int(70)));
+
+        let back = long.unstack().unwrap();
+        assert_eq!(back.shape(), (2, 2));
+        assert_eq!(back.labels.as_ref().unwrap().name, "Name");
+        assert_eq!(column(&back, "Math"), column(&wide, "Math"));
+        assert_eq!(column(&back, "Physics"), column(&wide, "Physics"));
+
+        // without labels the rows are stacked by index
+        let long = scores().select(["Term", "Score"]).unwrap().stack().unwrap();
+        assert_eq!(long.labels().get(1), Some(Cell::Int(0)));
+        assert_eq!(long.unstack().unwrap().shape(), (5, 2));
+
+        let repeated = scores().set_index("Name").unwrap();
+        assert!(matches!(
+            repeated.unstack_with("Subject", "Score"),
+            Err(PandasError::DuplicateLabel(label)) if label == "John"
+        ));
+    }
+}
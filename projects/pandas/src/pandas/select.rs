@@ -0,0 +1,215 @@
+use std::{
+    ops::{Bound, RangeBounds},
+    sync::{Arc, OnceLock},
+};
+
+use super::{
+    error::{PandasError, Result},
+    Cell, DType, DataFrame, Series,
+};
+
+impl Series {
+    /// returns a new series with the values at the given positions, without
+    /// labels
+    pub(crate) fn take(&self, indices: &[usize]) -> Series {
+        Series {
+            values: self.values.take(indices),
+            validity: indices.iter().map(|&idx| self.is_valid(idx)).collect(),
+            labels: None,
+            cells: OnceLock::new(),
+        }
+    }
+}
+
+impl DataFrame {
+    /// returns a new data frame with the rows at the given positions, rows
+    /// keep their index and labels
+    pub(crate) fn take(&self, indices: &[usize]) -> DataFrame {
+        let index = self.index();
+        let mut df = DataFrame {
+            headers: self.headers.clone(),
+            data: self
+                .data
+                .iter()
+                .map(|(name, series)| (name.clone(), series.take(indices)))
+                .collect(),
+            index: Some(indices.iter().map(|&idx| index[idx]).collect()),
+            labels: self
+                .labels
+                .as_ref()
+                .map(|labels| Arc::new(labels.take(indices))),
+        };
+        df.share_labels();
+        df
+    }
+
+    /// Returns a new data frame with only the given columns, in the order in
+    /// which they are listed. Rows keep their index and labels.
+    ///
+    /// ```ignore
+    /// let scores = df.select(["Age", "CGPA"])?;
+    /// ```
+    pub(crate) fn select<T, S>(&self, columns: T) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut df = DataFrame::new(Vec::<(String, Vec<String>)>::new());
+        for column in columns {
+            let name = column.as_ref();
+            if df.data.contains_key(name) {
+                continue;
+            }
+            let series = self.column(name)?;
+            df.headers.push(self.header(name)?.clone());
+            df.data.insert(name.to_string(), series.clone());
+        }
+        df.index = self.index.clone();
+        df.labels = self.labels.clone();
+        Ok(df)
+    }
+
+    /// Returns the rows within the given range of positions, ranges that go
+    /// past the last row are clamped to the size of the data frame.
+    ///
+    /// ```ignore
+    /// let first_two = df.iloc(..2);
+    /// let rest = df.iloc(2..);
+    /// ```
+    pub(crate) fn iloc<R: RangeBounds<usize>>(&self, rows: R) -> DataFrame {
+        let len = self.shape().0;
+        let start = match rows.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match rows.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        let end = end.min(len);
+        self.take(&(start.min(end)..end).collect::<Vec<usize>>())
+    }
+
+    /// Returns the rows for which `mask` is `true`, `mask` must be a boolean
+    /// series with one value per row. Missing values count as `false`.
+    pub(crate) fn filter(&self, mask: &Series) -> Result<DataFrame> {
+        let len = self.shape().0;
+        if mask.len() != len {
+            return Err(PandasError::LengthMismatch {
+                expected: len,
+                found: mask.len(),
+            });
+        }
+        // a mask without values has no type to check
+        if mask.d_type() != DType::Bool && mask.null_count() < len {
+            return Err(PandasError::DTypeMismatch {
+                expected: DType::Bool,
+                found: mask.d_type(),
+            });
+        }
+        let indices = (0..len)
+            .filter(|&idx| mask.get(idx) == Some(Cell::Bool(true)))
+            .collect::<Vec<usize>>();
+        Ok(self.take(&indices))
+    }
+
+    /// Returns the rows for which `predicate` returns `true`. The predicate
+    /// receives each row as returned by `get_item_at`, in `headers` order.
+    ///
+    /// ```ignore
+    /// let adults = df.filter_rows(|row| matches!(row[3], Some(Cell::Int(age)) if age >= 20));
+    /// ```
+    pub(crate) fn filter_rows<F>(&self, predicate: F) -> DataFrame
+    where
+        F: Fn(&[Option<Cell>]) -> bool,
+    {
+        let indices = (0..self.shape().0)
+            .filter(|&idx| predicate(&self.get_item_at(idx)))
+            .collect::<Vec<usize>>();
+        self.take(&indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::fixtures::students;
+
+    fn names(df: &DataFrame) -> Vec<String> {
+        df.headers.iter().map(|h| h.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_select() {
+        let df = students();
+        let selected = df.select(["CGPA", "Name"]).unwrap();
+        assert_eq!(names(&selected), vec!["CGPA", "Name"]);
+        assert_eq!(selected.shape(), (5, 2));
+        assert_eq!(selected.headers[0].d_type, DType::Float);
+        assert!(matches!(
+            df.select(["Name", "Grade"]),
+            Err(PandasError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_iloc() {
+        let df = students();
+        assert_eq!(df.iloc(1..3).shape(), (2, 6));
+        assert!(
+            matches!(&df.iloc(1..3).column("Name").unwrap()[0], Some(Cell::Str(v)) if v == "Jane")
+        );
+        assert_eq!(df.iloc(..=1).shape(), (2, 6));
+        assert_eq!(df.iloc(2..).shape(), (3, 6));
+        assert_eq!(df.iloc(4..10).shape(), (1, 6));
+        assert_eq!(df.iloc(10..).shape(), (0, 6));
+        assert_eq!(df.iloc(2..=usize::MAX).shape(), (3, 6));
+        let after_max = (Bound::Excluded(usize::MAX), Bound::Unbounded);
+        assert_eq!(df.iloc(after_max).shape(), (0, 6));
+        assert_eq!(names(&df.iloc(..)), names(&df));
+    }
+
+    #[test]
+    fn test_filter() {
+        let df = students();
+        let mask = [Some(true), None, Some(false), Some(true), Some(false)]
+            .into_iter()
+            .map(|v| v.map(Cell::Bool))
+            .collect::<Series>();
+        let filtered = df.filter(&mask).unwrap();
+        assert_eq!(filtered.shape(), (2, 6));
+        assert!(matches!(
+            filtered.column("Age").unwrap()[1],
+            Some(Cell::Int(22))
+        ));
+
+        let short = mask.take(&[0, 1]);
+        assert!(matches!(
+            df.filter(&short),
+            Err(PandasError::LengthMismatch {
+                expected: 5,
+                found: 2
+            })
+        ));
+        assert!(matches!(
+            df.filter(df.column("Age").unwrap()),
+            Err(PandasError::DTypeMismatch {
+                found: DType::Int,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_filter_rows() {
+        let df = students();
+        let filtered = df.filter_rows(|row| matches!(row[3], Some(Cell::Int(age)) if age > 20));
+        assert_eq!(filtered.shape(), (3, 6));
+        assert_eq!(names(&filtered), names(&df));
+        assert!(
+            matches!(&filtered.column("Name").unwrap()[0], Some(Cell::Str(v)) if v == "Satoshi")
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use std::cmp::Ordering;
+
+use super::{
+    error::{PandasError, Result},
+    storage::Column,
+    Cell, DataFrame, Header, Series,
+};
+
+/// Where `sort_values` places missing values, regardless of the direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum NaPosition {
+    First,
+    #[default]
+    Last,
+}
+
+/// Compares two cells of the same column: numbers numerically and strings
+/// lexicographically. `NaN` floats are greater than every other float.
+pub(crate) fn compare(a: &Cell, b: &Cell) -> Ordering {
+    match (a, b) {
+        (Cell::Float(a), Cell::Float(b)) => a.total_cmp(b),
+        (Cell::Str(a), Cell::Str(b)) | (Cell::Category(a), Cell::Category(b)) => a.cmp(b),
+        (Cell::Date(a), Cell::Date(b)) | (Cell::DateTime(a), Cell::DateTime(b)) => a.cmp(b),
+        (a, b) => match (a.as_f64(), b.as_f64()) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            _ => Ordering::Equal,
+        },
+    }
+}
+
+/// compares the values of two non-null rows of a series, see `compare`
+fn compare_rows(series: &Series, a: usize, b: usize) -> Ordering {
+    match &series.values {
+        Column::Bool(values) => values.get(a).cmp(&values.get(b)),
+        Column::Int(values) => values[a].cmp(&values[b]),
+        Column::Float(values) => values[a].total_cmp(&values[b]),
+        Column::Date(values) | Column::DateTime(values) => values[a].cmp(&values[b]),
+        Column::Str { .. } => series.values.str_at(a).cmp(series.values.str_at(b)),
+        // categories are ordered by their position, see `reorder_categories`
+        Column::Category { codes, .. } => codes[a].cmp(&codes[b]),
+    }
+}
+
+/// compares two rows of a series, placing missing values according to `nulls`
+fn compare_nullable(
+    series: &Series,
+    a: usize,
+    b: usize,
+    ascending: bool,
+    nulls: NaPosition,
+) -> Ordering {
+    match (series.is_valid(a), series.is_valid(b)) {
+        (true, true) if ascending => compare_rows(series, a, b),
+        (true, true) => compare_rows(series, b, a),
+        (false, false) => Ordering::Equal,
+        (false, true) if nulls == NaPosition::First => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (true, false) if nulls == NaPosition::First => Ordering::Greater,
+        (true, false) => Ordering::Less,
+    }
+}
+
+impl DataFrame {
+    /// original position of every row
+    pub(crate) fn index(&self) -> Vec<usize> {
+        match &self.index {
+            Some(index) => index.clone(),
+            None => (0..self.shape().0).collect(),
+        }
+    }
+
+    /// Sorts the rows by the values of the `by` columns, see `sort_values_with`.
+    /// Missing values are placed last.
+    ///
+    /// ```ignore
+    /// let ranked = df.sort_values(["CGPA", "Age"], &[false, true])?;
+    /// ```
+    pub(crate) fn sort_values<T, S>(&self, by: T, ascending: &[bool]) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.sort_values_with(by, ascending, NaPosition::Last)
+    }
+
+    /// Sorts the rows by the values of the `by` columns, later columns break
+    /// ties of earlier ones and rows that compare equal keep their order.
+    ///
+    /// `ascending` holds the direction of every column in `by`, or a single
+    /// direction used for all of them.
+    pub(crate) fn sort_values_with<T, S>(
+        &self,
+        by: T,
+        ascending: &[bool],
+        nulls: NaPosition,
+    ) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let columns = by
+            .into_iter()
+            .map(|name| self.column(name.as_ref()))
+            .collect::<Result<Vec<&Series>>>()?;
+        let ascending = match ascending.len() {
+            1 => vec![ascending[0]; columns.len()],
+            len if len == columns.len() => ascending.to_vec(),
+            found => {
+                return Err(PandasError::LengthMismatch {
+                    expected: columns.len(),
+                    found,
+                })
+            }
+        };
+        let mut indices = (0..self.shape().0).collect::<Vec<usize>>();
+        indices.sort_by(|&a, &b| {
+            columns
+                .iter()
+                .zip(&ascending)
+                .map(|(series, &asc)| compare_nullable(series, a, b, asc, nulls))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        Ok(self.take(&indices))
+    }
+
+    /// Sorts the rows by their labels, see `set_index`, or by their index,
+    /// restoring the original order of rows that have been reordered by
+    /// `sort_values`. Missing labels are placed last.
+    pub(crate) fn sort_index(&self, ascending: bool) -> DataFrame {
+        let labels = self.labels();
+        let mut indices = (0..labels.len()).collect::<Vec<usize>>();
+        indices.sort_by(|&a, &b| compare_nullable(&labels, a, b, ascending, NaPosition::Last));
+        self.take(&indices)
+    }
+
+    /// Resets the index to `0..n` and removes the labels. Unless `drop` is
+    /// set, the labels are kept as a column in front of the other columns,
+    /// under the name of the column passed to `set_index`, or the current
+    /// index as an `Int` column named `index`.
+    pub(crate) fn reset_index(&self, drop: bool) -> Result<DataFrame> {
+        let mut df = self.clone();
+        if !drop {
+            let name = self
+                .labels
+                .as_ref()
+                .map_or(String::from("index"), |labels| labels.name.clone());
+            if df.data.contains_key(&name) {
+                return Err(PandasError::DuplicateColumn(name));
+            }
+            let values = self.labels().into_owned();
+            df.headers.insert(
+                0,
+                Header {
+                    name: name.clone(),
+                    d_type: values.d_type(),
+                },
+            );
+            df.data.insert(name, values);
+        }
+        df.index = None;
+        df.labels = None;
+        df.share_labels();
+        Ok(df)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::fixtures::read;
+
+    fn ties() -> DataFrame {
+        let source = "Name,Age,CGPA\n\
+                      John,20,3.5\n\
+                      Jane,19,\n\
+                      Satoshi,21,3.9\n\
+                      Adam,20,2.5\n\
+                      Eve,19,3.9\n";
+        read(source)
+    }
+
+    fn names(df: &DataFrame) -> Vec<String> {
+        (0..df.shape().0)
+            .map(|idx| {
+                df.column("Name").unwrap()[idx]
+                    .as_ref()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_values_single_column() {
+        let df = ties();
+        let sorted = df.sort_values(["CGPA"], &[true]).unwrap();
+        assert_eq!(
+            names(&sorted),
+            vec!["Adam", "John", "Satoshi", "Eve", "Jane"]
+        );
+        assert_eq!(sorted.index(), vec![3, 0, 2, 4, 1]);
+
+        let sorted = df
+            .sort_values_with(["CGPA"], &[false], NaPosition::First)
+            .unwrap();
+        assert_eq!(
+            names(&sorted),
+            vec!["Jane", "Satoshi", "Eve", "John", "Adam"]
+        );
+
+        let sorted = df.sort_values(["Name"], &[true]).unwrap();
+        assert_eq!(
+            names(&sorted),
+            vec!["Adam", "Eve", "Jane", "John", "Satoshi"]
+        );
+    }
+
+    #[test]
+    fn test_sort_values_multiple_columns() {
+        let df = ties();
+        let sorted = df.sort_values(["Age", "CGPA"], &[true, false]).unwrap();
+        assert_eq!(
+            names(&sorted),
+            vec!["Eve", "Jane", "John", "Adam", "Satoshi"]
+        );
+
+        // the same direction for every column, ties keep their order
+        let sorted = df.sort_values(["Age"], &[false]).unwrap();
+        assert_eq!(
+            names(&sorted),
+            vec!["Satoshi", "John", "Adam", "Jane", "Eve"]
+        );
+
+        assert!(matches!(
+            df.sort_values(["Age", "CGPA"], &[true, false, true]),
+            Err(PandasError::LengthMismatch {
+                expected: 2,
+                found: 3
+            })
+        ));
+        assert!(matches!(
+            df.sort_values(["Grade"], &[true]),
+            Err(PandasError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_sort_and_reset_index() {
+        let df = ties();
+        let sorted = df.sort_values(["Name"], &[true]).unwrap();
+        assert_eq!(names(&sorted.sort_index(true)), names(&df));
+        assert_eq!(sorted.sort_index(false).index(), vec![4, 3, 2, 1, 0]);
+
+        let reset = sorted.reset_index(false).unwrap();
+        assert_eq!(reset.headers[0].name, "index");
+        assert_eq!(reset.index(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(reset.column("index").unwrap()[0], Some(Cell::Int(3)));
+        assert!(matches!(
+            reset.reset_index(false),
+            Err(PandasError::DuplicateColumn(_))
+        ));
+        assert_eq!(sorted.reset_index(true).unwrap().shape(), (5, 3));
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use super::{Agg, Cell, DType, DataFrame, HashKey, Header, Series};
+
+/// statistics computed for `Int` and `Float` columns
+const NUMERIC_STATS: [&str; 9] = [
+    "count", "null", "mean", "std", "min", "25%", "50%", "75%", "max",
+];
+/// statistics computed for `Str` and `Bool` columns
+const OBJECT_STATS: [&str; 5] = ["count", "null", "unique", "top", "freq"];
+
+/// Linear interpolation between the closest ranks of sorted `values`, the
+/// method used by pandas by default.
+fn quantile(sorted: &[f64], q: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64))
+}
+
+impl Series {
+    /// Value below which a fraction `q` of the non-null numeric values fall,
+    /// `None` if there are no numeric values.
+    pub(crate) fn quantile(&self, q: f64) -> Option<f64> {
+        let mut values = (0..self.len())
+            .filter_map(|row| self.f64_at(row))
+            .collect::<Vec<f64>>();
+        values.sort_by(|a, b| a.total_cmp(b));
+        quantile(&values, q)
+    }
+
+    /// statistics of a numeric series in the order of `NUMERIC_STATS`
+    fn numeric_stats(&self) -> Vec<Option<f64>> {
+        let cells = self.iter().flatten().collect::<Vec<Cell>>();
+        let values = cells.iter().collect::<Vec<&Cell>>();
+        let mut sorted = values
+            .iter()
+            .filter_map(|c| c.as_f64())
+            .collect::<Vec<f64>>();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        // aggregations of floats never fail
+        let agg = |agg: Agg| {
+            let cell = agg.apply(DType::Float, &values).ok().flatten();
+            cell.and_then(|c| c.as_f64())
+        };
+        vec![
+            Some(values.len() as f64),
+            Some(self.null_count() as f64),
+            agg(Agg::Mean),
+            agg(Agg::Std),
+            sorted.first().copied(),
+            quantile(&sorted, 0.25),
+            quantile(&sorted, 0.5),
+            quantile(&sorted, 0.75),
+            sorted.last().copied(),
+        ]
+    }
+
+    /// statistics of a string or boolean series in the order of `OBJECT_STATS`
+    fn object_stats(&self) -> Vec<Option<String>> {
+        let mut counts: HashMap<HashKey, usize> = HashMap::new();
+        let mut top: Option<(usize, usize)> = None;
+        for row in (0..self.len()).filter(|&row| self.is_valid(row)) {
+            let count = counts.entry(self.hash_key(row)).or_default();
+            *count += 1;
+            // ties are resolved in favour of the value seen first
+            if top.is_none_or(|(_, freq)| *count > freq) {
+                top = Some((row, *count));
+            }
+        }
+        vec![
+            Some((self.len() - self.null_count()).to_string()),
+            Some(self.null_count().to_string()),
+            Some(counts.len().to_string()),
+            top.and_then(|(row, _)| self.get(row))
+                .map(|cell| cell.to_string()),
+            top.map(|(_, freq)| freq.to_string()),
+        ]
+    }
+}
+
+impl DataFrame {
+    /// Computes summary statistics of every column and returns them as a new
+    /// data frame with one row per statistic, named in the `statistic` column,
+    /// or `statistic.1`, `statistic.2`, ... if the data frame already has a
+    /// column with that name.
+    ///
+    /// `Int` and `Float` columns get the number of non-null (`count`) and of
+    /// missing (`null`) values, `mean`, `std`, `min`, the 25%, 50% and 75%
+    /// percentiles and `max` as `Float` values. `Str` and `Bool` columns get
+    /// `count`, `null`, the number of `unique` values, the most frequent value
+    /// (`top`) and its frequency (`freq`) as `Str` values. Statistics that do
+    /// not apply to a column are `None`.
+    pub(crate) fn describe(&self) -> DataFrame {
+        let is_numeric = |h: &Header| matches!(h.d_type, DType::Int | DType::Float);
+        let mut stats: Vec<&str> = vec![];
+        if self.headers.iter().any(is_numeric) {
+            stats.extend(NUMERIC_STATS);
+        }
+        if !self.headers.iter().all(is_numeric) {
+            let extra = OBJECT_STATS
+                .into_iter()
+                .filter(|s| !stats.contains(s))
+                .collect::<Vec<&str>>();
+            stats.extend(extra);
+        }
+        let position = |stat: &str, list: &[&str]| list.iter().position(|s| *s == stat);
+
+        let name = (0..)
+            .map(|n| match n {
+                0 => String::from("statistic"),
+                n => format!("statistic.{n}"),
+            })
+            .find(|name| !self.data.contains_key(name))
+            .expect("a free name");
+        let mut df = DataFrame::new([(
+            name,
+            stats.iter().map(|s| s.to_string()).collect::<Vec<String>>(),
+        )]);
+        for header in &self.headers {
+            let series = &self.data[&header.name];
+            let (d_type, values): (DType, Series) = match is_numeric(header) {
+                true => {
+                    let values = series.numeric_stats();
+                    (
+                        DType::Float,
+                        Series::from_cells(
+                            DType::Float,
+                            stats
+                                .iter()
+                                .map(|stat| position(stat, &NUMERIC_STATS).and_then(|i| values[i]))
+                                .map(|v| v.map(Cell::Float)),
+                        ),
+                    )
+                }
+                false => {
+                    let values = series.object_stats();
+                    (
+                        DType::Str,
+                        Series::from_cells(
+                            DType::Str,
+                            stats
+                                .iter()
+                                .map(|stat| {
+                                    position(stat, &OBJECT_STATS).and_then(|i| values[i].clone())
+                                })
+                                .map(|v| v.map(Cell::Str)),
+                        ),
+                    )
+                }
+            };
+            df.headers.push(Header {
+                name: header.name.clone(),
+                d_type,
+            });
+            df.data.insert(header.name.clone(), values);
+        }
+        df
+    }
+
+    /// prints the name and `DType` of every column
+    pub(crate) fn info(&self) {
+        self.headers.iter().for_each(|item| {
+            println!("{:<30}: {:?}", item.name, item.d_type);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pandas::fixtures::read;
+
+    fn repeated() -> DataFrame {
+        let source = "Name,Age,CGPA,Graduated\n\
+                      John,20,3.5,true\n\
+                      Jane,19,,false\n\
+                      Satoshi,21,3.9,true\n\
+                      Adam,22,2.5,\n\
+                      John,18,3.9,true\n";
+        read(source)
+    }
+
+    fn stat(df: &DataFrame, column: &str, name: &str) -> Option<Cell> {
+        let row = (0..df.shape().0)
+            .find(|&idx| df.column("statistic").unwrap()[idx] == Some(Cell::Str(name.to_string())))
+            .unwrap();
+        df.column(column).unwrap()[row].clone()
+    }
+
+    #[test]
+    fn test_quantile() {
+        let series: Series = [1, 2, 3, 4]
+            .into_iter()
+            .map(|v| Some(Cell::Int(v)))
+            .chain([None])
+            .collect();
+        assert_eq!(series.quantile(0.0), Some(1.0));
+        assert_eq!(series.quantile(0.25), Some(1.75));
+        assert_eq!(series.quantile(0.5), Some(2.5));
+        assert_eq!(series.quantile(1.0), Some(4.0));
+        assert_eq!(Series::new(vec![]).quantile(0.5), None);
+    }
+
+    #[test]
+    fn test_describe_numeric() {
+        let df = repeated().select(["Age", "CGPA"]).unwrap().describe();
+        assert_eq!(df.shape(), (9, 3));
+        assert_eq!(df.header("Age").unwrap().d_type, DType::Float);
+        assert_eq!(stat(&df, "Age", "count"), Some(Cell::Float(5.0)));
+        assert_eq!(stat(&df, "CGPA", "count"), Some(Cell::Float(4.0)));
+        assert_eq!(stat(&df, "CGPA", "null"), Some(Cell::Float(1.0)));
+        assert_eq!(stat(&df, "Age", "mean"), Some(Cell::Float(20.0)));
+        assert_eq!(stat(&df, "Age", "std"), Some(Cell::Float(2.5f64.sqrt())));
+        assert_eq!(stat(&df, "Age", "min"), Some(Cell::Float(18.0)));
+        assert_eq!(stat(&df, "Age", "25%"), Some(Cell::Float(19.0)));
+        assert_eq!(stat(&df, "CGPA", "50%"), Some(Cell::Float(3.7)));
+        assert_eq!(stat(&df, "CGPA", "max"), Some(Cell::Float(3.9)));
+    }
+
+    #[test]
+    fn test_describe_mixed() {
+        let df = repeated().describe();
+        assert_eq!(df.shape(), (12, 5));
+        assert_eq!(stat(&df, "Name", "unique"), Some(Cell::Str("4".into())));
+        assert_eq!(stat(&df, "Name", "top"), Some(Cell::Str("John".into())));
+        assert_eq!(stat(&df, "Name", "freq"), Some(Cell::Str("2".into())));
+        assert_eq!(stat(&df, "Graduated", "count"), Some(Cell::Str("4".into())));
+        assert_eq!(
+            stat(&df, "Graduated", "top"),
+            Some(Cell::Str("true".into()))
+        );
+        assert_eq!(stat(&df, "Graduated", "null"), Some(Cell::Str("1".into())));
+        assert_eq!(stat(&df, "Name", "mean"), None);
+        assert_eq!(stat(&df, "Age", "top"), None);
+    }
+
+    #[test]
+    fn test_describe_statistic_column() {
+        let source = "statistic,Age\n\
+                      low,20\n\
+                      high,\n";
+        let df = read(source);
+        let df = df.describe();
+        let names = df
+            .headers
+            .iter()
+            .map(|h| h.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["statistic.1", "statistic", "Age"]);
+        assert_eq!(
+            df.column("statistic.1").unwrap().get(0),
+            Some(Cell::Str("count".into()))
+        );
+        assert_eq!(
+            df.column("statistic").unwrap().get(0),
+            Some(Cell::Str("2".into()))
+        );
+        assert_eq!(df.column("Age").unwrap().get(0), Some(Cell::Float(1.0)));
+    }
+}
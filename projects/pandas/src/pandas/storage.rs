@@ -0,0 +1,433 @@
+use std::{collections::HashMap, mem::size_of};
+
+use super::{datetime::parse_iso, Cell, DType};
+
+/// A growable sequence of bits packed into 64 bit words
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Bitmap {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bitmap {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            words: Vec::with_capacity(capacity.div_ceil(64)),
+            len: 0,
+        }
+    }
+
+    /// a bitmap of `len` bits that are all set to `value`
+    pub(crate) fn filled(len: usize, value: bool) -> Self {
+        let mut bitmap = Self {
+            words: vec![if value { u64::MAX } else { 0 }; len.div_ceil(64)],
+            len,
+        };
+        bitmap.clear_unused();
+        bitmap
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub(crate) fn get(&self, idx: usize) -> bool {
+        assert!(idx < self.len, "bit {idx} out of range for {}", self.len);
+        self.words[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    pub(crate) fn set(&mut self, idx: usize, value: bool) {
+        assert!(idx < self.len, "bit {idx} out of range for {}", self.len);
+        match value {
+            true => self.words[idx / 64] |= 1 << (idx % 64),
+            false => self.words[idx / 64] &= !(1 << (idx % 64)),
+        }
+    }
+
+    pub(crate) fn push(&mut self, value: bool) {
+        if self.len.is_multiple_of(64) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, value);
+    }
+
+    /// number of set bits
+    pub(crate) fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|idx| self.get(idx))
+    }
+
+    /// bytes used by the packed words
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.words.len() * size_of::<u64>()
+    }
+
+    /// unsets the bits of the last word past `len` so `count_ones` stays exact
+    fn clear_unused(&mut self) {
+        if let (Some(last), rest) = (self.words.last_mut(), self.len % 64) {
+            if rest != 0 {
+                *last &= (1 << rest) - 1;
+            }
+        }
+    }
+}
+
+impl FromIterator<bool> for Bitmap {
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        let iter = iter.into_iter();
+        let mut bitmap = Bitmap::with_capacity(iter.size_hint().0);
+        iter.for_each(|v| bitmap.push(v));
+        bitmap
+    }
+}
+
+/// The distinct values of a `Category` column in category order, the code of
+/// a value is its position.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Dictionary {
+    values: Vec<String>,
+    codes: HashMap<String, u32>,
+}
+
+impl Dictionary {
+    pub(crate) fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// the value with code `code`
+    pub(crate) fn get(&self, code: u32) -> &str {
+        &self.values[code as usize]
+    }
+
+    /// the code of `value`, `None` if it is not a category
+    pub(crate) fn code(&self, value: &str) -> Option<u32> {
+        self.codes.get(value).copied()
+    }
+
+    /// the code of `value`, which is added as the last category if it is new
+    pub(crate) fn insert(&mut self, value: &str) -> u32 {
+        if let Some(code) = self.code(value) {
+            return code;
+        }
+        let code = self.values.len() as u32;
+        self.values.push(value.to_string());
+        self.codes.insert(value.to_string(), code);
+        code
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.values.iter().map(String::as_str)
+    }
+
+    /// bytes used by the values and the lookup table, which keeps a copy of
+    /// every value
+    pub(crate) fn memory_usage(&self) -> usize {
+        let text = self.values.iter().map(String::len).sum::<usize>();
+        2 * text + self.len() * (2 * size_of::<String>() + size_of::<u32>())
+    }
+}
+
+/// Repeated values keep the position of their first occurrence.
+impl<S: AsRef<str>> FromIterator<S> for Dictionary {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut dictionary = Dictionary::default();
+        iter.into_iter().for_each(|value| {
+            dictionary.insert(value.as_ref());
+        });
+        dictionary
+    }
+}
+
+/// The values of a series in one contiguous buffer of their type. Rows with
+/// missing values hold a placeholder, validity is tracked by the series.
+#[derive(Debug, Clone)]
+pub(crate) enum Column {
+    Bool(Bitmap),
+    Int(Vec<i64>),
+    Float(Vec<f64>),
+    /// days since 1970-01-01
+    Date(Vec<i64>),
+    /// seconds since 1970-01-01 00:00:00
+    DateTime(Vec<i64>),
+    /// the text of all values back to back, value `i` spans
+    /// `offsets[i]..offsets[i + 1]`
+    Str {
+        offsets: Vec<usize>,
+        data: String,
+    },
+    /// the code of every value in `dictionary`
+    Category {
+        codes: Vec<u32>,
+        dictionary: Dictionary,
+    },
+}
+
+impl Column {
+    pub(crate) fn with_capacity(d_type: DType, capacity: usize) -> Self {
+        match d_type {
+            DType::Bool => Column::Bool(Bitmap::with_capacity(capacity)),
+            DType::Int => Column::Int(Vec::with_capacity(capacity)),
+            DType::Float => Column::Float(Vec::with_capacity(capacity)),
+            DType::Date => Column::Date(Vec::with_capacity(capacity)),
+            DType::DateTime => Column::DateTime(Vec::with_capacity(capacity)),
+            DType::Str => {
+                let mut offsets = Vec::with_capacity(capacity + 1);
+                offsets.push(0);
+                Column::Str {
+                    offsets,
+                    data: String::new(),
+                }
+            }
+            DType::Category => Column::Category {
+                codes: Vec::with_capacity(capacity),
+                dictionary: Dictionary::default(),
+            },
+        }
+    }
+
+    pub(crate) fn d_type(&self) -> DType {
+        match self {
+            Column::Bool(_) => DType::Bool,
+            Column::Int(_) => DType::Int,
+            Column::Float(_) => DType::Float,
+            Column::Date(_) => DType::Date,
+            Column::DateTime(_) => DType::DateTime,
+            Column::Str { .. } => DType::Str,
+            Column::Category { .. } => DType::Category,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Column::Bool(values) => values.len(),
+            Column::Int(values) => values.len(),
+            Column::Float(values) => values.len(),
+            Column::Date(values) | Column::DateTime(values) => values.len(),
+            Column::Str { offsets, .. } => offsets.len() - 1,
+            Column::Category { codes, .. } => codes.len(),
+        }
+    }
+
+    /// the value at `idx`, a placeholder if the row is missing
+    pub(crate) fn get(&self, idx: usize) -> Cell {
+        match self {
+            Column::Bool(values) => Cell::Bool(values.get(idx)),
+            Column::Int(values) => Cell::Int(values[idx]),
+            Column::Float(values) => Cell::Float(values[idx]),
+            Column::Date(values) => Cell::Date(values[idx]),
+            Column::DateTime(values) => Cell::DateTime(values[idx]),
+            Column::Str { .. } => Cell::Str(self.str_at(idx).to_string()),
+            Column::Category { .. } => Cell::Category(self.str_at(idx).to_string()),
+        }
+    }
+
+    /// text of the value at `idx` of a `Str` or `Category` column
+    pub(crate) fn str_at(&self, idx: usize) -> &str {
+        match self {
+            Column::Str { offsets, data } => &data[offsets[idx]..offsets[idx + 1]],
+            Column::Category { codes, dictionary } => dictionary.get(codes[idx]),
+            _ => panic!("{:?} column has no text values", self.d_type()),
+        }
+    }
+
+    /// numeric value at `idx` of a `Bool`, `Int` or `Float` column
+    pub(crate) fn f64_at(&self, idx: usize) -> Option<f64> {
+        match self {
+            Column::Bool(values) => Some(values.get(idx) as i64 as f64),
+            Column::Int(values) => Some(values[idx] as f64),
+            Column::Float(values) => Some(values[idx]),
+            Column::Date(_) | Column::DateTime(_) => None,
+            Column::Str { .. } | Column::Category { .. } => None,
+        }
+    }
+
+    /// appends the placeholder of a missing value
+    pub(crate) fn push_default(&mut self) {
+        match self {
+            Column::Bool(values) => values.push(false),
+            Column::Int(values) => values.push(0),
+            Column::Float(values) => values.push(0.0),
+            Column::Date(values) | Column::DateTime(values) => values.push(0),
+            Column::Str { offsets, data } => offsets.push(data.len()),
+            // missing values are never looked up in the dictionary
+            Column::Category { codes, .. } => codes.push(0),
+        }
+    }
+
+    /// Appends `cell`, which must have the type of the column.
+    pub(crate) fn push(&mut self, cell: &Cell) {
+        match (self, cell) {
+            (Column::Bool(values), Cell::Bool(v)) => values.push(*v),
+            (Column::Int(values), Cell::Int(v)) => values.push(*v),
+            (Column::Float(values), Cell::Float(v)) => values.push(*v),
+            (Column::Date(values), Cell::Date(v)) => values.push(*v),
+            (Column::DateTime(values), Cell::DateTime(v)) => values.push(*v),
+            (column @ Column::Str { .. }, Cell::Str(v)) => column.push_str(v),
+            (Column::Category { codes, dictionary }, Cell::Category(v)) => {
+                codes.push(dictionary.insert(v))
+            }
+            (column, cell) => panic!(
+                "can not push a {:?} value into a {:?} column",
+                cell.d_type(),
+                column.d_type()
+            ),
+        }
+    }
+
+    /// appends a value to a `Str` column without allocating a `Cell`
+    pub(crate) fn push_str(&mut self, value: &str) {
+        match self {
+            Column::Str { offsets, data } => {
+                data.push_str(value);
+                offsets.push(data.len());
+            }
+            _ => panic!("can not push text into a {:?} column", self.d_type()),
+        }
+    }
+
+    /// Parses `value` into the type of the column and appends it, returns
+    /// `false` and appends nothing if it can not be parsed.
+    pub(crate) fn push_parsed(&mut self, value: &str) -> bool {
+        match self {
+            Column::Bool(values) => match value {
+                "0" => values.push(false), // parse 0 as false
+                "1" => values.push(true),  // parse 1 as true
+                v => match v.parse::<bool>() {
+                    Ok(v) => values.push(v),
+                    Err(_) => return false,
+                },
+            },
+            Column::Int(values) => match value.parse::<i64>() {
+                Ok(v) => values.push(v),
+                Err(_) => return false,
+            },
+            Column::Float(values) => match value.parse::<f64>() {
+                Ok(v) => values.push(v),
+                Err(_) => return false,
+            },
+            Column::Date(values) => match parse_iso(value, DType::Date) {
+                Some(v) => values.push(v),
+                None => return false,
+            },
+            Column::DateTime(values) => match parse_iso(value, DType::DateTime) {
+                Some(v) => values.push(v),
+                None => return false,
+            },
+            Column::Str { .. } => self.push_str(value),
+            Column::Category { codes, dictionary } => codes.push(dictionary.insert(value)),
+        }
+        true
+    }
+
+    /// the values at `indices`, in that order
+    pub(crate) fn take(&self, indices: &[usize]) -> Column {
+        match self {
+            Column::Bool(values) => Column::Bool(indices.iter().map(|&i| values.get(i)).collect()),
+            Column::Int(values) => Column::Int(indices.iter().map(|&i| values[i]).collect()),
+            Column::Float(values) => Column::Float(indices.iter().map(|&i| values[i]).collect()),
+            Column::Date(values) => Column::Date(indices.iter().map(|&i| values[i]).collect()),
+            Column::DateTime(values) => {
+                Column::DateTime(indices.iter().map(|&i| values[i]).collect())
+            }
+            Column::Str { .. } => {
+                let mut column = Column::with_capacity(DType::Str, indices.len());
+                indices
+                    .iter()
+                    .for_each(|&i| column.push_str(self.str_at(i)));
+                column
+            }
+            Column::Category { codes, dictionary } => Column::Category {
+                codes: indices.iter().map(|&i| codes[i]).collect(),
+                dictionary: dictionary.clone(),
+            },
+        }
+    }
+
+    /// bytes used by the value buffers
+    pub(crate) fn memory_usage(&self) -> usize {
+        match self {
+            Column::Bool(values) => values.memory_usage(),
+            Column::Int(values) => values.len() * size_of::<i64>(),
+            Column::Float(values) => values.len() * size_of::<f64>(),
+            Column::Date(values) | Column::DateTime(values) => values.len() * size_of::<i64>(),
+            Column::Str { offsets, data } => offsets.len() * size_of::<usize>() + data.len(),
+            Column::Category { codes, dictionary } => {
+                codes.len() * size_of::<u32>() + dictionary.memory_usage()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitmap() {
+        let mut bitmap = Bitmap::filled(70, true);
+        assert_eq!(bitmap.count_ones(), 70);
+        bitmap.set(65, false);
+        bitmap.push(true);
+        assert_eq!(bitmap.len(), 71);
+        assert!(!bitmap.get(65));
+        assert!(bitmap.get(70));
+        assert_eq!(bitmap.count_ones(), 70);
+
+        let bitmap: Bitmap = [true, false, true].into_iter().collect();
+        assert_eq!(
+            bitmap.iter().collect::<Vec<bool>>(),
+            vec![true, false, true]
+        );
+    }
+
+    #[test]
+    fn test_str_column() {
+        let mut column = Column::with_capacity(DType::Str, 3);
+        column.push_str("John");
+        column.push_default();
+        column.push(&Cell::Str("Jane".into()));
+        assert_eq!(column.len(), 3);
+        assert_eq!(column.str_at(1), "");
+        assert_eq!(column.get(2), Cell::Str("Jane".into()));
+        let taken = column.take(&[2, 0]);
+        assert_eq!(taken.str_at(0), "Jane");
+        assert_eq!(taken.str_at(1), "John");
+
+        let mut column = Column::with_capacity(DType::Int, 2);
+        assert!(column.push_parsed("42"));
+        assert!(!column.push_parsed("4.2"));
+        assert_eq!(column.len(), 1);
+    }
+
+    #[test]
+    fn test_category_column() {
+        let mut column = Column::with_capacity(DType::Category, 4);
+        ["Doe", "Nakamoto", "Doe"].iter().for_each(|v| {
+            column.push_parsed(v);
+        });
+        column.push(&Cell::Category("Av".into()));
+        assert_eq!(column.len(), 4);
+        assert_eq!(column.str_at(2), "Doe");
+        assert_eq!(column.get(3), Cell::Category("Av".into()));
+        let Column::Category { codes, dictionary } = column.take(&[3, 2, 0]) else {
+            panic!("taking values keeps the type");
+        };
+        assert_eq!(codes, vec![2, 0, 0]);
+        assert_eq!(
+            dictionary.iter().collect::<Vec<&str>>(),
+            ["Doe", "Nakamoto", "Av"]
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use std::{cmp::Reverse, sync::OnceLock};
+
+use super::{
+    error::Result,
+    storage::{Bitmap, Column},
+    Cell, DataFrame, Series,
+};
+
+/// Which row of every set of repeated rows `duplicated` leaves unmarked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Keep {
+    /// the first occurrence
+    #[default]
+    First,
+    /// the last occurrence
+    Last,
+    /// none of them, every repeated row is marked
+    None,
+}
+
+/// `Bool` mask of `len` rows that is `true` for the repeated rows of every
+/// group except the one that `keep` selects
+fn mark_duplicates(groups: &[Vec<usize>], len: usize, keep: Keep) -> Series {
+    let mut marked = vec![false; len];
+    for rows in groups {
+        let kept = match keep {
+            Keep::First => rows.first(),
+            Keep::Last => rows.last(),
+            Keep::None if rows.len() > 1 => None,
+            Keep::None => rows.first(),
+        };
+        rows.iter().for_each(|row| marked[*row] = Some(row) != kept);
+    }
+    marked.into_iter().map(|m| Some(Cell::Bool(m))).collect()
+}
+
+impl Series {
+    /// `Bool` mask that is `true` for values that also appear in another row,
+    /// except for the row that `keep` selects. Missing values are equal to
+    /// each other, and so are `NaN` floats.
+    pub(crate) fn duplicated(&self, keep: Keep) -> Series {
+        let (codes, distinct) = self.encode();
+        let mut groups = vec![vec![]; distinct];
+        codes
+            .iter()
+            .enumerate()
+            .for_each(|(row, &code)| groups[code].push(row));
+        mark_duplicates(&groups, self.len(), keep)
+    }
+
+    /// The distinct values in order of first appearance, including a single
+    /// `None` if values are missing.
+    pub(crate) fn unique(&self) -> Series {
+        let (codes, distinct) = self.encode();
+        let mut seen = vec![false; distinct];
+        let rows = (0..self.len())
+            .filter(|&row| !std::mem::replace(&mut seen[codes[row]], true))
+            .collect::<Vec<usize>>();
+        self.take(&rows)
+    }
+
+    /// number of distinct values, missing values are not counted
+    pub(crate) fn n_unique(&self) -> usize {
+        let (codes, distinct) = self.encode();
+        let mut seen = vec![false; distinct];
+        codes.iter().for_each(|&code| seen[code] = true);
+        // code 0 holds the missing values
+        seen.iter().skip(1).filter(|&&seen| seen).count()
+    }
+
+    /// Counts the occurrences of every distinct value, see `value_counts_with`.
+    ///
+    /// ```ignore
+    /// let counts = df["Last Name".to_string()].value_counts();
+    /// ```
+    pub(crate) fn value_counts(&self) -> DataFrame {
+        self.value_counts_with(false)
+    }
+
+    /// Counts the occurrences of every distinct value. The result has a
+    /// `value` column with the type of the series and a `count` column, or a
+    /// `proportion` column with the share of the non-null values if
+    /// `normalize` is set. Most frequent values come first and equal counts in
+    /// order of first appearance, or in category order for a `Category`
+    /// series. Every category is listed, even without values, and missing
+    /// values are not counted.
+    pub(crate) fn value_counts_with(&self, normalize: bool) -> DataFrame {
+        let (codes, distinct) = self.encode();
+        let mut counts = vec![0; distinct];
+        codes.iter().for_each(|&code| counts[code] += 1);
+        // code 0 holds the missing values
+        let mut order = (1..distinct).collect::<Vec<usize>>();
+        order.sort_by_key(|&code| Reverse(counts[code]));
+
+        let values = match &self.values {
+            Column::Category { dictionary, .. } => Series {
+                values: Column::Category {
+                    codes: order.iter().map(|&code| code as u32 - 1).collect(),
+                    dictionary: dictionary.clone(),
+                },
+                validity: Bitmap::filled(order.len(), true),
+                labels: None,
+                cells: OnceLock::new(),
+            },
+            _ => {
+                let mut first = vec![0; distinct];
+                codes
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .for_each(|(row, &code)| first[code] = row);
+                self.take(
+                    &order
+                        .iter()
+                        .map(|&code| first[code])
+                        .collect::<Vec<usize>>(),
+                )
+            }
+        };
+        let total = (self.len() - counts[0]) as f64;
+        let (name, counts) = match normalize {
+            false => (
+                "count",
+                order
+                    .iter()
+                    .map(|&code| Some(Cell::Int(counts[code] as i64)))
+                    .collect::<Series>(),
+            ),
+            true => (
+                "proportion",
+                Series::from_cells(
+                    super::DType::Float,
+                    order
+                        .iter()
+                        .map(|&code| Some(Cell::Float(counts[code] as f64 / total))),
+                ),
+            ),
+        };
+        DataFrame::new(Vec::<(String, Vec<String>)>::new())
+            .with_column("value", values)
+            .and_then(|df| df.with_column(name, counts))
+            .expect("both columns have one row per distinct value")
+    }
+}
+
+impl DataFrame {
+    /// `Bool` mask of the rows that repeat another row in every column, see
+    /// `duplicated_with`.
+    pub(crate) fn duplicated(&self, keep: Keep) -> Series {
+        self.duplicated_with(keep, None::<[&str; 0]>)
+            .expect("every column exists")
+    }
+
+    /// `Bool` mask that is `true` for rows that have the same values as
+    /// another row in the `subset` columns, or in every column if `None`,
+    /// except for the row that `keep` selects.
+    ///
+    /// ```ignore
+    /// let repeated = df.duplicated_with(Keep::Last, Some(["First Name", "Last Name"]))?;
+    /// ```
+    pub(crate) fn duplicated_with<T, S>(&self, keep: Keep, subset: Option<T>) -> Result<Series>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let columns = self.column_names(subset)?;
+        let groups = self.group_by(&columns).groups()?;
+        Ok(mark_duplicates(&groups, self.shape().0, keep))
+    }
+
+    /// Removes the rows that repeat another row in every column, see
+    /// `drop_duplicates_with`.
+    pub(crate) fn drop_duplicates(&self, keep: Keep) -> DataFrame {
+        self.drop_duplicates_with(keep, None::<[&str; 0]>)
+            .expect("every column exists")
+    }
+
+    /// Removes the rows that `duplicated_with` marks, rows keep their index.
+    pub(crate) fn drop_duplicates_with<T, S>(
+        &self,
+        keep: Keep,
+        subset: Option<T>,
+    ) -> Result<DataFrame>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let marked = self.duplicated_with(keep, subset)?;
+        let rows = (0..self.shape().0)
+            .filter(|&row| marked.get(row) == Some(Cell::Bool(false)))
+            .collect::<Vec<usize>>();
+        Ok(self.take(&rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    use super::*;
+    use crate::pandas::{error::PandasError, fixtures::read, DType};
+
+    fn duplicates() -> DataFrame {
+        let source = "Name,Dept,CGPA\n\
+                      John,CS,3.5\n\
+                      Jane,EE,\n\
+                      John,CS,3.5\n\
+                      Jane,EE,NaN\n\
+                      Jane,EE,\n";
+        read(source)
+    }
+
+    fn flags(series: &Series) -> Vec<bool> {
+        series
+            .iter()
+            .map(|cell| cell == Some(Cell::Bool(true)))
+            .collect()
+    }
+
+    fn hash(cell: &Cell) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        cell.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_duplicated() {
+        let df = duplicates();
+        assert_eq!(
+            flags(&df.duplicated(Keep::First)),
+            [false, false, true, false, true]
+        );
+        assert_eq!(
+            flags(&df.duplicated(Keep::Last)),
+            [true, true, false, false, false]
+        );
+        assert_eq!(
+            flags(&df.duplicated(Keep::None)),
+            [true, true, true, false, true]
+        );
+        let marked = df.duplicated_with(Keep::First, Some(["Dept"])).unwrap();
+        assert_eq!(flags(&marked), [false, false, true, true, true]);
+        assert_eq!(df.drop_duplicates(Keep::First).index(), vec![0, 1, 3]);
+        let dropped = df.drop_duplicates_with(Keep::Last, Some(["Name"])).unwrap();
+        assert_eq!(dropped.index(), vec![2, 4]);
+        assert!(matches!(
+            df.duplicated_with(Keep::First, Some(["Grade"])),
+            Err(PandasError::ColumnNotFound(_))
+        ));
+
+        // NaN floats are equal to each other, and so are 0.0 and -0.0
+        let values: Series = [f64::NAN, 0.0, -f64::NAN, -0.0, 1.0]
+            .map(|v| Some(Cell::Float(v)))
+            .into_iter()
+            .collect();
+        assert_eq!(
+            flags(&values.duplicated(Keep::First)),
+            [false, false, true, true, false]
+        );
+    }
+
+    #[test]
+    fn test_unique() {
+        let df = duplicates();
+        let names = df.column("Name").unwrap();
+        assert_eq!(
+            names.unique().iter().collect::<Vec<_>>(),
+            [
+                Some(Cell::Str("John".into())),
+                Some(Cell::Str("Jane".into()))
+            ]
+        );
+        let cgpa = df.column("CGPA").unwrap();
+        assert_eq!(cgpa.unique().len(), 3);
+        assert_eq!(cgpa.unique().get(1), None);
+        assert_eq!(cgpa.n_unique(), 2);
+        assert_eq!(names.n_unique(), 2);
+    }
+
+    #[test]
+    fn test_value_counts() {
+        let df = duplicates();
+        let counts = df.column("Name").unwrap().value_counts();
+        assert_eq!(counts.header("count").unwrap().d_type, DType::Int);
+        assert_eq!(
+            counts.column("value").unwrap().get(0),
+            Some(Cell::Str("Jane".into()))
+        );
+        assert_eq!(counts.column("count").unwrap().get(0), Some(Cell::Int(3)));
+
+        let shares = df.column("CGPA").unwrap().value_counts_with(true);
+        assert_eq!(shares.shape(), (2, 2));
+        assert_eq!(
+            shares
+                .column("proportion")
+                .unwrap()
+                .iter()
+                .collect::<Vec<_>>(),
+            [Some(Cell::Float(2.0 / 3.0)), Some(Cell::Float(1.0 / 3.0))]
+        );
+    }
+
+    #[test]
+    fn test_cell_hash() {
+        assert_eq!(hash(&Cell::Float(0.0)), hash(&Cell::Float(-0.0)));
+        assert_eq!(hash(&Cell::Float(f64::NAN)), hash(&Cell::Float(-f64::NAN)));
+        assert_eq!(hash(&Cell::Int(1)), hash(&Cell::Int(1)));
+        assert_ne!(hash(&Cell::Int(1)), hash(&Cell::Float(1.0)));
+    }
+}
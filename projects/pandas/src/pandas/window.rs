@@ -0,0 +1,333 @@
+use std::collections::VecDeque;
+
+use super::{
+    error::{PandasError, Result},
+    Cell, DType, Series,
+};
+
+/// A moving window over the values of a numeric series, created with
+/// `Series::rolling` or `Series::expanding`. Every computation returns a
+/// `Float` series with one value per row of the input, computed over the
+/// non-null values of the window that ends at that row. `NaN` values are
+/// treated as missing.
+///
+/// ```ignore
+/// let average = df["Sales".to_string()].rolling(7).min_periods(3).mean()?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Window<'a> {
+    series: &'a Series,
+    /// number of rows in the window, `None` for a window that starts at the
+    /// first row
+    size: Option<usize>,
+    min_periods: usize,
+}
+
+/// Statistics of the values that are currently in a window. Values are added
+/// and removed in order, so the window never has to be scanned again.
+/// Infinite values are counted apart from the running sum and mean, which
+/// would stay `NaN` once an infinity is subtracted from them.
+#[derive(Default)]
+struct State {
+    count: usize,
+    /// number of positive and negative infinite values
+    infinite: (usize, usize),
+    /// sum and mean of the finite values
+    sum: f64,
+    mean: f64,
+    /// sum of squared differences from the mean, see Welford's algorithm
+    m2: f64,
+    /// rows that may still become the minimum and the maximum, their values
+    /// increase and decrease from front to back
+    min: VecDeque<(usize, f64)>,
+    max: VecDeque<(usize, f64)>,
+}
+
+impl State {
+    /// number of finite values
+    fn finite(&self) -> usize {
+        self.count - self.infinite.0 - self.infinite.1
+    }
+
+    /// the sum of the values if it is not finite
+    fn infinity(&self) -> Option<f64> {
+        match self.infinite {
+            (0, 0) => None,
+            (_, 0) => Some(f64::INFINITY),
+            (0, _) => Some(f64::NEG_INFINITY),
+            _ => Some(f64::NAN),
+        }
+    }
+
+    fn add(&mut self, row: usize, value: f64) {
+        self.count += 1;
+        match value {
+            f64::INFINITY => self.infinite.0 += 1,
+            f64::NEG_INFINITY => self.infinite.1 += 1,
+            value => {
+                self.sum += value;
+                let delta = value - self.mean;
+                self.mean += delta / self.finite() as f64;
+                self.m2 += delta * (value - self.mean);
+            }
+        }
+        while self.min.back().is_some_and(|&(_, v)| v >= value) {
+            self.min.pop_back();
+        }
+        self.min.push_back((row, value));
+        while self.max.back().is_some_and(|&(_, v)| v <= value) {
+            self.max.pop_back();
+        }
+        self.max.push_back((row, value));
+    }
+
+    fn remove(&mut self, row: usize, value: f64) {
+        self.count -= 1;
+        match value {
+            f64::INFINITY => self.infinite.0 -= 1,
+            f64::NEG_INFINITY => self.infinite.1 -= 1,
+            value => {
+                self.sum -= value;
+                match self.finite() {
+                    0 => (self.sum, self.mean, self.m2) = (0.0, 0.0, 0.0),
+                    count => {
+                        let delta = value - self.mean;
+                        self.mean -= delta / count as f64;
+                        // rounding errors must not make the variance negative
+                        self.m2 = (self.m2 - delta * (value - self.mean)).max(0.0);
+                    }
+                }
+            }
+        }
+        if self.min.front().is_some_and(|&(r, _)| r == row) {
+            self.min.pop_front();
+        }
+        if self.max.front().is_some_and(|&(r, _)| r == row) {
+            self.max.pop_front();
+        }
+    }
+}
+
+impl Series {
+    /// A window over the current row and the `window - 1` rows before it.
+    /// Rows with fewer than `window` non-null values in their window are
+    /// `None` unless `min_periods` is lowered. The computations fail with
+    /// `PandasError::EmptyWindow` if `window` is `0`.
+    pub(crate) fn rolling(&self, window: usize) -> Window<'_> {
+        Window {
+            series: self,
+            size: Some(window),
+            min_periods: window,
+        }
+    }
+
+    /// A window over the current row and every row before it. Rows are
+    /// computed once the window has a non-null value unless `min_periods` is
+    /// raised.
+    pub(crate) fn expanding(&self) -> Window<'_> {
+        Window {
+            series: self,
+            size: None,
+            min_periods: 1,
+        }
+    }
+}
+
+impl Window<'_> {
+    /// minimum number of non-null values a window needs to give a value,
+    /// rows with fewer values are `None`
+    pub(crate) fn min_periods(mut self, min_periods: usize) -> Self {
+        self.min_periods = min_periods;
+        self
+    }
+
+    /// computes `f` over the window of every row
+    fn compute<F>(&self, f: F) -> Result<Series>
+    where
+        F: Fn(&State) -> Option<f64>,
+    {
+        let series = self.series;
+        if self.size == Some(0) {
+            return Err(PandasError::EmptyWindow);
+        }
+        if !matches!(series.d_type(), DType::Bool | DType::Int | DType::Float) {
+            return Err(PandasError::DTypeMismatch {
+                expected: DType::Float,
+                found: series.d_type(),
+            });
+        }
+        // `NaN` is treated as a missing value
+        let value_at = |row: usize| series.f64_at(row).filter(|v| !v.is_nan());
+        let mut state = State::default();
+        let mut out = Series::with_capacity(DType::Float, series.len());
+        for row in 0..series.len() {
+            if let Some(value) = value_at(row) {
+                state.add(row, value);
+            }
+            // the row that just left the window
+            if let Some(first) = self.size.and_then(|size| row.checked_sub(size)) {
+                if let Some(value) = value_at(first) {
+                    state.remove(first, value);
+                }
+            }
+            let value = match state.count {
+                0 => None,
+                count if count < self.min_periods => None,
+                _ => f(&state),
+            };
+            out.push_cell(value.map(Cell::Float));
+        }
+        Ok(out)
+    }
+
+    pub(crate) fn sum(&self) -> Result<Series> {
+        self.compute(|state| Some(state.infinity().unwrap_or(state.sum)))
+    }
+
+    pub(crate) fn mean(&self) -> Result<Series> {
+        self.compute(|state| Some(state.infinity().unwrap_or(state.mean)))
+    }
+
+    pub(crate) fn min(&self) -> Result<Series> {
+        self.compute(|state| state.min.front().map(|&(_, v)| v))
+    }
+
+    pub(crate) fn max(&self) -> Result<Series> {
+        self.compute(|state| state.max.front().map(|&(_, v)| v))
+    }
+
+    /// sample standard deviation, `None` for windows with a single value and
+    /// `NaN` for windows with an infinite value
+    pub(crate) fn std(&self) -> Result<Series> {
+        self.compute(|state| match (state.count, state.infinity()) {
+            (0 | 1, _) => None,
+            (_, Some(_)) => Some(f64::NAN),
+            (count, None) => Some((state.m2 / (count - 1) as f64).sqrt()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn floats(values: &[Option<f64>]) -> Series {
+        values.iter().map(|v| v.map(Cell::Float)).collect()
+    }
+
+    fn values(series: Result<Series>) -> Vec<Option<f64>> {
+        let series = series.unwrap();
+        assert_eq!(series.d_type(), DType::Float);
+        (0..series.len()).map(|row| series.f64_at(row)).collect()
+    }
+
+    /// rounds to avoid comparing floating point errors
+    fn rounded(values: Vec<Option<f64>>) -> Vec<Option<f64>> {
+        values
+            .into_iter()
+            .map(|v| v.map(|v| (v * 1e9).round() / 1e9))
+            .collect()
+    }
+
+    #[test]
+    fn test_rolling() {
+        let sales = floats(&[Some(1.0), Some(3.0), None, Some(2.0), Some(6.0), Some(4.0)]);
+        assert_eq!(
+            values(sales.rolling(2).sum()),
+            [None, Some(4.0), None, None, Some(8.0), Some(10.0)]
+        );
+        assert_eq!(
+            values(sales.rolling(3).min_periods(1).mean()),
+            [
+                Some(1.0),
+                Some(2.0),
+                Some(2.0),
+                Some(2.5),
+                Some(4.0),
+                Some(4.0)
+            ]
+        );
+        assert_eq!(
+            values(sales.rolling(3).min_periods(2).min()),
+            [None, Some(1.0), Some(1.0), Some(2.0), Some(2.0), Some(2.0)]
+        );
+        assert_eq!(
+            values(sales.rolling(3).min_periods(2).max()),
+            [None, Some(3.0), Some(3.0), Some(3.0), Some(6.0), Some(6.0)]
+        );
+        assert_eq!(
+            rounded(values(sales.rolling(3).min_periods(1).std())),
+            rounded(vec![
+                None,
+                Some(2f64.sqrt()),
+                Some(2f64.sqrt()),
+                Some(0.5f64.sqrt()),
+                Some(8f64.sqrt()),
+                Some(4f64.sqrt()),
+            ])
+        );
+        assert!(matches!(
+            sales.rolling(0).min_periods(0).sum(),
+            Err(PandasError::EmptyWindow)
+        ));
+        let with_nan = floats(&[Some(1.0), Some(f64::NAN), Some(3.0)]);
+        assert_eq!(
+            values(with_nan.rolling(2).min_periods(1).max()),
+            [Some(1.0), Some(1.0), Some(3.0)]
+        );
+    }
+
+    #[test]
+    fn test_rolling_infinite() {
+        let inf = f64::INFINITY;
+        let series = floats(&[Some(inf), Some(1.0), Some(2.0)]);
+        assert_eq!(
+            values(series.rolling(1).sum()),
+            [Some(inf), Some(1.0), Some(2.0)]
+        );
+        assert_eq!(
+            values(series.rolling(2).mean()),
+            [None, Some(inf), Some(1.5)]
+        );
+        let series = floats(&[Some(inf), Some(-inf), Some(4.0), Some(2.0)]);
+        let sums = values(series.rolling(2).sum());
+        assert!(sums[1].unwrap().is_nan());
+        assert_eq!(sums[2..], [Some(-inf), Some(6.0)]);
+        let deviations = values(series.rolling(2).std());
+        assert!(deviations[2].unwrap().is_nan());
+        assert_eq!(deviations[3], Some(2f64.sqrt()));
+    }
+
+    #[test]
+    fn test_expanding() {
+        let ages: Series = [
+            Some(Cell::Int(20)),
+            None,
+            Some(Cell::Int(22)),
+            Some(Cell::Int(18)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            values(ages.expanding().sum()),
+            [Some(20.0), Some(20.0), Some(42.0), Some(60.0)]
+        );
+        assert_eq!(
+            values(ages.expanding().min_periods(2).mean()),
+            [None, None, Some(21.0), Some(20.0)]
+        );
+        assert_eq!(
+            values(ages.expanding().max()),
+            [Some(20.0), Some(20.0), Some(22.0), Some(22.0)]
+        );
+        assert_eq!(
+            values(ages.expanding().min()),
+            [Some(20.0), Some(20.0), Some(20.0), Some(18.0)]
+        );
+        let names = Series::new(vec!["John".into()]);
+        assert!(matches!(
+            names.expanding().mean(),
+            Err(PandasError::DTypeMismatch { .. })
+        ));
+    }
+}